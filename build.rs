@@ -0,0 +1,44 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Regenerates `include/udhrlen.h` from `src/ffi.rs` with cbindgen when
+//! building with the `ffi` feature.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    println!("cargo:rerun-if-changed=src/ffi.rs");
+    let config = cbindgen::Config::from_file("cbindgen.toml").expect("invalid cbindgen.toml");
+    let mut generated = Vec::new();
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src("src/ffi.rs")
+        .generate()
+        .expect("generating include/udhrlen.h failed")
+        .write(&mut generated);
+    // cbindgen writes negative constants without the parentheses that keep
+    // an expression like `x-UDHRLEN_INTERNAL_ERROR` meaning what it says.
+    let mut header = String::new();
+    for line in String::from_utf8_lossy(&generated).lines() {
+        match line
+            .strip_prefix("#define ")
+            .and_then(|define| define.split_once(" -"))
+        {
+            Some((name, value)) => header.push_str(&format!("#define {} (-{})", name, value)),
+            None => header.push_str(line),
+        }
+        header.push('\n');
+    }
+    // Rewriting an unchanged header would make C builds depending on it
+    // rebuild.
+    if std::fs::read_to_string("include/udhrlen.h").ok().as_deref() != Some(&header[..]) {
+        std::fs::write("include/udhrlen.h", header).expect("writing include/udhrlen.h failed");
+    }
+}
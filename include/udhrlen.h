@@ -0,0 +1,84 @@
+/* This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/. */
+
+/* The C interface of udhrlen, built with the ffi feature. */
+
+#ifndef UDHRLEN_H
+#define UDHRLEN_H
+
+/* Generated from src/ffi.rs by cbindgen. Don't edit by hand. */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+/**
+ * The counts were written.
+ */
+#define UDHRLEN_OK 0
+
+/**
+ * A pointer was null or the text isn't UTF-8.
+ */
+#define UDHRLEN_INVALID_ARGUMENT (-1)
+
+/**
+ * Counting failed unexpectedly.
+ */
+#define UDHRLEN_INTERNAL_ERROR (-2)
+
+/**
+ * The base metrics of a text. See `Metrics` for their definitions.
+ */
+typedef struct UdhrlenCounts {
+  /**
+   * UTF-8 code units
+   */
+  size_t utf8;
+  /**
+   * UTF-16 code units
+   */
+  size_t utf16;
+  /**
+   * Unicode scalar values
+   */
+  size_t utf32;
+  /**
+   * Extended grapheme clusters
+   */
+  size_t graphemes;
+  /**
+   * East Asian Width
+   */
+  size_t width;
+  /**
+   * Words containing a letter or a digit
+   */
+  size_t words;
+  /**
+   * Sentences
+   */
+  size_t sentences;
+} UdhrlenCounts;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Measures the `len` bytes of UTF-8 at `text`, after normalizing them to
+ * NFC if `normalize` is true, and writes the counts to `out`.
+ *
+ * # Safety
+ *
+ * `text` must point to `len` readable bytes, or may be null if `len` is
+ * zero, and `out` must point to a writable `UdhrlenCounts`.
+ */
+int32_t udhrlen_count(const uint8_t *text, size_t len, bool normalize, struct UdhrlenCounts *out);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* UDHRLEN_H */
@@ -0,0 +1,197 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Timing the steps of measuring each translation, which turns the corpus
+//! into a benchmark of Unicode text processing across scripts.
+
+use crate::extract;
+use crate::Corpus;
+use crate::CorpusFormat;
+use crate::CountOptions;
+use crate::IndexEntry;
+use crate::NormalizationForm;
+use crate::UdhrError;
+use std::collections::BTreeMap;
+use std::hint::black_box;
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+use unic_segment::Graphemes;
+use unicode_width::UnicodeWidthStr;
+
+/// A timed step of measuring a translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Step {
+    /// Parsing the file and concatenating the measured text
+    Extract,
+    /// Normalizing the text to NFC
+    Nfc,
+    /// Counting the UTF-16 code units
+    Utf16,
+    /// Counting the extended grapheme clusters
+    Graphemes,
+    /// Computing the width
+    Width,
+}
+
+impl Step {
+    pub const ALL: [Step; 5] = [
+        Step::Extract,
+        Step::Nfc,
+        Step::Utf16,
+        Step::Graphemes,
+        Step::Width,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Extract => "extract",
+            Step::Nfc => "nfc",
+            Step::Utf16 => "utf16",
+            Step::Graphemes => "graphemes",
+            Step::Width => "width",
+        }
+    }
+}
+
+/// The time that the steps take for one translation.
+#[derive(Debug, Clone)]
+pub struct Timing {
+    pub name: String,
+    pub code: String,
+    /// The size of the translation file, which extraction processes
+    pub file_bytes: usize,
+    /// The UTF-8 length of the extracted text, which the other steps
+    /// process
+    pub text_bytes: usize,
+    /// The fastest run of each step
+    pub durations: BTreeMap<Step, Duration>,
+}
+
+impl Timing {
+    /// The sums of the sizes and the durations of `timings`.
+    pub fn total(timings: &[Timing]) -> Timing {
+        let mut total = Timing {
+            name: "Total".to_string(),
+            code: String::new(),
+            file_bytes: 0,
+            text_bytes: 0,
+            durations: BTreeMap::new(),
+        };
+        for timing in timings {
+            total.file_bytes += timing.file_bytes;
+            total.text_bytes += timing.text_bytes;
+            for (&step, &duration) in timing.durations.iter() {
+                *total.durations.entry(step).or_default() += duration;
+            }
+        }
+        total
+    }
+
+    /// The speed of `step` in megabytes (10⁶ bytes) per second of its
+    /// input, if it took a measurable time.
+    pub fn throughput(&self, step: Step) -> Option<f64> {
+        let bytes = match step {
+            Step::Extract => self.file_bytes,
+            _ => self.text_bytes,
+        };
+        let seconds = self.durations.get(&step)?.as_secs_f64();
+        if seconds > 0.0 {
+            Some(bytes as f64 / 1e6 / seconds)
+        } else {
+            None
+        }
+    }
+}
+
+/// The fastest of `iterations` runs of `f`.
+fn fastest<T>(iterations: u32, mut f: impl FnMut() -> T) -> Duration {
+    (0..iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+/// Times each step for each translation of the corpus at `path` selected by
+/// `options`, taking the fastest of `iterations` runs. The translations are
+/// processed one at a time on the current thread so that the timings are
+/// comparable across runs, and the first one that can't be read or parsed
+/// ends the benchmark.
+pub fn run(path: &Path, options: &CountOptions, iterations: u32) -> Result<Vec<Timing>, UdhrError> {
+    let mut corpus = Corpus::open(path)?;
+    let index = corpus.index()?;
+    run_listed(corpus, index, options, iterations)
+}
+
+/// Like `run`, but for the translations listed in `index` instead of the
+/// corpus' own `index.xml`.
+pub fn run_with_index(
+    path: &Path,
+    index: Vec<IndexEntry>,
+    options: &CountOptions,
+    iterations: u32,
+) -> Result<Vec<Timing>, UdhrError> {
+    run_listed(Corpus::open(path)?, index, options, iterations)
+}
+
+fn run_listed(
+    mut corpus: Corpus,
+    index: Vec<IndexEntry>,
+    options: &CountOptions,
+    iterations: u32,
+) -> Result<Vec<Timing>, UdhrError> {
+    let format = corpus.format();
+    let mut timings = Vec::new();
+    for entry in index {
+        if !options.selects(&entry) {
+            continue;
+        }
+        let file_name = entry.file_name_in(format);
+        let file = corpus.display(&file_name);
+        let content = corpus.read(&file_name)?;
+        let content = content.text().map_err(UdhrError::parse(&file))?;
+        let extract = || match format {
+            CorpusFormat::Xml => extract::parse_document(content).map(|chunks| {
+                chunks
+                    .iter()
+                    .filter(|c| options.includes(c))
+                    .map(|c| &c.text[..])
+                    .collect::<String>()
+            }),
+            CorpusFormat::Text => extract::extract_plain_text(content),
+        };
+        let text = extract().map_err(UdhrError::parse(&file))?;
+        let nfc = NormalizationForm::Nfc.normalize(&text);
+
+        let mut durations = BTreeMap::new();
+        durations.insert(Step::Extract, fastest(iterations, extract));
+        durations.insert(
+            Step::Nfc,
+            fastest(iterations, || {
+                NormalizationForm::Nfc.normalize(black_box(&text))
+            }),
+        );
+        durations.insert(
+            Step::Utf16,
+            fastest(iterations, || black_box(&nfc).encode_utf16().count()),
+        );
+        durations.insert(
+            Step::Graphemes,
+            fastest(iterations, || Graphemes::new(black_box(&nfc)).count()),
+        );
+        durations.insert(Step::Width, fastest(iterations, || black_box(&nfc).width()));
+        timings.push(Timing {
+            file_bytes: content.len(),
+            text_bytes: nfc.len(),
+            name: entry.name,
+            code: entry.code,
+            durations,
+        });
+    }
+    Ok(timings)
+}
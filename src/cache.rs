@@ -0,0 +1,224 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The on-disk cache of the metrics of each translation.
+//!
+//! An entry is keyed by the SHA-256 of the file content together with the
+//! index entry, the options that affect the metrics and the build of
+//! udhrlen, so a cached result is reused only when measuring again would
+//! give the same one. Entries that go unused for 30 days are removed.
+//! Failing to read or write the cache only costs time.
+
+use crate::corpus::IndexEntry;
+use crate::CaseMapping;
+use crate::Compression;
+use crate::CountOptions;
+use crate::EncodingScheme;
+use crate::Lang;
+use crate::NormalizationForm;
+use crate::Tokenizer;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+use tracing::debug;
+
+/// How long an entry stays in the cache without being used. Other builds
+/// and options leave behind entries that are never used again.
+const MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The index entry and the options that affect the metrics, which together
+/// with the content and the build make up the cache key.
+#[derive(Serialize)]
+struct Key<'a> {
+    name: &'a str,
+    code: &'a str,
+    script: &'a str,
+    stage: u8,
+    per_article: bool,
+    articles: Option<&'a [RangeInclusive<u32>]>,
+    include_preamble: bool,
+    include_notes: bool,
+    normalizations: &'a [NormalizationForm],
+    case_mappings: &'a [CaseMapping],
+    encodings: &'a [EncodingScheme],
+    legacy: bool,
+    entropy: bool,
+    compressions: &'a [Compression],
+    tokenizers: &'a [Tokenizer],
+    no_normalize: bool,
+    ambiguous_wide: bool,
+    eaw_breakdown: bool,
+    category_breakdown: bool,
+    composition: bool,
+    script_runs: bool,
+    distinct: bool,
+    longest_grapheme: bool,
+    emoji: bool,
+    bidi: bool,
+    blocks: bool,
+    cluster_sizes: bool,
+    icu4x: bool,
+}
+
+impl<'a> Key<'a> {
+    fn new(entry: &'a IndexEntry, options: &'a CountOptions) -> Key<'a> {
+        // Destructured so that a new option has to be sorted into the key or
+        // into the rest, which only select the translations or change how
+        // they are measured but not the metrics.
+        let CountOptions {
+            strict: _,
+            jobs: _,
+            cache: _,
+            stages: _,
+            only: _,
+            exclude: _,
+            scripts: _,
+            per_article,
+            articles,
+            include_preamble,
+            include_notes,
+            normalizations,
+            case_mappings,
+            encodings,
+            legacy,
+            entropy,
+            compressions,
+            tokenizers,
+            no_normalize,
+            ambiguous_wide,
+            eaw_breakdown,
+            category_breakdown,
+            composition,
+            script_runs,
+            distinct,
+            longest_grapheme,
+            emoji,
+            bidi,
+            blocks,
+            cluster_sizes,
+            icu4x,
+        } = options;
+        Key {
+            name: &entry.name,
+            code: &entry.code,
+            script: &entry.script,
+            stage: entry.stage,
+            per_article: *per_article,
+            articles: articles.as_ref().map(|articles| articles.ranges()),
+            include_preamble: *include_preamble,
+            include_notes: *include_notes,
+            normalizations,
+            case_mappings,
+            encodings,
+            legacy: *legacy,
+            entropy: *entropy,
+            compressions,
+            tokenizers,
+            no_normalize: *no_normalize,
+            ambiguous_wide: *ambiguous_wide,
+            eaw_breakdown: *eaw_breakdown,
+            category_breakdown: *category_breakdown,
+            composition: *composition,
+            script_runs: *script_runs,
+            distinct: *distinct,
+            longest_grapheme: *longest_grapheme,
+            emoji: *emoji,
+            bidi: *bidi,
+            blocks: *blocks,
+            cluster_sizes: *cluster_sizes,
+            icu4x: *icu4x,
+        }
+    }
+}
+
+/// The cache key of measuring `content` of `entry` with `options`.
+fn key(content: &str, entry: &IndexEntry, options: &CountOptions) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    // Serializing these plain values can't fail.
+    hasher.update(serde_json::to_vec(&Key::new(entry, options)).unwrap_or_default());
+    hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+    // Rebuilding may change the metrics without changing the version.
+    if let Ok(modified) = std::env::current_exe()
+        .and_then(std::fs::metadata)
+        .and_then(|metadata| metadata.modified())
+    {
+        hasher.update(format!("{:?}", modified).as_bytes());
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Removes the entries of the cache directory `dir` that haven't been used
+/// for `MAX_AGE`, along with the temporary files of interrupted runs.
+pub fn prune(dir: &Path) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+    let now = SystemTime::now();
+    let mut pruned = 0;
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .is_some_and(|age| age > MAX_AGE);
+        if stale
+            && entry.file_type().is_ok_and(|t| t.is_file())
+            && std::fs::remove_file(entry.path()).is_ok()
+        {
+            pruned += 1;
+        }
+    }
+    if pruned > 0 {
+        debug!("pruned {} unused entries from the cache", pruned);
+    }
+}
+
+/// Reads the metrics of `content` of `entry` from the cache directory `dir`
+/// or, if they aren't there, computes them with `measure` and stores them.
+pub fn measure_cached<E>(
+    dir: &Path,
+    content: &str,
+    entry: &IndexEntry,
+    options: &CountOptions,
+    measure: impl FnOnce() -> Result<Lang, E>,
+) -> Result<Lang, E> {
+    let path = dir.join(format!("{}.json", key(content, entry, options)));
+    let cached = std::fs::read(&path)
+        .ok()
+        .and_then(|json| serde_json::from_slice(&json).ok());
+    if let Some(lang) = cached {
+        debug!("reusing the cached metrics of {}", entry.code);
+        // Marks the entry as used for pruning.
+        let _ = std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(lang);
+    }
+    let lang = measure()?;
+    let _ = store(&path, &lang);
+    Ok(lang)
+}
+
+/// Writes `lang` to `path` through a temporary file so that a concurrent run
+/// never reads a partial entry.
+fn store(path: &Path, lang: &Lang) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let temporary = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&temporary, serde_json::to_vec(lang)?)?;
+    std::fs::rename(&temporary, path)
+}
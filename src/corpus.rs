@@ -0,0 +1,281 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Access to the files of the UDHR corpus, either unpacked in a directory or
+//! inside `udhr_xml.zip` or `udhr_txt.zip`.
+
+use crate::normalize;
+use crate::ParseError;
+use crate::UdhrError;
+use memmap2::Mmap;
+use quick_xml::events::Event;
+use std::fs::File;
+use std::io::Read;
+use std::ops::Deref;
+use std::path::Path;
+use std::path::PathBuf;
+use tracing::debug;
+use zip::ZipArchive;
+
+/// A translation listed in `index.xml`.
+#[derive(Debug, Clone)]
+pub struct IndexEntry {
+    pub name: String,
+    pub code: String,
+    pub script: String,
+    pub stage: u8,
+}
+
+impl IndexEntry {
+    /// The file name of the XML translation within the corpus directory.
+    pub fn file_name(&self) -> String {
+        self.file_name_in(CorpusFormat::Xml)
+    }
+
+    /// The file name of the translation within a corpus directory in
+    /// `format`.
+    pub fn file_name_in(&self, format: CorpusFormat) -> String {
+        let mut file_name = String::from("udhr_");
+        file_name.push_str(&self.code);
+        file_name.push_str(match format {
+            CorpusFormat::Xml => ".xml",
+            CorpusFormat::Text => ".txt",
+        });
+        file_name
+    }
+}
+
+/// How the translations of a corpus are distributed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorpusFormat {
+    /// `udhr_<code>.xml` files
+    Xml,
+    /// `udhr_<code>.txt` files with a header
+    Text,
+}
+
+impl CorpusFormat {
+    /// The plain text format if `names` include translations in it but none
+    /// in XML.
+    fn detect<'a>(names: impl Iterator<Item = &'a str>) -> CorpusFormat {
+        let mut text = false;
+        for name in names {
+            let name = name.rsplit('/').next().unwrap_or(name);
+            if name.starts_with("udhr_") {
+                if name.ends_with(".xml") {
+                    return CorpusFormat::Xml;
+                }
+                text |= name.ends_with(".txt");
+            }
+        }
+        if text {
+            CorpusFormat::Text
+        } else {
+            CorpusFormat::Xml
+        }
+    }
+}
+
+/// The bytes of a file of the corpus.
+pub enum Content {
+    /// A file in a directory, mapped into memory so that it's paged in as
+    /// it's parsed instead of being copied into a buffer first
+    Mapped(Mmap),
+    /// A file decompressed from a zip archive
+    Decompressed(Vec<u8>),
+}
+
+impl Content {
+    /// Maps the file at `path` into memory.
+    pub fn map(path: &Path) -> std::io::Result<Content> {
+        let file = File::open(path)?;
+        // SAFETY: The mapping is only read, and the corpus isn't expected to
+        // change while it's being measured.
+        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Content::Mapped(mmap))
+    }
+
+    /// The content as text.
+    pub fn text(&self) -> Result<&str, ParseError> {
+        std::str::from_utf8(self).map_err(|e| ParseError::new(e.valid_up_to(), "invalid UTF-8"))
+    }
+}
+
+impl Deref for Content {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Content::Mapped(mmap) => mmap,
+            Content::Decompressed(bytes) => bytes,
+        }
+    }
+}
+
+enum Source {
+    Dir,
+    Zip(ZipArchive<File>),
+}
+
+/// The files of the corpus.
+pub struct Corpus {
+    path: PathBuf,
+    source: Source,
+    /// The path of the directory containing `index.xml` within a zip
+    /// archive, including the trailing slash.
+    prefix: String,
+    format: CorpusFormat,
+}
+
+impl Corpus {
+    /// Opens the corpus at `path`. A directory is expected to contain
+    /// `index.xml`; anything else is read as a zip archive, in which
+    /// `index.xml` may be at the root or in a subdirectory. The translations
+    /// are read from the plain text files if there are no XML ones.
+    pub fn open(path: &Path) -> Result<Corpus, UdhrError> {
+        if path.is_dir() {
+            let names = std::fs::read_dir(path)
+                .map_err(UdhrError::io(path.display()))?
+                .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+                .collect::<Vec<_>>();
+            return Ok(Corpus {
+                path: path.into(),
+                source: Source::Dir,
+                prefix: String::new(),
+                format: CorpusFormat::detect(names.iter().map(String::as_str)),
+            });
+        }
+        let file = File::open(path).map_err(UdhrError::io(path.display()))?;
+        let archive = ZipArchive::new(file).map_err(UdhrError::zip(path.display()))?;
+        let index = archive
+            .file_names()
+            .filter(|name| *name == "index.xml" || name.ends_with("/index.xml"))
+            .min_by_key(|name| name.len())
+            .ok_or_else(|| UdhrError::NoIndex {
+                file: path.display().to_string(),
+            })?;
+        let prefix = index[..index.len() - "index.xml".len()].to_string();
+        let format = CorpusFormat::detect(
+            archive
+                .file_names()
+                .filter(|name| name.starts_with(&prefix[..])),
+        );
+        Ok(Corpus {
+            path: path.into(),
+            source: Source::Zip(archive),
+            prefix,
+            format,
+        })
+    }
+
+    /// The format of the translation files.
+    pub fn format(&self) -> CorpusFormat {
+        self.format
+    }
+
+    /// How to refer to the file `name` in messages.
+    pub fn display(&self, name: &str) -> String {
+        match self.source {
+            Source::Dir => self.path.join(name).display().to_string(),
+            Source::Zip(_) => format!("{}:{}{}", self.path.display(), self.prefix, name),
+        }
+    }
+
+    /// Reads the file `name` relative to the directory containing
+    /// `index.xml`.
+    pub fn read(&mut self, name: &str) -> Result<Content, UdhrError> {
+        match &mut self.source {
+            Source::Dir => {
+                Content::map(&self.path.join(name)).map_err(UdhrError::io(self.display(name)))
+            }
+            Source::Zip(archive) => {
+                let mut path = self.prefix.clone();
+                path.push_str(name);
+                let display = format!("{}:{}", self.path.display(), path);
+                let mut file = archive.by_name(&path).map_err(UdhrError::zip(&display))?;
+                let mut content = Vec::with_capacity(file.size() as usize);
+                file.read_to_end(&mut content)
+                    .map_err(UdhrError::io(&display))?;
+                Ok(Content::Decompressed(content))
+            }
+        }
+    }
+
+    /// Reads and parses `index.xml`.
+    pub fn index(&mut self) -> Result<Vec<IndexEntry>, UdhrError> {
+        let content = self.read("index.xml")?;
+        content
+            .text()
+            .and_then(parse_index)
+            .map_err(UdhrError::parse(self.display("index.xml")))
+    }
+}
+
+/// Parses the text of `index.xml`.
+pub fn parse_index(index_text: &str) -> Result<Vec<IndexEntry>, ParseError> {
+    let mut entries = Vec::new();
+    let mut buf = Vec::new();
+    let mut index = quick_xml::Reader::from_str(index_text);
+    loop {
+        let position = index.buffer_position();
+        match index.read_event(&mut buf) {
+            Ok(Event::Empty(ref e)) if e.name() == b"udhr" => {
+                let mut name = String::new();
+                let mut code = String::new();
+                let mut script = String::new();
+                let mut stage = 0u8;
+                for attr in e.attributes() {
+                    let a = attr.map_err(|e| ParseError::new(position, e))?;
+                    let value = || {
+                        a.unescape_and_decode_value(&index)
+                            .map_err(|e| ParseError::new(position, e))
+                    };
+                    match a.key {
+                        b"stage" => {
+                            let v = value()?;
+                            if v.len() == 1 && v.as_bytes()[0].is_ascii_digit() {
+                                stage = v.as_bytes()[0] - b'0';
+                            }
+                        }
+                        b"f" => {
+                            code = value()?;
+                        }
+                        b"n" => {
+                            name = normalize(&value()?);
+                        }
+                        b"iso15924" => {
+                            script = value()?;
+                        }
+                        _ => {}
+                    }
+                }
+                let missing = match (code.is_empty(), name.is_empty()) {
+                    (true, _) => Some("<udhr> without f attribute"),
+                    (false, true) => Some("<udhr> without n attribute"),
+                    (false, false) => None,
+                };
+                if let Some(message) = missing {
+                    // The translations measured by default have to be
+                    // complete, but the index may list drafts that aren't.
+                    if stage == 4 || stage == 5 {
+                        return Err(ParseError::new(position, message));
+                    }
+                    debug!("skipping the index entry at {}: {}", position, message);
+                } else {
+                    entries.push(IndexEntry {
+                        name,
+                        code,
+                        script,
+                        stage,
+                    });
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(ParseError::new(index.buffer_position(), e)),
+        }
+        buf.clear();
+    }
+    Ok(entries)
+}
@@ -0,0 +1,16 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Encoding the text in schemes other than the UTFs and escaping it for
+//! markup.
+
+pub mod base64;
+pub mod bocu1;
+pub mod cesu8;
+pub mod json;
+pub mod legacy;
+pub mod markup;
+pub mod mime;
+pub mod scsu;
+pub mod utf7;
@@ -0,0 +1,40 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Base64 as specified in [RFC 4648](https://www.rfc-editor.org/rfc/rfc4648)
+//! with padding and without line breaks.
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Appends the base64 encoding of `bytes` to `out`.
+pub fn push(out: &mut Vec<u8>, bytes: &[u8]) {
+    for chunk in bytes.chunks(3) {
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, &b)| bits | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[((bits >> (18 - 6 * i)) & 0x3F) as usize]);
+            } else {
+                out.push(b'=');
+            }
+        }
+    }
+}
+
+/// Encodes `text` as UTF-8 in base64.
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len().div_ceil(3) * 4);
+    push(&mut out, text.as_bytes());
+    out
+}
+
+/// Encodes `text` as UTF-16LE in base64.
+pub fn encode_utf16(text: &str) -> Vec<u8> {
+    let bytes: Vec<u8> = text.encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let mut out = Vec::with_capacity(bytes.len().div_ceil(3) * 4);
+    push(&mut out, &bytes);
+    out
+}
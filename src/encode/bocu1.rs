@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The Binary Ordered Compression for Unicode as specified in
+//! [UTN #6](https://www.unicode.org/notes/tn6/).
+//!
+//! BOCU-1 encodes the difference of each code point from the middle of the
+//! block of the previous one, so text in a small script takes about a byte
+//! per character, and unlike SCSU the encoding is deterministic.
+
+const ASCII_PREV: i32 = 0x40;
+
+const MIDDLE: i32 = 0x90;
+const LEAD_2: i32 = 43;
+const LEAD_3: i32 = 3;
+
+/// The byte values for the trail values below 20. The C0 controls other
+/// than these are not used in trail bytes so that they stay recognizable.
+const TRAIL_TO_BYTE: [u8; 20] = [
+    0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19,
+    0x1C, 0x1D, 0x1E, 0x1F,
+];
+const TRAIL_BYTE_OFFSET: i32 = 0x21 - TRAIL_TO_BYTE.len() as i32;
+const TRAIL_COUNT: i32 = (0xFF - 0x21 + 1) + TRAIL_TO_BYTE.len() as i32;
+
+const REACH_POS_1: i32 = 63;
+const REACH_NEG_1: i32 = -64;
+const REACH_POS_2: i32 = REACH_POS_1 + LEAD_2 * TRAIL_COUNT;
+const REACH_NEG_2: i32 = REACH_NEG_1 - LEAD_2 * TRAIL_COUNT;
+const REACH_POS_3: i32 = REACH_POS_2 + LEAD_3 * TRAIL_COUNT * TRAIL_COUNT;
+const REACH_NEG_3: i32 = REACH_NEG_2 - LEAD_3 * TRAIL_COUNT * TRAIL_COUNT;
+
+const START_POS_2: i32 = MIDDLE + REACH_POS_1 + 1;
+const START_POS_3: i32 = START_POS_2 + LEAD_2;
+const START_POS_4: i32 = START_POS_3 + LEAD_3;
+const START_NEG_2: i32 = MIDDLE + REACH_NEG_1;
+const START_NEG_3: i32 = START_NEG_2 - LEAD_2;
+const START_NEG_4: i32 = START_NEG_3 - LEAD_3;
+
+fn trail_byte(trail: i32) -> u8 {
+    match TRAIL_TO_BYTE.get(trail as usize) {
+        Some(&byte) => byte,
+        None => (trail + TRAIL_BYTE_OFFSET) as u8,
+    }
+}
+
+/// The state to encode the code point after `c` relative to.
+fn prev_for(c: i32) -> i32 {
+    match c {
+        // Hiragana
+        0x3040..=0x309F => 0x3070,
+        // CJK Unified Ideographs
+        0x4E00..=0x9FA5 => 0x4E00 - REACH_NEG_2,
+        // Hangul Syllables
+        0xAC00..=0xD7A3 => (0xD7A3 + 0xAC00) / 2,
+        _ => (c & !0x7F) + ASCII_PREV,
+    }
+}
+
+/// Appends the bytes for the code point difference `diff` to `out`.
+fn push_diff(out: &mut Vec<u8>, diff: i32) {
+    let (mut diff, lead, trail_count) = if diff >= REACH_NEG_1 {
+        if diff <= REACH_POS_1 {
+            out.push((MIDDLE + diff) as u8);
+            return;
+        } else if diff <= REACH_POS_2 {
+            (diff - (REACH_POS_1 + 1), START_POS_2, 1)
+        } else if diff <= REACH_POS_3 {
+            (diff - (REACH_POS_2 + 1), START_POS_3, 2)
+        } else {
+            (diff - (REACH_POS_3 + 1), START_POS_4, 3)
+        }
+    } else if diff >= REACH_NEG_2 {
+        (diff - REACH_NEG_1, START_NEG_2, 1)
+    } else if diff >= REACH_NEG_3 {
+        (diff - REACH_NEG_2, START_NEG_3, 2)
+    } else {
+        (diff - REACH_NEG_3, START_NEG_4, 3)
+    };
+    let mut trails = [0u8; 3];
+    for trail in trails[..trail_count].iter_mut().rev() {
+        *trail = trail_byte(diff.rem_euclid(TRAIL_COUNT));
+        diff = diff.div_euclid(TRAIL_COUNT);
+    }
+    out.push((lead + diff) as u8);
+    out.extend_from_slice(&trails[..trail_count]);
+}
+
+/// Encodes `text` in BOCU-1.
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut prev = ASCII_PREV;
+    for c in text.chars() {
+        let c = c as i32;
+        if c <= 0x20 {
+            // The C0 controls and the space are encoded as themselves. The
+            // controls other than the space reset the state.
+            if c != 0x20 {
+                prev = ASCII_PREV;
+            }
+            out.push(c as u8);
+            continue;
+        }
+        push_diff(&mut out, c - prev);
+        prev = prev_for(c);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+
+    /// Checks the encoding of `text` against `icu`, the output of ICU's
+    /// BOCU-1 converter, which is the reference implementation of UTN #6.
+    fn check(text: &str, icu: &[u8]) {
+        assert_eq!(encode(text), icu, "{:?}", text);
+    }
+
+    #[test]
+    fn small_scripts() {
+        check(
+            "Öl fließt",
+            &[
+                0xD0, 0x63, 0x4F, 0xEC, 0x20, 0xB6, 0xBC, 0xB9, 0xB5, 0xD0, 0x6C, 0x4F, 0xF4,
+            ],
+        );
+        check("Москва", &[0xD3, 0xD0, 0x8E, 0x91, 0x8A, 0x82, 0x80]);
+        check(
+            "Ελληνικά και English",
+            &[
+                0xD3, 0x49, 0x8B, 0x8B, 0x87, 0x8D, 0x89, 0x8A, 0x7C, 0x20, 0x8A, 0x81, 0x89, 0x20,
+                0x4C, 0x9E, 0xBE, 0xB7, 0xBC, 0xB9, 0xC3, 0xB8,
+            ],
+        );
+    }
+
+    #[test]
+    fn cjk_and_hangul() {
+        check(
+            "日本語のテキスト",
+            &[
+                0xFB, 0x4C, 0xD4, 0x3F, 0x8B, 0xE4, 0x5E, 0x24, 0xE0, 0xDB, 0xD0, 0x23, 0x7D, 0x89,
+                0x98,
+            ],
+        );
+        check(
+            "대한민국",
+            &[0xFB, 0x9E, 0x16, 0xE4, 0x5C, 0x4A, 0x2A, 0x3A, 0xCB],
+        );
+        check(
+            "中文\u{E001}中文",
+            &[
+                0xFB, 0x33, 0xD7, 0x3D, 0xCC, 0xFB, 0x50, 0x53, 0x24, 0x91, 0x68, 0x3D, 0xCC,
+            ],
+        );
+    }
+
+    #[test]
+    fn astral() {
+        check(
+            "\u{1D518}\u{1D52B}\u{1D526}\u{1D520}\u{1D52C}\u{1D521}\u{1D522}",
+            &[0xFC, 0xDC, 0xAE, 0x7B, 0x76, 0x70, 0x7C, 0x71, 0x72],
+        );
+        check(
+            "a\u{1F600}b\u{1F600}",
+            &[0xB1, 0xFC, 0xFF, 0x5D, 0x23, 0x01, 0x92, 0xFC, 0xFF, 0x5D],
+        );
+        check(
+            "\u{1D518}a\u{10FFFF} \u{10000}\u{A}\u{10FFFF}",
+            &[
+                0xFC, 0xDC, 0xAE, 0x23, 0x30, 0x58, 0xFE, 0x19, 0xB4, 0x54, 0x20, 0x21, 0xF1, 0x73,
+                0x6E, 0x0A, 0xFE, 0x19, 0xB4, 0x54,
+            ],
+        );
+    }
+
+    #[test]
+    fn controls() {
+        check("a\0b", &[0xB1, 0x00, 0xB2]);
+        check(
+            "中文\0中文",
+            &[
+                0xFB, 0x33, 0xD7, 0x3D, 0xCC, 0x00, 0xFB, 0x33, 0xD7, 0x3D, 0xCC,
+            ],
+        );
+        check("\u{1}\u{7}x\u{1B}", &[0x01, 0x07, 0xC8, 0x1B]);
+        check("\u{FEFF}a", &[0xFB, 0xEE, 0x28, 0x24, 0x1E, 0x52]);
+        check("", &[]);
+    }
+}
@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! CESU-8 as specified in [UTR #26](https://www.unicode.org/reports/tr26/)
+//! and Java's Modified UTF-8, which additionally encodes U+0000 in two
+//! bytes.
+
+fn encode_impl(text: &str, modified: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut units = [0u16; 2];
+    for c in text.chars() {
+        if c == '\0' && modified {
+            out.extend_from_slice(&[0xC0, 0x80]);
+        } else if c.len_utf16() == 1 {
+            let mut buf = [0u8; 4];
+            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+        } else {
+            // Each surrogate in the three-byte form of UTF-8
+            for &unit in c.encode_utf16(&mut units).iter() {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    out
+}
+
+/// Encodes `text` in CESU-8.
+pub fn encode(text: &str) -> Vec<u8> {
+    encode_impl(text, false)
+}
+
+/// Encodes `text` in Modified UTF-8.
+pub fn encode_modified(text: &str) -> Vec<u8> {
+    encode_impl(text, true)
+}
@@ -0,0 +1,45 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The text as a JSON string literal as specified in
+//! [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259), either as UTF-8 or
+//! ASCII-safe with every non-ASCII character escaped as `\uXXXX`.
+
+use std::fmt::Write;
+
+fn escape_impl(text: &str, ascii: bool) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    let mut units = [0u16; 2];
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{C}' => out.push_str("\\f"),
+            _ if c < ' ' || (ascii && !c.is_ascii()) => {
+                // Astral characters as surrogate pairs
+                for &unit in c.encode_utf16(&mut units).iter() {
+                    write!(out, "\\u{:04x}", unit).expect("write to string");
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Serializes `text` as a JSON string with only the mandatory escapes.
+pub fn escape(text: &str) -> String {
+    escape_impl(text, false)
+}
+
+/// Serializes `text` as a JSON string that is ASCII.
+pub fn escape_ascii(text: &str) -> String {
+    escape_impl(text, true)
+}
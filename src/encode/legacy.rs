@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The pre-Unicode encodings that the translations would most likely have
+//! been written in.
+
+use crate::metrics::LegacyLength;
+use crate::IndexEntry;
+use encoding_rs::EncoderResult;
+use encoding_rs::Encoding;
+use unic_normal::StrNormalForm;
+
+/// The combining marks that windows-1258 uses for Vietnamese tones
+const VIETNAMESE_TONE_MARKS: [char; 5] = ['\u{300}', '\u{301}', '\u{303}', '\u{309}', '\u{323}'];
+
+/// The legacy encoding for the language and script of `entry`, if
+/// encoding_rs supports one.
+pub fn encoding_for(entry: &IndexEntry) -> Option<&'static Encoding> {
+    let language = entry.code.split('_').next().unwrap_or("");
+    let encoding = match &entry.script[..] {
+        "Jpan" | "Hira" | "Kana" => encoding_rs::SHIFT_JIS,
+        "Kore" | "Hang" => encoding_rs::EUC_KR,
+        "Hans" => encoding_rs::GBK,
+        "Hant" => encoding_rs::BIG5,
+        "Cyrl" => encoding_rs::WINDOWS_1251,
+        "Grek" => encoding_rs::WINDOWS_1253,
+        "Hebr" => encoding_rs::WINDOWS_1255,
+        "Arab" => encoding_rs::WINDOWS_1256,
+        "Thai" => encoding_rs::WINDOWS_874,
+        "Latn" => match language {
+            "vie" => encoding_rs::WINDOWS_1258,
+            "tur" | "aze" | "tuk" | "uzn" => encoding_rs::WINDOWS_1254,
+            "est" | "lav" | "lit" => encoding_rs::WINDOWS_1257,
+            "bos" | "ces" | "hrv" | "hsb" | "dsb" | "hun" | "pol" | "slk" | "slv" | "srp" => {
+                encoding_rs::WINDOWS_1250
+            }
+            "ron" => encoding_rs::ISO_8859_16,
+            "epo" | "mlt" => encoding_rs::ISO_8859_3,
+            "sme" | "smj" | "sma" => encoding_rs::ISO_8859_10,
+            "cym" | "gla" | "gle" => encoding_rs::ISO_8859_14,
+            _ => encoding_rs::WINDOWS_1252,
+        },
+        _ => return None,
+    };
+    Some(encoding)
+}
+
+/// Splits the tone marks off Vietnamese characters that windows-1258 has no
+/// precomposed form for, which is how Vietnamese was written in it.
+fn split_tone_marks(text: &str, encoding: &'static Encoding) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut buf = [0u8; 4];
+    for c in text.chars() {
+        let (_, _, unmappable) = encoding.encode(c.encode_utf8(&mut buf));
+        if !unmappable {
+            out.push(c);
+            continue;
+        }
+        let mut decomposed: Vec<char> = c.encode_utf8(&mut buf).nfd().collect();
+        match decomposed
+            .iter()
+            .position(|m| VIETNAMESE_TONE_MARKS.contains(m))
+        {
+            Some(tone) if decomposed.len() > 1 => {
+                let mark = decomposed.remove(tone);
+                out.extend(decomposed.into_iter().collect::<String>().nfc());
+                out.push(mark);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Measures `text` in `encoding`. Unmappable characters are counted but
+/// contribute no bytes.
+pub fn measure(text: &str, encoding: &'static Encoding) -> LegacyLength {
+    let split;
+    let mut input = text;
+    if encoding == encoding_rs::WINDOWS_1258 {
+        split = split_tone_marks(text, encoding);
+        input = &split;
+    }
+    let mut encoder = encoding.new_encoder();
+    let mut buf = [0u8; 1024];
+    let mut length = LegacyLength {
+        encoding: encoding.name(),
+        bytes: 0,
+        unmappable: 0,
+    };
+    loop {
+        let (result, read, written) =
+            encoder.encode_from_utf8_without_replacement(input, &mut buf, true);
+        input = &input[read..];
+        length.bytes += written;
+        match result {
+            EncoderResult::InputEmpty => break,
+            EncoderResult::OutputFull => {}
+            EncoderResult::Unmappable(_) => length.unmappable += 1,
+        }
+    }
+    length
+}
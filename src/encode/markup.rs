@@ -0,0 +1,45 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Text escaped for inclusion in HTML or XML content and attribute values.
+
+/// The escape for a character that is markup syntax.
+fn markup_escape(c: char) -> Option<&'static str> {
+    match c {
+        '&' => Some("&amp;"),
+        '<' => Some("&lt;"),
+        '>' => Some("&gt;"),
+        '"' => Some("&quot;"),
+        '\'' => Some("&#39;"),
+        _ => None,
+    }
+}
+
+/// Escapes the markup syntax characters in `text`, leaving the rest as
+/// UTF-8.
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match markup_escape(c) {
+            Some(escape) => out.push_str(escape),
+            None => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escapes the markup syntax characters in `text` and writes every
+/// non-ASCII character as a hexadecimal numeric character reference,
+/// resulting in ASCII.
+pub fn escape_ncr(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match markup_escape(c) {
+            Some(escape) => out.push_str(escape),
+            None if c.is_ascii() => out.push(c),
+            None => out.push_str(&format!("&#x{:X};", u32::from(c))),
+        }
+    }
+    out
+}
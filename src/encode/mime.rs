@@ -0,0 +1,246 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The UTF-8 text in the transfer encodings of email: quoted-printable as
+//! specified in [RFC 2045](https://www.rfc-editor.org/rfc/rfc2045) for
+//! bodies and the B and Q encoded-words of
+//! [RFC 2047](https://www.rfc-editor.org/rfc/rfc2047) for headers.
+
+use crate::encode::base64;
+
+const HEX: &[u8; 16] = b"0123456789ABCDEF";
+
+/// The maximum length of an encoded line excluding the CRLF.
+const MAX_LINE: usize = 76;
+
+/// The maximum length of an encoded-word.
+const MAX_WORD: usize = 75;
+
+/// The length of `=?UTF-8?B?` and `?=`.
+const WORD_OVERHEAD: usize = 12;
+
+fn push_hex(out: &mut Vec<u8>, byte: u8) {
+    out.push(b'=');
+    out.push(HEX[usize::from(byte >> 4)]);
+    out.push(HEX[usize::from(byte & 0xF)]);
+}
+
+/// Encodes `text` as a quoted-printable body with CRLF line breaks.
+pub fn quoted_printable(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut lines = text.split('\n').peekable();
+    while let Some(line) = lines.next() {
+        // A CR is part of the line break only before an LF and encoded
+        // elsewhere.
+        let bytes = match lines.peek() {
+            Some(_) => line.strip_suffix('\r').unwrap_or(line).as_bytes(),
+            None => line.as_bytes(),
+        };
+        let mut len = 0;
+        for (j, &byte) in bytes.iter().enumerate() {
+            let last = j + 1 == bytes.len();
+            // Whitespace at the end of a line has to be encoded
+            let literal = (byte.is_ascii_graphic() && byte != b'=')
+                || (matches!(byte, b' ' | b'\t') && !last);
+            let width = if literal { 1 } else { 3 };
+            // Leave room for the `=` of a soft line break except after the
+            // last byte.
+            let limit = if last { MAX_LINE } else { MAX_LINE - 1 };
+            if len + width > limit {
+                out.extend_from_slice(b"=\r\n");
+                len = 0;
+            }
+            if literal {
+                out.push(byte);
+            } else {
+                push_hex(&mut out, byte);
+            }
+            len += width;
+        }
+        if lines.peek().is_some() {
+            out.extend_from_slice(b"\r\n");
+        }
+    }
+    out
+}
+
+/// RFC 2047 encoded-word encodings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WordEncoding {
+    B,
+    Q,
+}
+
+impl WordEncoding {
+    fn q_is_literal(byte: u8) -> bool {
+        byte.is_ascii_graphic() && !matches!(byte, b'=' | b'?' | b'_')
+    }
+
+    fn encoded_len(self, bytes: &[u8]) -> usize {
+        match self {
+            WordEncoding::B => bytes.len().div_ceil(3) * 4,
+            WordEncoding::Q => bytes
+                .iter()
+                .map(|&b| {
+                    if b == b' ' || WordEncoding::q_is_literal(b) {
+                        1
+                    } else {
+                        3
+                    }
+                })
+                .sum(),
+        }
+    }
+
+    fn push_word(self, out: &mut Vec<u8>, bytes: &[u8]) {
+        if !out.is_empty() {
+            // Folding whitespace between the words
+            out.extend_from_slice(b"\r\n ");
+        }
+        match self {
+            WordEncoding::B => {
+                out.extend_from_slice(b"=?UTF-8?B?");
+                base64::push(out, bytes);
+            }
+            WordEncoding::Q => {
+                out.extend_from_slice(b"=?UTF-8?Q?");
+                for &byte in bytes {
+                    if byte == b' ' {
+                        out.push(b'_');
+                    } else if WordEncoding::q_is_literal(byte) {
+                        out.push(byte);
+                    } else {
+                        push_hex(out, byte);
+                    }
+                }
+            }
+        }
+        out.extend_from_slice(b"?=");
+    }
+}
+
+/// Encodes `text` as a header value of encoded-words that don't split
+/// characters, separated by folding whitespace.
+fn encoded_words(text: &str, encoding: WordEncoding) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut word = Vec::new();
+    let mut buf = [0u8; 4];
+    for c in text.chars() {
+        let bytes = c.encode_utf8(&mut buf).as_bytes();
+        word.extend_from_slice(bytes);
+        if encoding.encoded_len(&word) > MAX_WORD - WORD_OVERHEAD {
+            word.truncate(word.len() - bytes.len());
+            encoding.push_word(&mut out, &word);
+            word.clear();
+            word.extend_from_slice(bytes);
+        }
+    }
+    if !word.is_empty() {
+        encoding.push_word(&mut out, &word);
+    }
+    out
+}
+
+/// Encodes `text` as B encoded-words.
+pub fn encoded_words_b(text: &str) -> Vec<u8> {
+    encoded_words(text, WordEncoding::B)
+}
+
+/// Encodes `text` as Q encoded-words.
+pub fn encoded_words_q(text: &str) -> Vec<u8> {
+    encoded_words(text, WordEncoding::Q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The expected encodings are those of Python's `quopri` module with
+    /// CRLF line breaks.
+    #[test]
+    fn quoted_printable_encoding() {
+        assert_eq!(quoted_printable(""), b"");
+        assert_eq!(
+            quoted_printable("Keld J\u{F8}rn Simonsen"),
+            b"Keld J=C3=B8rn Simonsen"
+        );
+        assert_eq!(quoted_printable("a=b"), b"a=3Db");
+        assert_eq!(quoted_printable("a\0b"), b"a=00b");
+        assert_eq!(quoted_printable("\u{1F600}"), b"=F0=9F=98=80");
+        // Whitespace at the end of a line
+        assert_eq!(quoted_printable("a \nb"), b"a=20\r\nb");
+        assert_eq!(quoted_printable("a\t"), b"a=09");
+        // CRLF is a line break, but a lone CR isn't.
+        assert_eq!(quoted_printable("a\r\nb"), b"a\r\nb");
+        assert_eq!(quoted_printable("a\rb\r"), b"a=0Db=0D");
+    }
+
+    #[test]
+    fn quoted_printable_line_length() {
+        let a = |n| "a".repeat(n);
+        assert_eq!(quoted_printable(&a(76)), a(76).as_bytes());
+        assert_eq!(
+            quoted_printable(&a(77)),
+            format!("{}=\r\naa", a(75)).as_bytes()
+        );
+        assert_eq!(
+            quoted_printable(&format!("{} b", a(74))),
+            format!("{} b", a(74)).as_bytes()
+        );
+        assert_eq!(
+            quoted_printable(&format!("{} ", a(75))),
+            format!("{}=\r\n=20", a(75)).as_bytes()
+        );
+        // An escape isn't split by a soft line break.
+        assert_eq!(
+            quoted_printable(&format!("{}=", a(75))),
+            format!("{}=\r\n=3D", a(75)).as_bytes()
+        );
+        assert_eq!(
+            quoted_printable(&format!("{}\u{E4}", a(73))),
+            format!("{}=\r\n=C3=A4", a(73)).as_bytes()
+        );
+        assert_eq!(
+            quoted_printable(&format!("{}\u{E4}", a(72))),
+            format!("{}=C3=\r\n=A4", a(72)).as_bytes()
+        );
+    }
+
+    /// The example of RFC 2047 in UTF-8 instead of ISO-8859-1.
+    #[test]
+    fn encoded_words() {
+        assert_eq!(
+            encoded_words_q("Keld J\u{F8}rn Simonsen"),
+            b"=?UTF-8?Q?Keld_J=C3=B8rn_Simonsen?="
+        );
+        assert_eq!(
+            encoded_words_b("Keld J\u{F8}rn Simonsen"),
+            b"=?UTF-8?B?S2VsZCBKw7hybiBTaW1vbnNlbg==?="
+        );
+        assert_eq!(encoded_words_q("a?_=\0"), b"=?UTF-8?Q?a=3F=5F=3D=00?=");
+        assert_eq!(encoded_words_b("a\0b"), b"=?UTF-8?B?YQBi?=");
+        assert_eq!(encoded_words_b("\u{1F600}"), b"=?UTF-8?B?8J+YgA==?=");
+        assert_eq!(encoded_words_q(""), b"");
+    }
+
+    /// The words are at most 75 characters long and don't split characters.
+    #[test]
+    fn encoded_word_length() {
+        let text = "\u{E4}".repeat(30);
+        assert_eq!(
+            encoded_words_b(&text),
+            &b"=?UTF-8?B?w6TDpMOkw6TDpMOkw6TDpMOkw6TDpMOkw6TDpMOkw6TDpMOkw6TDpMOkw6Q=?=\r\n =?UTF-8?B?w6TDpMOkw6TDpMOkw6TDpA==?="[..]
+        );
+        let word = format!("=?UTF-8?Q?{}?=", "=C3=A4".repeat(10));
+        assert_eq!(
+            encoded_words_q(&text),
+            format!("{}\r\n {}\r\n {}", word, word, word).as_bytes()
+        );
+        let text = "\u{1F600}".repeat(16);
+        assert_eq!(
+            encoded_words_b(&text),
+            &b"=?UTF-8?B?8J+YgPCfmIDwn5iA8J+YgPCfmIDwn5iA8J+YgPCfmIDwn5iA8J+YgPCfmIA=?=\r\n =?UTF-8?B?8J+YgPCfmIDwn5iA8J+YgPCfmIA=?="[..]
+        );
+    }
+}
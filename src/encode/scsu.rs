@@ -0,0 +1,340 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The Standard Compression Scheme for Unicode as specified in
+//! [UTS #6](https://www.unicode.org/reports/tr6/).
+//!
+//! The encoder is greedy with one character of lookahead, which is roughly
+//! what the sample encoder in UTS #6 does. A cleverer encoder could produce
+//! slightly shorter output, but the scheme is designed so that the obvious
+//! choices are usually the right ones.
+
+// Tags in single-byte mode
+const SQ0: u8 = 0x01;
+const SDX: u8 = 0x0B;
+const SQU: u8 = 0x0E;
+const SCU: u8 = 0x0F;
+const SC0: u8 = 0x10;
+const SD0: u8 = 0x18;
+
+// Tags in Unicode mode
+const UC0: u8 = 0xE0;
+const UD0: u8 = 0xE8;
+const UQU: u8 = 0xF0;
+const UDX: u8 = 0xF1;
+
+const STATIC_WINDOWS: [u32; 8] = [
+    0x0000, 0x0080, 0x0100, 0x0300, 0x2000, 0x2080, 0x2100, 0x3000,
+];
+
+const DEFAULT_WINDOWS: [u32; 8] = [
+    0x0080, 0x00C0, 0x0400, 0x0600, 0x0900, 0x3040, 0x30A0, 0xFF00,
+];
+
+/// The window offsets that have window offset bytes of their own
+const FIXED_OFFSETS: [(u8, u32); 7] = [
+    (0xF9, 0x00C0),
+    (0xFA, 0x0250),
+    (0xFB, 0x0370),
+    (0xFC, 0x0530),
+    (0xFD, 0x3040),
+    (0xFE, 0x30A0),
+    (0xFF, 0xFF60),
+];
+
+/// Whether `c` is represented by its own value in single-byte mode.
+fn is_plain(c: u32) -> bool {
+    matches!(c, 0x00 | 0x09 | 0x0A | 0x0D | 0x20..=0x7F)
+}
+
+fn in_window(c: u32, offset: u32) -> bool {
+    c >= offset && c < offset + 0x80
+}
+
+/// A dynamic window position that contains a given character.
+#[derive(Clone, Copy)]
+enum Window {
+    /// A window offset byte and the offset it stands for
+    Bmp(u8, u32),
+    /// The 13-bit offset value of `SDX` and the offset it stands for
+    Supplementary(u16, u32),
+}
+
+/// Where to put a dynamic window for `c`. `None` for ASCII and for the
+/// characters between U+3400 and U+DFFF, which can't be windowed and are
+/// best encoded in Unicode mode.
+fn window_for(c: u32) -> Option<Window> {
+    for &(byte, offset) in FIXED_OFFSETS.iter() {
+        if in_window(c, offset) {
+            return Some(Window::Bmp(byte, offset));
+        }
+    }
+    match c {
+        0x0080..=0x33FF => Some(Window::Bmp((c >> 7) as u8, c & !0x7F)),
+        0xE000..=0xFFFF => Some(Window::Bmp(((c - 0xAC00) >> 7) as u8, c & !0x7F)),
+        0x10000.. => Some(Window::Supplementary(
+            ((c - 0x10000) >> 7) as u16,
+            c & !0x7F,
+        )),
+        _ => None,
+    }
+}
+
+/// Whether `c` can only be represented in UTF-16 code units.
+fn needs_unicode_mode(c: u32) -> bool {
+    c >= 0x80 && window_for(c).is_none()
+}
+
+struct Encoder {
+    out: Vec<u8>,
+    windows: [u32; 8],
+    active: usize,
+    unicode_mode: bool,
+    /// The dynamic window to redefine next
+    next_window: usize,
+}
+
+impl Encoder {
+    fn dynamic_window(&self, c: u32) -> Option<usize> {
+        self.windows.iter().position(|&offset| in_window(c, offset))
+    }
+
+    fn push_utf16(&mut self, c: u32) {
+        let mut units = [0u16; 2];
+        let c = char::from_u32(c).expect("encoding a char");
+        for &unit in c.encode_utf16(&mut units).iter() {
+            let high = (unit >> 8) as u8;
+            if self.unicode_mode && (UC0..=0xF2).contains(&high) {
+                self.out.push(UQU);
+            }
+            self.out.extend_from_slice(&unit.to_be_bytes());
+        }
+    }
+
+    /// Positions the least recently redefined window other than the
+    /// active one at `window`, makes it active and switches to single-byte
+    /// mode.
+    fn define(&mut self, window: Window) {
+        if self.next_window == self.active {
+            self.next_window = (self.next_window + 1) % 8;
+        }
+        let n = self.next_window;
+        self.next_window = (n + 1) % 8;
+        let (define, define_extended) = if self.unicode_mode {
+            (UD0, UDX)
+        } else {
+            (SD0, SDX)
+        };
+        match window {
+            Window::Bmp(byte, offset) => {
+                self.out.push(define + n as u8);
+                self.out.push(byte);
+                self.windows[n] = offset;
+            }
+            Window::Supplementary(value, offset) => {
+                self.out.push(define_extended);
+                self.out
+                    .extend_from_slice(&(((n as u16) << 13) | value).to_be_bytes());
+                self.windows[n] = offset;
+            }
+        }
+        self.active = n;
+        self.unicode_mode = false;
+    }
+
+    fn single_byte(&mut self, c: u32, next: Option<u32>) {
+        if is_plain(c) {
+            self.out.push(c as u8);
+            return;
+        }
+        if c < 0x20 {
+            self.out.extend_from_slice(&[SQ0, c as u8]);
+            return;
+        }
+        let offset = self.windows[self.active];
+        if in_window(c, offset) {
+            self.out.push((0x80 + c - offset) as u8);
+            return;
+        }
+        if let Some(n) = self.dynamic_window(c) {
+            let offset = self.windows[n];
+            if next.is_some_and(|next| in_window(next, offset)) {
+                self.out.push(SC0 + n as u8);
+                self.active = n;
+            } else {
+                self.out.push(SQ0 + n as u8);
+            }
+            self.out.push((0x80 + c - offset) as u8);
+            return;
+        }
+        if let Some(n) = STATIC_WINDOWS.iter().position(|&o| in_window(c, o)) {
+            self.out
+                .extend_from_slice(&[SQ0 + n as u8, (c - STATIC_WINDOWS[n]) as u8]);
+            return;
+        }
+        match window_for(c) {
+            Some(window) => {
+                self.define(window);
+                self.out.push((0x80 + c - self.windows[self.active]) as u8);
+            }
+            None if next.is_some_and(needs_unicode_mode) => {
+                self.out.push(SCU);
+                self.unicode_mode = true;
+                self.push_utf16(c);
+            }
+            None => {
+                self.out.push(SQU);
+                self.push_utf16(c);
+            }
+        }
+    }
+
+    fn unicode(&mut self, c: u32, next: Option<u32>) {
+        if needs_unicode_mode(c) || next.is_some_and(needs_unicode_mode) {
+            self.push_utf16(c);
+            return;
+        }
+        if let Some(n) = self.dynamic_window(c) {
+            self.out.push(UC0 + n as u8);
+            self.active = n;
+            self.unicode_mode = false;
+            self.out.push((0x80 + c - self.windows[n]) as u8);
+            return;
+        }
+        if c >= 0x80 {
+            if let Some(window) = window_for(c) {
+                self.define(window);
+                self.out.push((0x80 + c - self.windows[self.active]) as u8);
+                return;
+            }
+        }
+        self.out.push(UC0 + self.active as u8);
+        self.unicode_mode = false;
+        self.single_byte(c, next);
+    }
+}
+
+/// Encodes `text` in SCSU.
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut encoder = Encoder {
+        out: Vec::with_capacity(text.len()),
+        windows: DEFAULT_WINDOWS,
+        active: 0,
+        unicode_mode: false,
+        next_window: 0,
+    };
+    let chars: Vec<u32> = text.chars().map(u32::from).collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let next = chars.get(i + 1).copied();
+        if encoder.unicode_mode {
+            encoder.unicode(c, next);
+        } else {
+            encoder.single_byte(c, next);
+        }
+    }
+    encoder.out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+
+    /// Checks the length of the encoding of `text` against that of `icu`,
+    /// the output of ICU's SCSU converter. The bytes may differ, since the
+    /// encoders are free to pick different windows.
+    fn check(text: &str, icu: &[u8]) {
+        assert_eq!(encode(text).len(), icu.len(), "{:?}", text);
+    }
+
+    #[test]
+    fn uts_6_examples() {
+        assert_eq!(
+            encode("Öl fließt"),
+            [0xD6, 0x6C, 0x20, 0x66, 0x6C, 0x69, 0x65, 0xDF, 0x74]
+        );
+        assert_eq!(encode("Москва"), [0x12, 0x9C, 0xBE, 0xC1, 0xBA, 0xB2, 0xB0]);
+        // ICU writes U+10FFFF in Unicode mode, which takes a byte more than
+        // defining a window for it.
+        assert_eq!(
+            encode("AßЁşßǟ\u{F000}\u{10FFFF}"),
+            [
+                0x41, 0xDF, 0x03, 0x81, 0x03, 0x5F, 0xDF, 0x19, 0x03, 0xDF, 0x1A, 0x88, 0x80, 0x0B,
+                0x7F, 0xFF, 0xFF,
+            ]
+        );
+    }
+
+    #[test]
+    fn small_scripts() {
+        check(
+            "Ελληνικά και English",
+            &[
+                0x1F, 0xFB, 0xA5, 0xCB, 0xCB, 0xC7, 0xCD, 0xC9, 0xCA, 0xBC, 0x20, 0xCA, 0xC1, 0xC9,
+                0x20, 0x45, 0x6E, 0x67, 0x6C, 0x69, 0x73, 0x68,
+            ],
+        );
+        check(
+            "हिन्दी भाषा",
+            &[
+                0x14, 0xB9, 0xBF, 0xA8, 0xCD, 0xA6, 0xC0, 0x20, 0xAD, 0xBE, 0xB7, 0xBE,
+            ],
+        );
+        check("العربية", &[0x13, 0xA7, 0xC4, 0xB9, 0xB1, 0xA8, 0xCA, 0xA9]);
+    }
+
+    #[test]
+    fn cjk_and_hangul() {
+        check(
+            "日本語のテキスト",
+            &[
+                0x0F, 0x65, 0xE5, 0x67, 0x2C, 0x8A, 0x9E, 0xE5, 0xAE, 0x16, 0xA6, 0x8D, 0x99, 0xA8,
+            ],
+        );
+        check(
+            "대한민국",
+            &[0x0F, 0xB3, 0x00, 0xD5, 0x5C, 0xBB, 0xFC, 0xAD, 0x6D],
+        );
+        check(
+            "中文中文",
+            &[0x0F, 0x4E, 0x2D, 0x65, 0x87, 0x4E, 0x2D, 0x65, 0x87],
+        );
+        check("a中b", &[0x61, 0x0E, 0x4E, 0x2D, 0x62]);
+    }
+
+    #[test]
+    fn astral() {
+        check(
+            "\u{1D518}\u{1D52B}\u{1D526}\u{1D520}\u{1D52C}\u{1D521}\u{1D522}",
+            &[0x0B, 0xE1, 0xAA, 0x98, 0xAB, 0xA6, 0xA0, 0xAC, 0xA1, 0xA2],
+        );
+        check(
+            "a\u{1F600}b\u{1F600}",
+            &[0x61, 0x0B, 0xE1, 0xEC, 0x80, 0x62, 0x80],
+        );
+        check(
+            "漢字\u{20000}漢字",
+            &[
+                0x0F, 0x6F, 0x22, 0x5B, 0x57, 0xD8, 0x40, 0xDC, 0x00, 0x6F, 0x22, 0x5B, 0x57,
+            ],
+        );
+    }
+
+    #[test]
+    fn controls() {
+        check("a\0b", &[0x61, 0x00, 0x62]);
+        check(
+            "中文\0中文",
+            &[
+                0x0F, 0x4E, 0x2D, 0x65, 0x87, 0x00, 0x00, 0x4E, 0x2D, 0x65, 0x87,
+            ],
+        );
+        check("中文\0", &[0x0F, 0x4E, 0x2D, 0x65, 0x87, 0x00, 0x00]);
+        check(
+            "\u{1}\u{7}x\u{1B}",
+            &[0x01, 0x01, 0x01, 0x07, 0x78, 0x01, 0x1B],
+        );
+        check("\u{FEFF}a", &[0x0E, 0xFE, 0xFF, 0x61]);
+        check("", &[]);
+    }
+}
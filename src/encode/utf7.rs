@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! UTF-7 as specified in [RFC 2152](https://www.rfc-editor.org/rfc/rfc2152).
+//!
+//! The optional direct characters are encoded directly, and a base64 run is
+//! terminated with `-` only at the end of the text and when the next
+//! character would otherwise be taken as part of the run.
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Whether `c` can be written as itself.
+fn is_direct(c: char) -> bool {
+    c.is_ascii()
+        && (c.is_ascii_graphic() || matches!(c, ' ' | '\t' | '\r' | '\n'))
+        && !matches!(c, '+' | '\\' | '~')
+}
+
+fn is_base64(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '+' || c == '/'
+}
+
+/// Appends the base64 encoding of `units` to `out`, padding the last sextet
+/// with zero bits.
+fn push_base64(out: &mut Vec<u8>, units: &[u16]) {
+    let mut bits = 0u32;
+    let mut count = 0;
+    for &unit in units {
+        bits = (bits << 16) | u32::from(unit);
+        count += 16;
+        while count >= 6 {
+            count -= 6;
+            out.push(BASE64[((bits >> count) & 0x3F) as usize]);
+        }
+    }
+    if count > 0 {
+        out.push(BASE64[((bits << (6 - count)) & 0x3F) as usize]);
+    }
+}
+
+/// Encodes `text` in UTF-7.
+pub fn encode(text: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(text.len());
+    let mut units = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if is_direct(c) {
+            out.push(c as u8);
+        } else if c == '+' {
+            out.extend_from_slice(b"+-");
+        } else {
+            units.clear();
+            units.extend(c.encode_utf16(&mut [0u16; 2]).iter());
+            while let Some(&next) = chars.peek() {
+                if is_direct(next) || next == '+' {
+                    break;
+                }
+                units.extend(next.encode_utf16(&mut [0u16; 2]).iter());
+                chars.next();
+            }
+            out.push(b'+');
+            push_base64(&mut out, &units);
+            if chars
+                .peek()
+                .is_none_or(|&next| is_base64(next) || next == '-')
+            {
+                out.push(b'-');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode;
+
+    #[test]
+    fn rfc_2152_examples() {
+        assert_eq!(encode("A\u{2262}\u{391}."), b"A+ImIDkQ.");
+        assert_eq!(encode("Hi Mom -\u{263A}-!"), b"Hi Mom -+Jjo--!");
+        assert_eq!(encode("日本語"), b"+ZeVnLIqe-");
+        assert_eq!(encode("Item 3 is £1."), b"Item 3 is +AKM-1.");
+    }
+
+    /// The expected encodings are those of Python's `utf-7` codec.
+    #[test]
+    fn edge_cases() {
+        assert_eq!(encode(""), b"");
+        assert_eq!(encode("1 + 1 = 2"), b"1 +- 1 = 2");
+        assert_eq!(encode("~\\"), b"+AH4AXA-");
+        assert_eq!(encode("a\0b"), b"a+AAA-b");
+        assert_eq!(encode("caf\u{E9} au lait"), b"caf+AOk au lait");
+        assert_eq!(encode("\u{E4}\u{E4}\u{E4}"), b"+AOQA5ADk-");
+        // A run is terminated before a `-` or a base64 character but not
+        // before other direct characters.
+        assert_eq!(encode("\u{E4}-x"), b"+AOQ--x");
+        assert_eq!(encode("\u{E4}/"), b"+AOQ-/");
+        // Astral characters as surrogate pairs
+        assert_eq!(encode("\u{1F600}"), b"+2D3eAA-");
+        assert_eq!(encode("\u{1D518}a"), b"+2DXdGA-a");
+    }
+}
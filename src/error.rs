@@ -0,0 +1,160 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use std::fmt::Display;
+use thiserror::Error;
+
+/// A malformed XML document.
+#[derive(Debug, Error)]
+#[error("{message} at byte {position}")]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub(crate) fn new(position: usize, message: impl Display) -> ParseError {
+        ParseError {
+            position,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum UdhrError {
+    #[error("{file}: {source}")]
+    Io {
+        file: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{file}: {source}")]
+    Parse {
+        file: String,
+        #[source]
+        source: ParseError,
+    },
+    #[error("{file}: {source}")]
+    Zip {
+        file: String,
+        #[source]
+        source: zip::result::ZipError,
+    },
+    #[error("{file}: {source}")]
+    Json {
+        file: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{file}: {source}")]
+    Sqlite {
+        file: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+    #[error("{url}: {source}")]
+    Http {
+        url: String,
+        #[source]
+        source: ureq::Error,
+    },
+    #[error("could not listen on {address}: {source}")]
+    Serve {
+        address: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("{file}: {}", with_causes(source))]
+    Template {
+        file: String,
+        #[source]
+        source: tera::Error,
+    },
+    #[error("{pattern}: {source}")]
+    Glob {
+        pattern: String,
+        #[source]
+        source: glob::PatternError,
+    },
+    #[error("no files match {pattern}")]
+    NoMatches { pattern: String },
+    #[error("could not start worker threads: {0}")]
+    ThreadPool(#[from] rayon::ThreadPoolBuildError),
+    #[error("{file} does not contain index.xml")]
+    NoIndex { file: String },
+    #[error("could not determine the cache directory; pass --cache-dir")]
+    NoCacheDir,
+    #[error("no corpus given and none fetched; run `udhrlen fetch` first")]
+    NoCorpus,
+    #[error("no translation with code '{code}' was measured")]
+    NoSuchLang { code: String },
+    #[error("{metric} is not measured; enable it with the corresponding option")]
+    NotMeasured { metric: String },
+    #[error("{format} output does not support {option}")]
+    Unsupported { format: String, option: String },
+    #[error("{format} output requires building with the {feature} feature")]
+    NeedsFeature { format: String, feature: String },
+    #[error("{format} output can't be written to standard output; pass --output FILE")]
+    NeedsFile { format: String },
+}
+
+impl UdhrError {
+    /// Adapter for `map_err` that attributes an I/O error to `file`.
+    pub fn io(file: impl Display) -> impl FnOnce(std::io::Error) -> UdhrError {
+        move |source| UdhrError::Io {
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    /// Adapter for `map_err` that attributes a parse error to `file`.
+    pub fn parse(file: impl Display) -> impl FnOnce(ParseError) -> UdhrError {
+        move |source| UdhrError::Parse {
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    pub fn zip(file: impl Display) -> impl FnOnce(zip::result::ZipError) -> UdhrError {
+        move |source| UdhrError::Zip {
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    pub fn json(file: impl Display) -> impl FnOnce(serde_json::Error) -> UdhrError {
+        move |source| UdhrError::Json {
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    pub fn sqlite(file: impl Display) -> impl FnOnce(rusqlite::Error) -> UdhrError {
+        move |source| UdhrError::Sqlite {
+            file: file.to_string(),
+            source,
+        }
+    }
+
+    pub fn template(file: impl Display) -> impl FnOnce(tera::Error) -> UdhrError {
+        move |source| UdhrError::Template {
+            file: file.to_string(),
+            source,
+        }
+    }
+}
+
+/// `error` followed by its causes, since Tera puts the details of what is
+/// wrong with a template in them.
+fn with_causes(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut cause = error.source();
+    while let Some(error) = cause {
+        message.push_str(": ");
+        message.push_str(&error.to_string());
+        cause = error.source();
+    }
+    message
+}
@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Extracting the text of a UDHR XML document or of a file of the plain
+//! text distribution.
+
+use crate::ParseError;
+use quick_xml::events::BytesStart;
+use quick_xml::events::Event;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// A text node of the document together with where in the document it
+/// appears.
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    /// The number of the enclosing `<article>`, if any
+    pub article: Option<u32>,
+    /// Whether the text is inside the `<preamble>`
+    pub preamble: bool,
+    /// Whether the text is inside a `<note>`
+    pub note: bool,
+    pub text: String,
+}
+
+impl Chunk {
+    /// Whether the text counts as part of the declaration by default.
+    pub fn is_body(&self) -> bool {
+        !self.preamble && !self.note
+    }
+}
+
+/// The `number` attribute of `<article>` or, failing that, `ordinal`.
+fn article_number(e: &BytesStart, ordinal: u32) -> u32 {
+    e.attributes()
+        .filter_map(Result::ok)
+        .find(|a| a.key == b"number")
+        .and_then(|a| std::str::from_utf8(&a.value).ok()?.trim().parse().ok())
+        .unwrap_or(ordinal)
+}
+
+/// Splits a UDHR XML document into its text nodes. Whitespace-only text
+/// nodes are skipped.
+pub fn parse_document(content: &str) -> Result<Vec<Chunk>, ParseError> {
+    let mut chunks = Vec::new();
+    let mut note = false;
+    let mut preamble = false;
+    let mut article = None;
+    let mut articles_seen = 0;
+
+    let mut buf = Vec::new();
+    let mut xml = quick_xml::Reader::from_str(content);
+    loop {
+        let position = xml.buffer_position();
+        let toggle = |flag: &mut bool, start: bool, element: &str| {
+            if *flag == start {
+                let message = if start {
+                    format!("nested <{}>", element)
+                } else {
+                    format!("unexpected </{}>", element)
+                };
+                return Err(ParseError::new(position, message));
+            }
+            *flag = start;
+            Ok(())
+        };
+        match xml.read_event(&mut buf) {
+            Ok(Event::Start(ref e)) => match e.name() {
+                b"preamble" => toggle(&mut preamble, true, "preamble")?,
+                b"note" => toggle(&mut note, true, "note")?,
+                b"article" => {
+                    if article.is_some() {
+                        return Err(ParseError::new(position, "nested <article>"));
+                    }
+                    articles_seen += 1;
+                    article = Some(article_number(e, articles_seen));
+                }
+                _ => {}
+            },
+            Ok(Event::End(ref e)) => match e.name() {
+                b"preamble" => toggle(&mut preamble, false, "preamble")?,
+                b"note" => toggle(&mut note, false, "note")?,
+                b"article" => {
+                    article
+                        .take()
+                        .ok_or_else(|| ParseError::new(position, "unexpected </article>"))?;
+                }
+                _ => {}
+            },
+            Ok(Event::Text(e)) => {
+                let text = e
+                    .unescape_and_decode(&xml)
+                    .map_err(|e| ParseError::new(position, e))?;
+                if !text.as_bytes().iter().all(u8::is_ascii_whitespace) {
+                    chunks.push(Chunk {
+                        article,
+                        preamble,
+                        note,
+                        text,
+                    });
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => return Err(ParseError::new(xml.buffer_position(), e)),
+        }
+        buf.clear();
+    }
+    Ok(chunks)
+}
+
+/// Extracts the text of a UDHR XML document excluding the preamble and
+/// notes. Whitespace-only text nodes are skipped.
+pub fn extract_text(content: &str) -> Result<String, ParseError> {
+    Ok(parse_document(content)?
+        .iter()
+        .filter(|c| c.is_body())
+        .map(|c| &c.text[..])
+        .collect())
+}
+
+/// Extracts the text of a file of the plain text distribution: the
+/// non-blank lines after the header, which ends with a `---` line, trimmed
+/// and concatenated like the text nodes of the XML documents. The plain
+/// text doesn't mark the preamble or the articles, so the whole
+/// declaration is included.
+pub fn extract_plain_text(content: &str) -> Result<String, ParseError> {
+    let mut lines = content.lines();
+    if !lines.any(|line| line.trim() == "---") {
+        return Err(ParseError::new(0, "no --- line ending the header"));
+    }
+    Ok(lines
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect())
+}
+
+/// A set of article numbers, written like `1,2,5-10`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArticleSet {
+    ranges: Vec<RangeInclusive<u32>>,
+}
+
+impl ArticleSet {
+    pub fn contains(&self, article: u32) -> bool {
+        self.ranges.iter().any(|r| r.contains(&article))
+    }
+
+    pub(crate) fn ranges(&self) -> &[RangeInclusive<u32>] {
+        &self.ranges
+    }
+}
+
+impl FromStr for ArticleSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ArticleSet, String> {
+        let number = |n: &str| {
+            n.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("invalid article number '{}'", n.trim()))
+        };
+        let mut ranges = Vec::new();
+        for part in s.split(',') {
+            let range = match part.split_once('-') {
+                Some((start, end)) => number(start)?..=number(end)?,
+                None => {
+                    let n = number(part)?;
+                    n..=n
+                }
+            };
+            if range.is_empty() {
+                return Err(format!("empty article range '{}'", part.trim()));
+            }
+            ranges.push(range);
+        }
+        Ok(ArticleSet { ranges })
+    }
+}
@@ -0,0 +1,122 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Downloading the UDHR corpus from unicode.org.
+
+use crate::UdhrError;
+use serde::Deserialize;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Cursor;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Where unicode.org publishes the XML version of the corpus.
+pub const DEFAULT_URL: &str = "https://www.unicode.org/udhr/assemblies/udhr_xml.zip";
+
+const VERSION_FILE: &str = "dataset.json";
+const CORPUS_DIR: &str = "udhr_xml";
+
+/// The archive is a few megabytes, so this is generous.
+const MAX_SIZE: u64 = 256 * 1024 * 1024;
+
+/// What is known about the version of a downloaded corpus. unicode.org
+/// doesn't version the archive, so the HTTP validators stand in for a
+/// version number.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatasetVersion {
+    pub url: String,
+    pub last_modified: Option<String>,
+    pub etag: Option<String>,
+    /// Seconds since the Unix epoch
+    pub fetched: u64,
+}
+
+impl DatasetVersion {
+    /// Reads the version record stored in the cache directory `cache`.
+    pub fn load(cache: &Path) -> Result<DatasetVersion, UdhrError> {
+        let path = cache.join(VERSION_FILE);
+        let file = File::open(&path).map_err(UdhrError::io(path.display()))?;
+        serde_json::from_reader(file).map_err(UdhrError::json(path.display()))
+    }
+
+    fn store(&self, cache: &Path) -> Result<(), UdhrError> {
+        let path = cache.join(VERSION_FILE);
+        let file = File::create(&path).map_err(UdhrError::io(path.display()))?;
+        serde_json::to_writer_pretty(file, self).map_err(UdhrError::json(path.display()))
+    }
+}
+
+/// The default cache directory: `$XDG_CACHE_HOME/udhrlen` or
+/// `~/.cache/udhrlen`.
+pub fn cache_dir() -> Option<PathBuf> {
+    let base = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+    Some(base.join("udhrlen"))
+}
+
+/// The directory containing `index.xml` within the cache directory `cache`,
+/// if a corpus has been fetched there.
+pub fn corpus_dir(cache: &Path) -> Option<PathBuf> {
+    let root = cache.join(CORPUS_DIR);
+    if root.join("index.xml").is_file() {
+        return Some(root);
+    }
+    // Tolerate archives that wrap everything in a top-level directory.
+    for entry in std::fs::read_dir(&root).ok()? {
+        let path = entry.ok()?.path();
+        if path.join("index.xml").is_file() {
+            return Some(path);
+        }
+    }
+    None
+}
+
+/// Downloads the corpus archive from `url` and unpacks it into the cache
+/// directory `cache`, replacing any previously fetched corpus. Returns the
+/// directory containing `index.xml`.
+pub fn fetch(url: &str, cache: &Path) -> Result<PathBuf, UdhrError> {
+    let http = |source| UdhrError::Http {
+        url: url.to_string(),
+        source,
+    };
+    let mut response = ureq::get(url).call().map_err(http)?;
+    let header = |name: &str| {
+        response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let version = DatasetVersion {
+        url: url.to_string(),
+        last_modified: header("last-modified"),
+        etag: header("etag"),
+        fetched: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs()),
+    };
+    let bytes = response
+        .body_mut()
+        .with_config()
+        .limit(MAX_SIZE)
+        .read_to_vec()
+        .map_err(http)?;
+
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).map_err(UdhrError::zip(url))?;
+    std::fs::create_dir_all(cache).map_err(UdhrError::io(cache.display()))?;
+    let root = cache.join(CORPUS_DIR);
+    if root.exists() {
+        std::fs::remove_dir_all(&root).map_err(UdhrError::io(root.display()))?;
+    }
+    archive.extract(&root).map_err(UdhrError::zip(url))?;
+    version.store(cache)?;
+
+    corpus_dir(cache).ok_or_else(|| UdhrError::NoIndex {
+        file: url.to_string(),
+    })
+}
@@ -0,0 +1,95 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A C interface to the base metrics, declared in `include/udhrlen.h`,
+//! which `build.rs` generates from this file.
+
+use crate::Metrics;
+use crate::NormalizationForm;
+use std::panic::catch_unwind;
+
+/// The base metrics of a text. See `Metrics` for their definitions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UdhrlenCounts {
+    /// UTF-8 code units
+    pub utf8: usize,
+    /// UTF-16 code units
+    pub utf16: usize,
+    /// Unicode scalar values
+    pub utf32: usize,
+    /// Extended grapheme clusters
+    pub graphemes: usize,
+    /// East Asian Width
+    pub width: usize,
+    /// Words containing a letter or a digit
+    pub words: usize,
+    /// Sentences
+    pub sentences: usize,
+}
+
+impl From<&Metrics> for UdhrlenCounts {
+    fn from(metrics: &Metrics) -> UdhrlenCounts {
+        UdhrlenCounts {
+            utf8: metrics.utf8,
+            utf16: metrics.utf16,
+            utf32: metrics.utf32,
+            graphemes: metrics.graphemes,
+            width: metrics.width,
+            words: metrics.words,
+            sentences: metrics.sentences,
+        }
+    }
+}
+
+/// The counts were written.
+pub const UDHRLEN_OK: i32 = 0;
+/// A pointer was null or the text isn't UTF-8.
+pub const UDHRLEN_INVALID_ARGUMENT: i32 = -1;
+/// Counting failed unexpectedly.
+pub const UDHRLEN_INTERNAL_ERROR: i32 = -2;
+
+/// Measures the `len` bytes of UTF-8 at `text`, after normalizing them to
+/// NFC if `normalize` is true, and writes the counts to `out`.
+///
+/// # Safety
+///
+/// `text` must point to `len` readable bytes, or may be null if `len` is
+/// zero, and `out` must point to a writable `UdhrlenCounts`.
+#[no_mangle]
+pub unsafe extern "C" fn udhrlen_count(
+    text: *const u8,
+    len: usize,
+    normalize: bool,
+    out: *mut UdhrlenCounts,
+) -> i32 {
+    if out.is_null() || (text.is_null() && len != 0) {
+        return UDHRLEN_INVALID_ARGUMENT;
+    }
+    let bytes = if len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(text, len)
+    };
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(_) => return UDHRLEN_INVALID_ARGUMENT,
+    };
+    // Unwinding into C is undefined behavior.
+    let counts = catch_unwind(|| {
+        let metrics = if normalize {
+            Metrics::new(&NormalizationForm::Nfc.normalize(text))
+        } else {
+            Metrics::new(text)
+        };
+        UdhrlenCounts::from(&metrics)
+    });
+    match counts {
+        Ok(counts) => {
+            out.write(counts);
+            UDHRLEN_OK
+        }
+        Err(_) => UDHRLEN_INTERNAL_ERROR,
+    }
+}
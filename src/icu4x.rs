@@ -0,0 +1,43 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Grapheme cluster and width counts computed with ICU4X, for checking the
+//! counts of `unic-segment` and `unicode-width`.
+
+use icu_properties::props::EastAsianWidth;
+use icu_properties::props::GeneralCategory;
+use icu_properties::CodePointMapData;
+use icu_segmenter::GraphemeClusterSegmenter;
+
+/// The number of extended grapheme clusters in `text`.
+pub fn graphemes(text: &str) -> usize {
+    // The boundaries include the start and the end of the text.
+    GraphemeClusterSegmenter::new()
+        .segment_str(text)
+        .count()
+        .saturating_sub(1)
+}
+
+/// The width of `text` in the manner of `unicode-width`: wide and
+/// fullwidth characters take two columns, controls, marks, format
+/// characters other than the soft hyphen and Hangul medial vowels and final
+/// consonants take none and other characters, including ambiguous ones, take
+/// one.
+pub fn width(text: &str) -> usize {
+    let east_asian_width = CodePointMapData::<EastAsianWidth>::new();
+    let general_category = CodePointMapData::<GeneralCategory>::new();
+    text.chars()
+        .map(|c| match general_category.get(c) {
+            GeneralCategory::Control
+            | GeneralCategory::NonspacingMark
+            | GeneralCategory::EnclosingMark => 0,
+            GeneralCategory::Format if c != '\u{AD}' => 0,
+            _ if ('\u{1160}'..='\u{11FF}').contains(&c) => 0,
+            _ => match east_asian_width.get(c) {
+                EastAsianWidth::Wide | EastAsianWidth::Fullwidth => 2,
+                _ => 1,
+            },
+        })
+        .sum()
+}
@@ -0,0 +1,823 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Measuring the length of the translations of the Universal Declaration of
+//! Human Rights in various units.
+
+pub mod bench;
+pub mod cache;
+pub mod corpus;
+pub mod encode;
+pub mod error;
+pub mod extract;
+pub mod fetch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod icu4x;
+pub mod metrics;
+pub mod output;
+pub mod population;
+#[cfg(feature = "python")]
+mod python;
+pub mod serve;
+pub mod stats;
+pub mod tokenize;
+pub mod tui;
+
+pub use crate::corpus::Content;
+pub use crate::corpus::Corpus;
+pub use crate::corpus::CorpusFormat;
+pub use crate::corpus::IndexEntry;
+pub use crate::error::ParseError;
+pub use crate::error::UdhrError;
+pub use crate::extract::extract_text;
+pub use crate::extract::ArticleSet;
+pub use crate::extract::Chunk;
+pub use crate::metrics::CaseMapping;
+pub use crate::metrics::Compression;
+pub use crate::metrics::EncodingScheme;
+pub use crate::metrics::Metric;
+pub use crate::metrics::Metrics;
+pub use crate::metrics::NormalizationForm;
+pub use crate::tokenize::Tokenizer;
+
+use crate::metrics::Counter;
+use rayon::prelude::*;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::cmp::Reverse;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Instant;
+use tracing::debug;
+use tracing::info;
+
+/// A translation and its metrics.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Lang {
+    pub name: String,
+    pub code: Option<String>,
+    pub script: Option<String>,
+    /// The stage of the translation in the UDHR in Unicode project, from 1
+    /// (least reviewed) to 5
+    pub stage: Option<u8>,
+    pub metrics: Metrics,
+    /// The encoding in which the text is the smallest, when requested
+    #[serde(skip_serializing_if = "Option::is_none", skip_deserializing)]
+    pub smallest_encoding: Option<&'static str>,
+    /// The metrics of each article when measured with `per_article`
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub articles: Vec<Article>,
+}
+
+/// The metrics of one article of a translation.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Article {
+    pub number: u32,
+    pub metrics: Metrics,
+}
+
+/// What the deviations in a report are relative to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Baseline {
+    /// The median of the metric
+    Median,
+    /// The mean of the metric
+    Mean,
+    /// The UTF-8 length of the same translation
+    Utf8,
+    /// The metric of the translation with this code
+    Lang(String),
+}
+
+impl FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Baseline, String> {
+        match s.trim() {
+            "median" => Ok(Baseline::Median),
+            "mean" => Ok(Baseline::Mean),
+            "utf8" => Ok(Baseline::Utf8),
+            other => match other.strip_prefix("lang:") {
+                Some(code) if !code.is_empty() => Ok(Baseline::Lang(code.to_string())),
+                _ => Err(format!(
+                    "unknown baseline '{}', expected median, mean, utf8 or lang:<code>",
+                    other
+                )),
+            },
+        }
+    }
+}
+
+/// What to order the translations of a report by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Metric(Metric),
+    Name,
+    Script,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<SortKey, String> {
+        match s.trim() {
+            "name" => Ok(SortKey::Name),
+            "script" => Ok(SortKey::Script),
+            metric => Ok(SortKey::Metric(metric.parse()?)),
+        }
+    }
+}
+
+/// The translations of a report that share a script.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Group {
+    pub script: String,
+    /// The number of translations in the group
+    pub count: usize,
+    pub summary: stats::Summary,
+}
+
+/// How many translations an encoding is the smallest for.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct EncodingWins {
+    pub encoding: &'static str,
+    pub count: usize,
+}
+
+/// The translations together with their summary statistics.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Report {
+    /// The metrics to report, in order
+    #[serde(skip)]
+    pub columns: Vec<Metric>,
+    /// What deviations are relative to
+    #[serde(skip)]
+    pub baseline: Baseline,
+    /// The metrics of the translation that a `Baseline::Lang` baseline
+    /// refers to, kept even if the translation isn't listed
+    #[serde(skip)]
+    pub reference: Option<Metrics>,
+    /// How the summary statistics are computed
+    #[serde(skip)]
+    pub stats_options: stats::StatsOptions,
+    pub langs: Vec<Lang>,
+    pub summary: stats::Summary,
+    /// The script groups in the order of `langs` when grouped by script
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<Group>,
+    /// The encodings that are the smallest for some translation, most
+    /// frequent first, when requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub encoding_wins: Vec<EncodingWins>,
+}
+
+impl Report {
+    /// Sorts `langs` by UTF-8 length and computes the summary statistics of
+    /// `columns`.
+    pub fn new(mut langs: Vec<Lang>, columns: Vec<Metric>) -> Report {
+        langs.sort_by_key(|l| {
+            let m = &l.metrics;
+            (m.utf8, m.utf16, m.utf32, m.graphemes, m.width)
+        });
+        let stats_options = stats::StatsOptions::default();
+        let summary = stats::Summary::new(&langs, &columns, &stats_options);
+        Report {
+            columns,
+            baseline: Baseline::Median,
+            reference: None,
+            stats_options,
+            langs,
+            summary,
+            groups: Vec::new(),
+            encoding_wins: Vec::new(),
+        }
+    }
+
+    /// Makes the deviations relative to `baseline`.
+    pub fn set_baseline(&mut self, baseline: Baseline) {
+        self.reference = match &baseline {
+            Baseline::Lang(code) => self
+                .langs
+                .iter()
+                .find(|l| l.code.as_ref() == Some(code))
+                .map(|l| l.metrics.clone()),
+            _ => None,
+        };
+        self.baseline = baseline;
+    }
+
+    /// Recomputes the summary statistics with `options`.
+    pub fn set_stats_options(&mut self, options: stats::StatsOptions) {
+        self.summary = stats::Summary::new(&self.langs, &self.columns, &options);
+        self.stats_options = options;
+    }
+
+    /// Orders the translations by `key`, breaking ties by name. Translations
+    /// lacking the metric go last.
+    pub fn sort(&mut self, key: SortKey, descending: bool) {
+        let direction = |ordering: Ordering| {
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        self.langs.sort_by(|a, b| {
+            let primary = match key {
+                SortKey::Name => direction(a.name.cmp(&b.name)),
+                SortKey::Script => direction(a.script.cmp(&b.script)),
+                SortKey::Metric(metric) => {
+                    match (metric.value(&a.metrics), metric.value(&b.metrics)) {
+                        (Some(a), Some(b)) => direction(a.total_cmp(&b)),
+                        (a, b) => b.is_none().cmp(&a.is_none()).reverse(),
+                    }
+                }
+            };
+            primary.then_with(|| a.name.cmp(&b.name))
+        });
+    }
+
+    /// Keeps only the `n` translations with the largest values of `metric`,
+    /// or the smallest ones if `largest` is false, in their current order.
+    /// The summary statistics remain those of all the translations.
+    pub fn retain_extremes(&mut self, metric: Metric, n: usize, largest: bool) {
+        let mut values: Vec<(f64, usize)> = self
+            .langs
+            .iter()
+            .enumerate()
+            .filter_map(|(i, l)| Some((metric.value(&l.metrics)?, i)))
+            .collect();
+        values.sort_by(|a, b| {
+            let ordering = a.0.total_cmp(&b.0);
+            if largest {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+        let mut keep = vec![false; self.langs.len()];
+        for &(_, i) in values.iter().take(n) {
+            keep[i] = true;
+        }
+        let mut keep = keep.into_iter();
+        self.langs.retain(|_| keep.next().unwrap_or(false));
+    }
+
+    /// Clusters the translations by script, keeping their order within each
+    /// script, and computes the summary statistics of each script.
+    pub fn group_by_script(&mut self) {
+        self.langs.sort_by(|a, b| a.script.cmp(&b.script));
+        self.groups.clear();
+        for chunk in self.langs.chunk_by(|a, b| a.script == b.script) {
+            self.groups.push(Group {
+                script: chunk[0].script.clone().unwrap_or_default(),
+                count: chunk.len(),
+                summary: stats::Summary::new(chunk, &self.columns, &self.stats_options),
+            });
+        }
+    }
+
+    /// Whether the report has a direction column, which accompanies the
+    /// percentage of right-to-left characters.
+    pub fn has_direction(&self) -> bool {
+        self.columns.contains(&Metric::RtlPercent)
+    }
+
+    /// Determines the smallest encoding of each translation and counts how
+    /// many translations each encoding wins.
+    pub fn find_smallest_encodings(&mut self) {
+        self.encoding_wins.clear();
+        for lang in self.langs.iter_mut() {
+            let encoding = lang.metrics.smallest_encoding();
+            lang.smallest_encoding = Some(encoding);
+            match self
+                .encoding_wins
+                .iter_mut()
+                .find(|w| w.encoding == encoding)
+            {
+                Some(wins) => wins.count += 1,
+                None => self.encoding_wins.push(EncodingWins { encoding, count: 1 }),
+            }
+        }
+        self.encoding_wins.sort_by_key(|w| Reverse(w.count));
+    }
+
+    /// The value that `metric` of a row whose metrics are given by `value`
+    /// is compared to, if there is one.
+    pub fn baseline_value(
+        &self,
+        value: &dyn Fn(Metric) -> Option<f64>,
+        metric: Metric,
+    ) -> Option<f64> {
+        match &self.baseline {
+            Baseline::Median => Some(self.summary.get(metric)?.median),
+            Baseline::Mean => Some(self.summary.get(metric)?.mean),
+            Baseline::Utf8 => value(Metric::Utf8),
+            Baseline::Lang(_) => metric.value(self.reference.as_ref()?),
+        }
+    }
+
+    /// A report for each article number found in `langs`, in which each
+    /// translation's metrics are those of that article. Translations lacking
+    /// the article are left out of its report.
+    pub fn per_article(langs: &[Lang], columns: &[Metric]) -> Vec<(u32, Report)> {
+        let mut numbers: Vec<u32> = langs
+            .iter()
+            .flat_map(|l| l.articles.iter().map(|a| a.number))
+            .collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        numbers
+            .into_iter()
+            .map(|number| {
+                let article_langs = langs
+                    .iter()
+                    .filter_map(|l| {
+                        let article = l.articles.iter().find(|a| a.number == number)?;
+                        Some(Lang {
+                            metrics: article.metrics.clone(),
+                            articles: Vec::new(),
+                            ..l.clone()
+                        })
+                    })
+                    .collect();
+                (number, Report::new(article_langs, columns.to_vec()))
+            })
+            .collect()
+    }
+}
+
+/// Normalizes `text` to Normalization Form C.
+pub fn normalize(text: &str) -> String {
+    NormalizationForm::Nfc.normalize(text)
+}
+
+/// Measures the UDHR XML document `content` of the translation `entry`.
+pub fn measure(
+    content: &str,
+    entry: &IndexEntry,
+    options: &CountOptions,
+) -> Result<Lang, ParseError> {
+    // The chunks are measured as they come, for the whole text and for the
+    // article they are in. The text is only collected for the optional
+    // metrics that need all of it.
+    let mut counter = Counter::new(entry, options);
+    let mut article: Option<(u32, Counter)> = None;
+    let mut articles = Vec::new();
+    let finish = |(number, counter): (u32, Counter)| Article {
+        number,
+        metrics: counter.finish(),
+    };
+    for chunk in extract::parse_document(content)? {
+        if !options.includes(&chunk) {
+            continue;
+        }
+        counter.push(&chunk.text);
+        if let (true, Some(number)) = (options.per_article, chunk.article) {
+            if article.as_ref().is_none_or(|(last, _)| *last != number) {
+                articles.extend(article.take().map(finish));
+            }
+            article
+                .get_or_insert_with(|| (number, Counter::new(entry, options)))
+                .1
+                .push(&chunk.text);
+        }
+    }
+    articles.extend(article.map(finish));
+
+    Ok(Lang {
+        name: entry.name.clone(),
+        code: Some(entry.code.clone()),
+        script: Some(entry.script.clone()),
+        stage: Some(entry.stage),
+        metrics: counter.finish(),
+        smallest_encoding: None,
+        articles,
+    })
+}
+
+/// Measures the text of `entry` from the plain text distribution.
+/// `per_article` and the options selecting parts of the text don't apply.
+pub fn measure_plain_text(
+    content: &str,
+    entry: &IndexEntry,
+    options: &CountOptions,
+) -> Result<Lang, ParseError> {
+    let text = extract::extract_plain_text(content)?;
+    Ok(Lang {
+        name: entry.name.clone(),
+        code: Some(entry.code.clone()),
+        script: Some(entry.script.clone()),
+        stage: Some(entry.stage),
+        metrics: Metrics::compute(&text, entry, options),
+        smallest_encoding: None,
+        articles: Vec::new(),
+    })
+}
+
+/// Measures the UDHR XML file at `path` of the translation `entry`.
+pub fn count(path: &Path, entry: &IndexEntry, options: &CountOptions) -> Result<Lang, UdhrError> {
+    let content = Content::map(path).map_err(UdhrError::io(path.display()))?;
+    content
+        .text()
+        .and_then(|content| measure(content, entry, options))
+        .map_err(UdhrError::parse(path.display()))
+}
+
+/// Settings for measuring a corpus.
+#[derive(Debug, Clone, Default)]
+pub struct CountOptions {
+    /// Fail on the first translation that can't be read or parsed instead of
+    /// skipping it.
+    pub strict: bool,
+    /// The number of worker threads. `None` uses one per CPU.
+    pub jobs: Option<usize>,
+    /// The directory for caching the metrics of each translation between
+    /// runs. `None` measures every translation.
+    pub cache: Option<PathBuf>,
+    /// Also measure each article separately.
+    pub per_article: bool,
+    /// Measure only these articles instead of the whole declaration.
+    pub articles: Option<ArticleSet>,
+    /// Measure the preamble, too, even when `articles` is set.
+    pub include_preamble: bool,
+    /// Measure the notes, too.
+    pub include_notes: bool,
+    /// Also measure the encoded lengths in these normalization forms.
+    pub normalizations: Vec<NormalizationForm>,
+    /// Also measure the encoded lengths after these case mappings.
+    pub case_mappings: Vec<CaseMapping>,
+    /// Also measure the length in these encoding schemes.
+    pub encodings: Vec<EncodingScheme>,
+    /// Also measure the length in the legacy encoding of each translation's
+    /// language.
+    pub legacy: bool,
+    /// Also compute the entropy of the scalar values and the UTF-8 bytes.
+    pub entropy: bool,
+    /// Also measure the UTF-8 length after compressing with these.
+    pub compressions: Vec<Compression>,
+    /// Also count the tokens with these vocabularies. Ignored unless built
+    /// with the `tokenizer` feature.
+    pub tokenizers: Vec<Tokenizer>,
+    /// Measure the text as distributed instead of normalizing it to NFC
+    /// first, and report how the UTF-8 length would change by normalizing.
+    pub no_normalize: bool,
+    /// Also measure the width with the ambiguous characters taken as wide.
+    pub ambiguous_wide: bool,
+    /// Also count the scalar values in each East Asian Width category.
+    pub eaw_breakdown: bool,
+    /// Also count the scalar values in each major General_Category class.
+    pub category_breakdown: bool,
+    /// Also count the combining marks and the supplementary-plane scalar
+    /// values.
+    pub composition: bool,
+    /// Also count the script runs and the scalar values in scripts other
+    /// than the declared one.
+    pub script_runs: bool,
+    /// Also count the distinct scalar values and grapheme clusters.
+    pub distinct: bool,
+    /// Also measure the longest grapheme cluster.
+    pub longest_grapheme: bool,
+    /// Also count the emoji, zero width joiners and variation selectors.
+    pub emoji: bool,
+    /// Also count the strongly left-to-right and right-to-left characters.
+    pub bidi: bool,
+    /// Also count the scalar values in each Unicode block.
+    pub blocks: bool,
+    /// Also count the grapheme clusters of each length.
+    pub cluster_sizes: bool,
+    /// Also count the grapheme clusters and the width with ICU4X.
+    pub icu4x: bool,
+    /// Measure only the translations at these stages instead of 4 and 5,
+    /// unless empty.
+    pub stages: Vec<u8>,
+    /// Measure only the translations with these codes or language codes,
+    /// unless empty.
+    pub only: Vec<String>,
+    /// Skip the translations with these codes or language codes.
+    pub exclude: Vec<String>,
+    /// Measure only the translations in these scripts, unless empty.
+    pub scripts: Vec<String>,
+}
+
+impl CountOptions {
+    /// The metrics that these options compute.
+    pub fn columns(&self) -> Vec<Metric> {
+        let mut columns = Metric::BASE.to_vec();
+        if self.ambiguous_wide {
+            columns.push(Metric::WidthCjk);
+        }
+        if self.eaw_breakdown {
+            for &category in metrics::EastAsianWidthCategory::ALL.iter() {
+                columns.push(Metric::EastAsianWidth(category));
+            }
+        }
+        if self.category_breakdown {
+            for &class in metrics::CategoryClass::ALL.iter() {
+                columns.push(Metric::Category(class));
+            }
+        }
+        if self.composition {
+            columns.push(Metric::CombiningMarks);
+            columns.push(Metric::Astral);
+        }
+        if self.script_runs {
+            columns.push(Metric::ScriptRuns);
+            columns.push(Metric::ForeignScript);
+        }
+        if self.distinct {
+            columns.push(Metric::DistinctChars);
+            columns.push(Metric::DistinctGraphemes);
+        }
+        if self.longest_grapheme {
+            columns.push(Metric::LongestGraphemeChars);
+            columns.push(Metric::LongestGraphemeBytes);
+        }
+        if self.emoji {
+            columns.push(Metric::Emoji);
+            columns.push(Metric::Zwj);
+            columns.push(Metric::VariationSelectors);
+        }
+        if self.bidi {
+            columns.push(Metric::RtlPercent);
+        }
+        for &form in self.normalizations.iter() {
+            for &encoding in metrics::EncodingForm::ALL.iter() {
+                columns.push(Metric::Normalized(form, encoding));
+            }
+        }
+        for &mapping in self.case_mappings.iter() {
+            for &encoding in metrics::EncodingForm::ALL.iter() {
+                columns.push(Metric::CaseMapped(mapping, encoding));
+            }
+        }
+        for &scheme in self.encodings.iter() {
+            columns.push(Metric::Encoded(scheme));
+        }
+        if self.legacy {
+            columns.push(Metric::Legacy);
+            columns.push(Metric::LegacyUnmappable);
+        }
+        for &compression in self.compressions.iter() {
+            columns.push(Metric::Compressed(compression));
+        }
+        for &tokenizer in self.tokenizers.iter() {
+            columns.push(Metric::Tokens(tokenizer));
+        }
+        if self.entropy {
+            columns.push(Metric::CharEntropy);
+            columns.push(Metric::ByteEntropy);
+        }
+        if self.no_normalize {
+            columns.push(Metric::NfcDelta);
+        }
+        if self.icu4x {
+            columns.push(Metric::Icu4xGraphemes);
+            columns.push(Metric::Icu4xWidth);
+        }
+        columns
+    }
+
+    /// Whether `metric` can be reported with these options: either it is
+    /// computed or it is derived from the base metrics.
+    pub fn measures(&self, metric: Metric) -> bool {
+        metric.is_measured_in(&self.columns())
+    }
+
+    /// Whether the translation `entry` is selected by `stages`, `only`,
+    /// `exclude` and `scripts`.
+    pub fn selects(&self, entry: &IndexEntry) -> bool {
+        let stage_selected = if self.stages.is_empty() {
+            entry.stage == 4 || entry.stage == 5
+        } else {
+            self.stages.contains(&entry.stage)
+        };
+        let language = entry.code.split('_').next().unwrap_or("");
+        let matches = |code: &String| *code == entry.code || code == language;
+        stage_selected
+            && (self.only.is_empty() || self.only.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+            && (self.scripts.is_empty()
+                || self
+                    .scripts
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&entry.script)))
+    }
+
+    /// Whether `chunk` contributes to the measured text.
+    pub fn includes(&self, chunk: &Chunk) -> bool {
+        if (chunk.preamble && !self.include_preamble) || (chunk.note && !self.include_notes) {
+            return false;
+        }
+        if chunk.preamble {
+            return true;
+        }
+        match (&self.articles, chunk.article) {
+            (None, _) => true,
+            (Some(set), Some(article)) => set.contains(article),
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// The result of measuring a corpus.
+#[derive(Debug, Default)]
+pub struct Counts {
+    pub langs: Vec<Lang>,
+    /// The errors of the translations that were skipped.
+    pub skipped: Vec<UdhrError>,
+}
+
+/// Measured translations together with the metrics that were measured, for
+/// rendering reports without measuring the corpus again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub columns: Vec<Metric>,
+    pub langs: Vec<Lang>,
+}
+
+impl Snapshot {
+    /// Writes the snapshot to `path` as JSON.
+    pub fn save(&self, path: &Path) -> Result<(), UdhrError> {
+        let file = File::create(path).map_err(UdhrError::io(path.display()))?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer(&mut writer, self).map_err(UdhrError::json(path.display()))?;
+        writer.flush().map_err(UdhrError::io(path.display()))
+    }
+
+    /// Reads a snapshot written by `save`.
+    pub fn load(path: &Path) -> Result<Snapshot, UdhrError> {
+        let text = std::fs::read_to_string(path).map_err(UdhrError::io(path.display()))?;
+        serde_json::from_str(&text).map_err(UdhrError::json(path.display()))
+    }
+}
+
+impl From<Snapshot> for Counts {
+    fn from(snapshot: Snapshot) -> Counts {
+        Counts {
+            langs: snapshot.langs,
+            skipped: Vec::new(),
+        }
+    }
+}
+
+/// Measures every stage 4 and 5 translation in the corpus at `path`, which
+/// is either a directory or a zip archive of the XML or the plain text
+/// distribution.
+///
+/// The files are read sequentially and then measured in parallel.
+pub fn count_corpus(path: &Path, options: &CountOptions) -> Result<Counts, UdhrError> {
+    let mut corpus = Corpus::open(path)?;
+    let index = corpus.index()?;
+    count_listed(corpus, index, options)
+}
+
+/// Measures the translations listed in `index`, e.g. an `index.xml`
+/// filtered or generated by another tool, with their files in the corpus
+/// directory or zip archive at `path` instead of the ones that its own
+/// `index.xml` lists.
+pub fn count_corpus_with_index(
+    path: &Path,
+    index: Vec<IndexEntry>,
+    options: &CountOptions,
+) -> Result<Counts, UdhrError> {
+    count_listed(Corpus::open(path)?, index, options)
+}
+
+fn count_listed(
+    mut corpus: Corpus,
+    index: Vec<IndexEntry>,
+    options: &CountOptions,
+) -> Result<Counts, UdhrError> {
+    let format = corpus.format();
+    let start = Instant::now();
+    let mut inputs = Vec::new();
+    for entry in index {
+        if options.selects(&entry) {
+            let file_name = entry.file_name_in(format);
+            debug!("reading {}", corpus.display(&file_name));
+            let content = corpus.read(&file_name);
+            inputs.push((entry, corpus.display(&file_name), content));
+        }
+    }
+    info!("read {} files in {:.2?}", inputs.len(), start.elapsed());
+
+    measure_all(inputs, options, |(entry, file, content)| {
+        let start = Instant::now();
+        let content = content?;
+        let content = content.text().map_err(UdhrError::parse(&file))?;
+        let measure = || match format {
+            CorpusFormat::Xml => measure(content, &entry, options),
+            CorpusFormat::Text => measure_plain_text(content, &entry, options),
+        };
+        let lang = match &options.cache {
+            Some(dir) => cache::measure_cached(dir, content, &entry, options, measure),
+            None => measure(),
+        }
+        .map_err(UdhrError::parse(&file))?;
+        debug!("measured {} in {:.2?}", file, start.elapsed());
+        Ok(lang)
+    })
+}
+
+/// Measures each of the UTF-8 text files matching the glob `pattern` as a
+/// translation named after the file, with the file stem as its code. The
+/// selection options and `per_article` don't apply.
+pub fn count_plain(pattern: &str, options: &CountOptions) -> Result<Counts, UdhrError> {
+    let paths = glob::glob(pattern).map_err(|source| UdhrError::Glob {
+        pattern: pattern.to_string(),
+        source,
+    })?;
+    let mut inputs = Vec::new();
+    for path in paths {
+        let path = path.map_err(|error| {
+            let file = error.path().display().to_string();
+            UdhrError::io(file)(error.into())
+        })?;
+        if path.is_file() {
+            debug!("reading {}", path.display());
+            let content = std::fs::read_to_string(&path).map_err(UdhrError::io(path.display()));
+            inputs.push((path, content));
+        }
+    }
+    if inputs.is_empty() {
+        return Err(UdhrError::NoMatches {
+            pattern: pattern.to_string(),
+        });
+    }
+
+    measure_all(inputs, options, |(path, content)| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let code = path.file_stem().unwrap_or_default().to_string_lossy();
+        let entry = IndexEntry {
+            name: name.into_owned(),
+            code: code.into_owned(),
+            script: String::new(),
+            stage: 0,
+        };
+        let content = content?;
+        let measure = || -> Result<Lang, UdhrError> {
+            Ok(Lang {
+                metrics: Metrics::compute(&content, &entry, options),
+                name: entry.name.clone(),
+                code: Some(entry.code.clone()),
+                script: None,
+                stage: None,
+                smallest_encoding: None,
+                articles: Vec::new(),
+            })
+        };
+        match &options.cache {
+            Some(dir) => cache::measure_cached(dir, &content, &entry, options, measure),
+            None => measure(),
+        }
+    })
+}
+
+/// Applies `measure` to each of `inputs` in parallel, skipping the ones
+/// that fail unless `options` are strict.
+fn measure_all<T, F>(
+    inputs: Vec<T>,
+    options: &CountOptions,
+    measure: F,
+) -> Result<Counts, UdhrError>
+where
+    T: Send,
+    F: Fn(T) -> Result<Lang, UdhrError> + Sync + Send,
+{
+    if let Some(dir) = &options.cache {
+        cache::prune(dir);
+    }
+    let start = Instant::now();
+    let measure_all = || inputs.into_par_iter().map(measure).collect::<Vec<_>>();
+    let results = match options.jobs {
+        Some(jobs) => rayon::ThreadPoolBuilder::new()
+            .num_threads(jobs)
+            .build()?
+            .install(measure_all),
+        None => measure_all(),
+    };
+
+    let mut counts = Counts::default();
+    for lang in results {
+        match lang {
+            Ok(lang) => counts.langs.push(lang),
+            Err(error) if !options.strict => counts.skipped.push(error),
+            Err(error) => return Err(error),
+        }
+    }
+    info!(
+        "measured {} translations in {:.2?}",
+        counts.langs.len(),
+        start.elapsed()
+    );
+    Ok(counts)
+}
@@ -2,357 +2,1889 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use quick_xml::events::Event;
-use std::fs::File;
+use clap::Args;
+use clap::CommandFactory;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use clap_complete::Shell;
+use std::io::IsTerminal;
 use std::io::Read;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
-use unic_normal::StrNormalForm;
-use unic_segment::Graphemes;
-use unicode_width::UnicodeWidthStr;
-
-#[derive(Debug)]
-struct Lang {
-    name: String,
-    utf8: usize,
-    utf16: usize,
-    utf32: usize,
-    graphemes: usize,
-    width: usize,
-    code: Option<String>,
-    script: Option<String>,
-}
-
-fn count(path: &Path, name: String, code: String, script: String) -> std::io::Result<Lang> {
-    let mut file = File::open(path)?;
-    let mut content = String::new();
-    file.read_to_string(&mut content)?;
-
-    let mut accu = String::new();
-    let mut note = false;
-    let mut preamble = false;
-
-    let mut buf = Vec::new();
-    let mut xml = quick_xml::Reader::from_str(&content);
-    loop {
-        match xml.read_event(&mut buf) {
-            Ok(Event::Start(ref e)) => match e.name() {
-                b"preamble" => {
-                    assert!(!preamble);
-                    preamble = true;
-                }
-                b"note" => {
-                    assert!(!note);
-                    note = true;
-                }
-                _ => {}
+use std::sync::Arc;
+use std::time::Instant;
+use std::time::SystemTime;
+use tracing::info;
+use tracing::warn;
+use tracing::Event;
+use tracing::Level;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::format;
+use tracing_subscriber::fmt::FmtContext;
+use tracing_subscriber::fmt::FormatEvent;
+use tracing_subscriber::fmt::FormatFields;
+use tracing_subscriber::registry::LookupSpan;
+use udhrlen::bench::Step;
+use udhrlen::bench::Timing;
+use udhrlen::fetch;
+use udhrlen::output;
+#[cfg(feature = "columnar")]
+use udhrlen::output::columnar::Container;
+use udhrlen::output::html::Coloring;
+use udhrlen::output::html::Document;
+use udhrlen::output::html::Palette;
+use udhrlen::output::html::Style;
+use udhrlen::output::json::Kind;
+use udhrlen::output::json::Syntax;
+use udhrlen::output::term::Terminal;
+use udhrlen::output::Numbers;
+use udhrlen::population::Populations;
+use udhrlen::serve::Site;
+use udhrlen::stats::OutlierMethod;
+use udhrlen::stats::StatsOptions;
+use udhrlen::tui::Explorer;
+use udhrlen::ArticleSet;
+use udhrlen::Baseline;
+use udhrlen::CaseMapping;
+use udhrlen::Compression;
+use udhrlen::CountOptions;
+use udhrlen::Counts;
+use udhrlen::EncodingScheme;
+use udhrlen::IndexEntry;
+use udhrlen::Lang;
+use udhrlen::Metric;
+use udhrlen::Metrics;
+use udhrlen::NormalizationForm;
+use udhrlen::Report;
+use udhrlen::Snapshot;
+use udhrlen::SortKey;
+use udhrlen::Tokenizer;
+use udhrlen::UdhrError;
+
+/// Measures the length of the Universal Declaration of Human Rights in
+/// various units across its translations.
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Log more to standard error: -v for the timings of reading, measuring
+    /// and writing, -vv for each file as well and -vvv for everything
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    verbose: u8,
+    /// Log only errors, leaving out the warnings about the translations and
+    /// the status messages of serve and fetch
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+}
+
+/// Writes the events like the rest of the messages to standard error, e.g.
+/// `udhrlen: warning: ...`.
+struct LogFormat;
+
+impl<S, N> FormatEvent<S, N> for LogFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &Event<'_>,
+    ) -> std::fmt::Result {
+        let level = match *event.metadata().level() {
+            Level::ERROR => "error",
+            Level::WARN => "warning",
+            Level::INFO => "info",
+            Level::DEBUG => "debug",
+            Level::TRACE => "trace",
+        };
+        write!(writer, "udhrlen: {}: ", level)?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// Prints `message` to standard error like the warnings, without the
+/// prefix of a log level, unless `-q` was given.
+fn status(message: std::fmt::Arguments) {
+    if tracing::enabled!(Level::WARN) {
+        eprintln!("{}", message);
+    }
+}
+
+/// Logs to standard error at the level of the `verbose` and `quiet` flags.
+fn init_logging(verbose: u8, quiet: bool) {
+    let level = match (quiet, verbose) {
+        (true, _) => Level::ERROR,
+        (false, 0) => Level::WARN,
+        (false, 1) => Level::INFO,
+        (false, 2) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+    tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .event_format(LogFormat)
+        .init();
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the metrics of each translation as tab-separated values
+    Count {
+        /// Directory containing index.xml and the udhr_*.xml or udhr_*.txt
+        /// files, or udhr_xml.zip or udhr_txt.zip [default: the corpus
+        /// downloaded by `fetch`]
+        #[arg(value_parser = corpus_path, conflicts_with_all = ["index", "data_dir"])]
+        corpus: Option<PathBuf>,
+        #[command(flatten)]
+        layout: Layout,
+        /// Measure the UTF-8 text files matching this glob pattern instead
+        /// of a corpus, each as a translation named after the file
+        #[arg(
+            long,
+            value_name = "GLOB",
+            conflicts_with_all = ["corpus", "index", "data_dir"]
+        )]
+        plain: Option<String>,
+        #[command(flatten)]
+        options: CountArgs,
+    },
+    /// Print a table of the metrics with summary statistics
+    Report {
+        /// Directory containing index.xml and the udhr_*.xml or udhr_*.txt
+        /// files, or udhr_xml.zip or udhr_txt.zip [default: the corpus
+        /// downloaded by `fetch`]
+        #[arg(value_parser = corpus_path, conflicts_with_all = ["index", "data_dir"])]
+        corpus: Option<PathBuf>,
+        #[command(flatten)]
+        layout: Layout,
+        /// Measure the UTF-8 text files matching this glob pattern instead
+        /// of a corpus, each as a translation named after the file
+        #[arg(
+            long,
+            value_name = "GLOB",
+            conflicts_with_all = ["corpus", "index", "data_dir"]
+        )]
+        plain: Option<String>,
+        /// Render the results saved with --save-snapshot instead of
+        /// measuring a corpus. The options that select what to measure don't
+        /// apply
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with_all = ["corpus", "index", "data_dir", "plain"]
+        )]
+        from_snapshot: Option<PathBuf>,
+        #[command(flatten)]
+        options: CountArgs,
+        #[command(flatten)]
+        report: Box<ReportArgs>,
+    },
+    /// Print the changes in metrics between two versions of the corpus,
+    /// including the added and removed translations
+    Diff {
+        /// Directory or zip archive of the old version of the corpus
+        #[arg(value_parser = corpus_path)]
+        old: PathBuf,
+        /// Directory or zip archive of the new version of the corpus
+        #[arg(value_parser = corpus_path)]
+        new: PathBuf,
+        #[command(flatten)]
+        options: CountArgs,
+    },
+    /// Print the throughput of the steps of measuring each translation in
+    /// MB/s as tab-separated values, with a total row
+    Bench {
+        /// Directory containing index.xml and the udhr_*.xml or udhr_*.txt
+        /// files, or udhr_xml.zip or udhr_txt.zip [default: the corpus
+        /// downloaded by `fetch`]
+        #[arg(value_parser = corpus_path, conflicts_with_all = ["index", "data_dir"])]
+        corpus: Option<PathBuf>,
+        #[command(flatten)]
+        layout: Layout,
+        /// Time each step this many times and report the fastest run
+        #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..))]
+        iterations: u32,
+        /// Benchmark only the translations with these codes, e.g.
+        /// fin,swe,jpn; a language code such as cmn selects all of its
+        /// translations
+        #[arg(long, value_delimiter = ',', value_name = "CODES")]
+        only: Vec<String>,
+        /// Benchmark only the translations in these ISO 15924 scripts, e.g.
+        /// Latn,Cyrl
+        #[arg(long = "script", value_delimiter = ',', value_name = "SCRIPTS")]
+        scripts: Vec<String>,
+    },
+    /// Serve the HTML report and the metrics as JSON over HTTP
+    Serve {
+        /// Directory containing index.xml and the udhr_*.xml or udhr_*.txt
+        /// files, or udhr_xml.zip or udhr_txt.zip [default: the corpus
+        /// downloaded by `fetch`]
+        #[arg(
+            long,
+            value_name = "DIR",
+            value_parser = corpus_path,
+            conflicts_with_all = ["index", "data_dir"]
+        )]
+        dir: Option<PathBuf>,
+        #[command(flatten)]
+        layout: Layout,
+        /// Port to listen on
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+        #[command(flatten)]
+        options: CountArgs,
+    },
+    /// Explore the metrics interactively in the terminal: scroll the list,
+    /// sort by any column, show the translations in one script and open
+    /// the details of a translation
+    Tui {
+        /// Directory containing index.xml and the udhr_*.xml or udhr_*.txt
+        /// files, or udhr_xml.zip or udhr_txt.zip [default: the corpus
+        /// downloaded by `fetch`]
+        #[arg(
+            long,
+            value_name = "DIR",
+            value_parser = corpus_path,
+            conflicts_with_all = ["index", "data_dir"]
+        )]
+        dir: Option<PathBuf>,
+        #[command(flatten)]
+        layout: Layout,
+        #[command(flatten)]
+        options: CountArgs,
+    },
+    /// Download and unpack the corpus from unicode.org
+    Fetch {
+        /// URL of the zipped XML corpus
+        #[arg(long, default_value = fetch::DEFAULT_URL)]
+        url: String,
+        /// Cache directory to unpack the corpus into
+        /// [default: $XDG_CACHE_HOME/udhrlen or ~/.cache/udhrlen]
+        #[arg(long)]
+        cache_dir: Option<PathBuf>,
+    },
+    /// Print the JSON Schema of the JSON output of `report`
+    Schema {
+        /// The schema of the output with --per-article
+        #[arg(long)]
+        per_article: bool,
+        /// The schema of the output with --detail
+        #[arg(long, value_enum, conflicts_with = "per_article")]
+        detail: Option<Detail>,
+    },
+    /// Print the completion script for a shell, e.g. for
+    /// `source <(udhrlen completions bash)`
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Where the index and the translation files of a corpus in another layout
+/// are.
+#[derive(Args)]
+struct Layout {
+    /// Measure the translations listed in this index.xml, or in the one
+    /// piped on standard input if -, instead of those of a corpus [default:
+    /// --data-dir/index.xml]
+    #[arg(long, value_name = "FILE", requires_if("-", "data_dir"))]
+    index: Option<PathBuf>,
+    /// Directory containing the udhr_*.xml or udhr_*.txt files of the
+    /// translations [default: the directory of --index]
+    #[arg(long, value_name = "DIR", value_parser = directory)]
+    data_dir: Option<PathBuf>,
+}
+
+impl Layout {
+    /// The corpus in this layout or, if neither the index nor the data
+    /// directory is given, `corpus`, falling back to the fetched one.
+    fn input(self, corpus: Option<PathBuf>) -> Result<Input, UdhrError> {
+        match (self.index, self.data_dir) {
+            (Some(index), Some(data_dir)) => Ok(Input::Indexed { index, data_dir }),
+            (Some(index), None) => {
+                let data_dir = match index.parent() {
+                    Some(dir) if dir != Path::new("") => dir.to_path_buf(),
+                    _ => PathBuf::from("."),
+                };
+                Ok(Input::Indexed { index, data_dir })
+            }
+            (None, Some(data_dir)) => Ok(Input::Corpus(data_dir)),
+            (None, None) => resolve_corpus(corpus).map(Input::Corpus),
+        }
+    }
+}
+
+/// Options that affect how the corpus is measured.
+#[derive(Args)]
+struct CountArgs {
+    /// Abort on the first missing or malformed translation file instead of
+    /// skipping it with a warning
+    #[arg(long)]
+    strict: bool,
+    /// Number of worker threads [default: one per CPU]
+    #[arg(short, long, value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+    /// Measure every translation instead of reusing the metrics cached in
+    /// the cache directory by earlier runs over the same files
+    #[arg(long)]
+    no_cache: bool,
+    /// Measure only these articles, e.g. 1 or 1,2,5-10
+    #[arg(long, value_name = "LIST")]
+    articles: Option<ArticleSet>,
+    /// Measure the preamble, too
+    #[arg(long)]
+    include_preamble: bool,
+    /// Measure the translators' notes, too
+    #[arg(long)]
+    include_notes: bool,
+    /// Also measure the encoded lengths in these normalization forms, e.g.
+    /// nfd,nfkc
+    #[arg(long, value_delimiter = ',', value_name = "FORMS")]
+    normalization: Vec<NormalizationForm>,
+    /// Also measure the encoded lengths after these full case mappings:
+    /// fold, upper, lower, nfkc_casefold
+    #[arg(long, value_delimiter = ',', value_name = "MAPPINGS")]
+    case_mapping: Vec<CaseMapping>,
+    /// Also measure the length in bytes in these encodings: scsu, bocu-1,
+    /// gb18030, cesu-8, mutf-8, utf-7, html (UTF-8 with markup escaped),
+    /// ncr (ASCII with numeric character references), json (a JSON string),
+    /// json-ascii (a JSON string with \uXXXX escapes), qp
+    /// (quoted-printable), mime-b, mime-q (RFC 2047 encoded-words), base64,
+    /// base64-utf16
+    #[arg(long, value_delimiter = ',', value_name = "ENCODINGS")]
+    encoding: Vec<EncodingScheme>,
+    /// Also measure the length in bytes in the legacy encoding of each
+    /// translation's language, such as Shift_JIS or windows-1252, and count
+    /// the characters it can't represent
+    #[arg(long)]
+    legacy: bool,
+    /// Also measure the UTF-8 length after compression, e.g. gzip,brotli:5,zstd:19
+    /// [default levels: gzip 6, brotli 11, zstd 3]
+    #[arg(long, value_delimiter = ',', value_name = "ALGORITHMS")]
+    compression: Vec<Compression>,
+    /// Also count the tokens with these byte-pair encoding vocabularies of
+    /// OpenAI's models: r50k_base, p50k_base, cl100k_base, o200k_base.
+    /// Requires building with the tokenizer feature
+    #[arg(long, value_delimiter = ',', value_name = "VOCABULARIES")]
+    tokenizer: Vec<Tokenizer>,
+    /// Also compute the order-0 Shannon entropy in bits per character and
+    /// bits per UTF-8 byte
+    #[arg(long)]
+    entropy: bool,
+    /// Measure the text exactly as distributed instead of normalizing it to
+    /// NFC, adding a column with the change in UTF-8 length that NFC would
+    /// make
+    #[arg(long)]
+    no_normalize: bool,
+    /// Measure the translations at this stage of review and above, from 1
+    /// to 5 [default: 4]
+    #[arg(long, value_name = "STAGE", value_parser = clap::value_parser!(u8).range(1..=5), conflicts_with = "stages")]
+    min_stage: Option<u8>,
+    /// Measure the translations at these stages, e.g. 3,4,5
+    #[arg(long, value_delimiter = ',', value_name = "STAGES", value_parser = clap::value_parser!(u8).range(1..=5))]
+    stages: Vec<u8>,
+    /// Measure only the translations with these codes, e.g. fin,swe,jpn; a
+    /// language code such as cmn selects all of its translations
+    #[arg(long, value_delimiter = ',', value_name = "CODES")]
+    only: Vec<String>,
+    /// Skip the translations with these codes
+    #[arg(long, value_delimiter = ',', value_name = "CODES")]
+    exclude: Vec<String>,
+    /// Measure only the translations in these ISO 15924 scripts, e.g.
+    /// Latn or Hani,Jpan,Kore
+    #[arg(long = "script", value_delimiter = ',', value_name = "SCRIPTS")]
+    scripts: Vec<String>,
+    /// How wide the characters of ambiguous East Asian Width are; wide adds
+    /// a column with the width in East Asian contexts
+    #[arg(long, value_enum, default_value_t = AmbiguousWidth::Narrow)]
+    eaw_ambiguous: AmbiguousWidth,
+    /// Also count the code points in each East Asian Width category:
+    /// neutral, narrow, wide, ambiguous, halfwidth and fullwidth
+    #[arg(long)]
+    eaw_breakdown: bool,
+    /// Also count the code points in each major general category: letters,
+    /// marks, numbers, punctuation, symbols, separators and other
+    #[arg(long)]
+    category_breakdown: bool,
+    /// Also count the combining marks (Mn, Mc and Me), which make the
+    /// grapheme cluster count differ from the UTF-32 length, and the
+    /// supplementary-plane code points, which make the UTF-16 length differ
+    #[arg(long)]
+    composition: bool,
+    /// Also count the script runs (UAX #24) and the code points in scripts
+    /// other than the declared one, warning about the translations with
+    /// more than 5% of them
+    #[arg(long)]
+    script_runs: bool,
+    /// Also count the distinct code points and grapheme clusters, a proxy
+    /// for the size of the alphabet, syllabary or logographic repertoire
+    #[arg(long)]
+    distinct: bool,
+    /// Also measure the longest grapheme cluster in code points and in
+    /// UTF-8 bytes
+    #[arg(long)]
+    longest_grapheme: bool,
+    /// Also count the emoji (Extended_Pictographic) code points, zero width
+    /// joiners and variation selectors
+    #[arg(long)]
+    emoji: bool,
+    /// Also compute the percentage of strongly right-to-left code points,
+    /// adding a column with the dominant direction
+    #[arg(long)]
+    bidi: bool,
+    /// Also count the grapheme clusters and the width with ICU4X, warning
+    /// about the translations for which it disagrees with the default counts
+    #[arg(long)]
+    icu4x: bool,
+    /// Output only these metrics, in this order, e.g. utf8,utf16,egc
+    /// [default: all measured metrics]
+    #[arg(long, value_delimiter = ',', value_name = "METRICS")]
+    columns: Vec<Metric>,
+}
+
+impl CountArgs {
+    /// The metrics to output, which must all be measured.
+    fn columns(&self) -> Result<Vec<Metric>, UdhrError> {
+        self.select_columns(self.count_options().columns())
+    }
+
+    /// The requested columns, or the `measured` ones if none were
+    /// requested.
+    fn select_columns(&self, measured: Vec<Metric>) -> Result<Vec<Metric>, UdhrError> {
+        if self.columns.is_empty() {
+            return Ok(measured);
+        }
+        for &metric in self.columns.iter() {
+            if !metric.is_measured_in(&measured) {
+                return Err(UdhrError::NotMeasured {
+                    metric: metric.name(),
+                });
+            }
+        }
+        Ok(self.columns.clone())
+    }
+
+    fn count_options(&self) -> CountOptions {
+        CountOptions {
+            strict: self.strict,
+            jobs: self.jobs.map(usize::from),
+            cache: if self.no_cache {
+                None
+            } else {
+                fetch::cache_dir().map(|dir| dir.join("metrics"))
             },
-            Ok(Event::End(ref e)) => match e.name() {
-                b"preamble" => {
-                    assert!(preamble);
-                    preamble = false;
-                }
-                b"note" => {
-                    assert!(note);
-                    note = false;
-                }
-                _ => {}
+            articles: self.articles.clone(),
+            include_preamble: self.include_preamble,
+            include_notes: self.include_notes,
+            normalizations: self.normalization.clone(),
+            case_mappings: self.case_mapping.clone(),
+            encodings: self.encoding.clone(),
+            legacy: self.legacy,
+            compressions: self.compression.clone(),
+            tokenizers: self.tokenizer.clone(),
+            entropy: self.entropy,
+            no_normalize: self.no_normalize,
+            ambiguous_wide: self.eaw_ambiguous == AmbiguousWidth::Wide,
+            eaw_breakdown: self.eaw_breakdown,
+            category_breakdown: self.category_breakdown,
+            composition: self.composition,
+            script_runs: self.script_runs,
+            distinct: self.distinct,
+            longest_grapheme: self.longest_grapheme,
+            emoji: self.emoji,
+            bidi: self.bidi,
+            icu4x: self.icu4x,
+            stages: match self.min_stage {
+                Some(min) => (min..=5).collect(),
+                None => self.stages.clone(),
             },
-            Ok(Event::Text(e)) => {
-                if !note && !preamble {
-                    let text = e.unescape_and_decode(&xml).unwrap();
-                    if !text.as_bytes().iter().all(u8::is_ascii_whitespace) {
-                        accu.push_str(&text);
-                    }
+            only: self.only.clone(),
+            exclude: self.exclude.clone(),
+            scripts: self.scripts.clone(),
+            ..CountOptions::default()
+        }
+    }
+}
+
+/// Options that affect how the measurements are presented.
+#[derive(Args)]
+struct ReportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = Format::Html)]
+    format: Format,
+    /// Report each article separately instead of whole translations
+    #[arg(long)]
+    per_article: bool,
+    /// With HTML output, emit a complete document in which the tables can
+    /// be sorted by clicking a column heading and filtered with a text box
+    #[arg(long)]
+    interactive: bool,
+    /// With HTML output, emit a complete styled document that says which
+    /// corpus was measured and when instead of a fragment
+    #[arg(long)]
+    standalone: bool,
+    /// With HTML output, write the stylesheet into FILE and link to it
+    /// instead of embedding it, e.g. for pages whose Content Security
+    /// Policy doesn't allow inline styles
+    #[arg(long, value_name = "FILE")]
+    stylesheet: Option<PathBuf>,
+    /// Colors of the HTML cells by deviation: default (red above the
+    /// baseline, green below), viridis, colorblind (orange and blue) or none
+    #[arg(long, default_value = "default")]
+    palette: Palette,
+    /// When to color the cells with --format term: auto (if standard output
+    /// is a terminal and NO_COLOR isn't set), always or never
+    #[arg(long, value_enum, default_value_t = ColorWhen::Auto, value_name = "WHEN")]
+    color: ColorWhen,
+    /// The exponent of the magnitude of the deviations when coloring the
+    /// HTML cells: below 1 makes small deviations stand out, above 1 only
+    /// the large ones
+    #[arg(long, value_name = "GAMMA", default_value = "0.75", value_parser = gamma)]
+    color_gamma: f64,
+    /// The saturation in percent of the HTML cells that deviate the most
+    #[arg(long, value_name = "PERCENT", default_value = "100", value_parser = saturation)]
+    color_max_saturation: f64,
+    /// Give the HTML cells that deviate by PERCENT or more the full
+    /// saturation and scale the smaller deviations up to it
+    #[arg(long, value_name = "PERCENT", value_parser = clip)]
+    color_clip: Option<f64>,
+    /// The locale whose separators to write the numbers in the HTML cells
+    /// with, e.g. de or en-IN, or none for plain digits
+    #[arg(long, default_value = "en")]
+    locale: Numbers,
+    /// Also save the measured translations in FILE for rendering them again
+    /// with --from-snapshot
+    #[arg(long, value_name = "FILE")]
+    save_snapshot: Option<PathBuf>,
+    /// Instead of writing the table to standard output, write a site into
+    /// DIR: index.html with the table and a page per translation with its
+    /// category breakdown, normalization forms and per-article counts
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["format", "detail", "per_article"])]
+    out: Option<PathBuf>,
+    /// Write the report into FILE instead of standard output, replacing it
+    /// only once the report is complete; - for standard output
+    #[arg(short, long, value_name = "FILE", conflicts_with = "out")]
+    output: Option<PathBuf>,
+    /// Write the report in several formats from one measurement, as a
+    /// comma-separated list of FORMAT=FILE, e.g.
+    /// html=report.html,csv=report.csv,json=report.json
+    #[arg(
+        long,
+        value_delimiter = ',',
+        value_name = "FORMAT=FILE",
+        value_parser = emission,
+        conflicts_with_all = ["format", "output", "out"]
+    )]
+    emit: Vec<(Format, PathBuf)>,
+    /// Render the table with the Tera template in FILE instead of in one of
+    /// the formats, escaping the values for HTML if FILE ends in .html
+    #[arg(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = ["format", "emit", "out", "detail", "per_article"]
+    )]
+    template: Option<PathBuf>,
+    /// Instead of the table, emit a breakdown of each translation: blocks
+    /// (the number of code points in each Unicode block and plane) or
+    /// clusters (the number of grapheme clusters of each length in code
+    /// points)
+    #[arg(long, value_enum, value_name = "KIND", conflicts_with = "per_article")]
+    detail: Option<Detail>,
+    /// What the deviations and colors in the HTML table are relative to:
+    /// median, mean, utf8 (the translation's own UTF-8 length) or
+    /// lang:<code> (e.g. lang:eng)
+    #[arg(long, default_value = "median")]
+    baseline: Baseline,
+    /// Compare every translation against the one with this code, e.g. eng;
+    /// short for --baseline lang:<CODE>
+    #[arg(long, value_name = "CODE", conflicts_with = "baseline")]
+    reference: Option<String>,
+    /// Order the translations by this metric (e.g. utf8, utf16, utf32, egc,
+    /// eaw) or by name or script, breaking ties by name [default: utf8]
+    #[arg(long, value_name = "KEY")]
+    sort_by: Option<SortKey>,
+    /// Sort in descending order
+    #[arg(long)]
+    desc: bool,
+    /// Add these percentiles to the summary statistics, e.g. 5,95
+    #[arg(long, value_delimiter = ',', value_name = "LIST", value_parser = percentile)]
+    percentiles: Vec<f64>,
+    /// How to detect the outliers excluded from "Max (ignoring outliers)":
+    /// iqr (beyond 1.5 times the interquartile range), zscore (more than
+    /// three standard deviations from the mean) or max (the largest value)
+    #[arg(long, default_value = "iqr", value_name = "METHOD")]
+    outlier_method: OutlierMethod,
+    /// Add population-weighted mean and median rows using the speaker
+    /// populations in FILE: a JSON object or CSV lines of code,population,
+    /// keyed by translation code (cmn_hans) or language code (cmn)
+    #[arg(long, value_name = "FILE")]
+    populations: Option<PathBuf>,
+    /// Name the measured encoding that is the smallest for each translation
+    /// and count how many translations each encoding wins
+    #[arg(long)]
+    smallest_encoding: bool,
+    /// List only the N translations with the largest values of the --by
+    /// metric; the summary statistics still cover all of them
+    #[arg(long, value_name = "N", conflicts_with = "bottom")]
+    top: Option<usize>,
+    /// List only the N translations with the smallest values of the --by
+    /// metric
+    #[arg(long, value_name = "N")]
+    bottom: Option<usize>,
+    /// The metric for --top and --bottom
+    #[arg(long, value_name = "METRIC", default_value = "utf8")]
+    by: Metric,
+    /// The chart with --format vega
+    #[arg(long, value_enum, default_value_t = Chart::Bars)]
+    chart: Chart,
+    /// The metric that bar charts, histograms and box plots show
+    #[arg(long, value_name = "METRIC", default_value = "utf8")]
+    metric: Metric,
+    /// The number of bins of histograms [default: by Sturges' rule]
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    bins: Option<u16>,
+    /// The metric on the horizontal axis of scatter plots
+    #[arg(long, value_name = "METRIC", default_value = "utf8")]
+    x: Metric,
+    /// The metric on the vertical axis of scatter plots
+    #[arg(long, value_name = "METRIC", default_value = "egc")]
+    y: Metric,
+    /// Cluster the translations, adding the median of each group
+    #[arg(long, value_enum)]
+    group_by: Option<GroupBy>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Detail {
+    /// Code points per Unicode block and plane
+    Blocks,
+    /// Grapheme clusters per length
+    Clusters,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum AmbiguousWidth {
+    /// One column, as in Western contexts
+    Narrow,
+    /// Two columns, as in East Asian contexts
+    Wide,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum GroupBy {
+    /// ISO 15924 script
+    Script,
+}
+
+impl ReportArgs {
+    /// Applies the presentation options to `report`.
+    fn apply(&self, report: &mut Report, stats_options: &StatsOptions) {
+        report.set_baseline(self.baseline());
+        report.set_stats_options(stats_options.clone());
+        if self.sort_by.is_some() || self.desc {
+            report.sort(
+                self.sort_by.unwrap_or(SortKey::Metric(Metric::Utf8)),
+                self.desc,
+            );
+        }
+        if self.smallest_encoding {
+            report.find_smallest_encodings();
+        }
+        if let Some(n) = self.top {
+            report.retain_extremes(self.by, n, true);
+        }
+        if let Some(n) = self.bottom {
+            report.retain_extremes(self.by, n, false);
+        }
+        if let Some(GroupBy::Script) = self.group_by {
+            report.group_by_script();
+        }
+    }
+
+    /// The statistics options, reading the populations if given.
+    fn stats_options(&self) -> Result<StatsOptions, UdhrError> {
+        let populations = match &self.populations {
+            Some(path) => Some(Arc::new(Populations::load(path)?)),
+            None => None,
+        };
+        Ok(StatsOptions {
+            percentiles: self.percentiles.clone(),
+            outlier_method: self.outlier_method,
+            populations,
+        })
+    }
+
+    /// The document to wrap the HTML tables into, described by
+    /// `description` if standalone.
+    fn document(&self, description: Option<String>) -> Document {
+        Document {
+            description,
+            interactive: self.interactive,
+            stylesheet: self
+                .stylesheet
+                .as_ref()
+                .map(|path| path.display().to_string()),
+            style: self.style(),
+        }
+    }
+
+    fn style(&self) -> Style {
+        Style {
+            coloring: self.coloring(),
+            numbers: self.locale,
+        }
+    }
+
+    fn coloring(&self) -> Coloring {
+        Coloring {
+            palette: self.palette,
+            gamma: self.color_gamma,
+            max_saturation: self.color_max_saturation,
+            clip: self.color_clip,
+        }
+    }
+
+    /// The metrics that the chart in `format` shows.
+    fn chart_metrics(&self, format: Format) -> Vec<Metric> {
+        match (format, self.chart) {
+            (
+                Format::SvgBars
+                | Format::SvgHistogram
+                | Format::SvgBoxplot
+                | Format::PngBars
+                | Format::PngHistogram
+                | Format::PngBoxplot,
+                _,
+            )
+            | (Format::Vega, Chart::Bars | Chart::Histogram | Chart::Boxplot) => vec![self.metric],
+            (Format::SvgScatter | Format::PngScatter, _) | (Format::Vega, Chart::Scatter) => {
+                vec![self.x, self.y]
+            }
+            (
+                Format::Html
+                | Format::Json
+                | Format::Yaml
+                | Format::Toml
+                | Format::Xml
+                | Format::Csv
+                | Format::Term
+                | Format::Latex
+                | Format::Mediawiki
+                | Format::Rst
+                | Format::Org
+                | Format::Sqlite
+                | Format::Parquet
+                | Format::Arrow
+                | Format::SvgHeatmap
+                | Format::PngHeatmap,
+                _,
+            ) => Vec::new(),
+        }
+    }
+
+    /// How to write for the terminal if `to_terminal`, i.e. the output is
+    /// standard output and it is a terminal.
+    fn terminal(&self, to_terminal: bool) -> Terminal {
+        let colors = match self.color {
+            ColorWhen::Auto => {
+                to_terminal && std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+            }
+            ColorWhen::Always => true,
+            ColorWhen::Never => false,
+        };
+        Terminal {
+            colors,
+            width: to_terminal.then(output::term::stdout_width),
+        }
+    }
+
+    fn baseline(&self) -> Baseline {
+        match &self.reference {
+            Some(code) => Baseline::Lang(code.clone()),
+            None => self.baseline.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// HTML table fragment
+    Html,
+    /// JSON document with the metrics and summary statistics
+    Json,
+    /// YAML document with the metrics and summary statistics, in the
+    /// structure of the JSON output
+    Yaml,
+    /// TOML document with the metrics and summary statistics, in the
+    /// structure of the JSON output
+    Toml,
+    /// XML document with the metrics and summary statistics, valid against
+    /// schema/report.xsd
+    Xml,
+    /// Comma-separated values with one row per translation (and article)
+    Csv,
+    /// Aligned table for the terminal with the cells colored like in the
+    /// HTML table and the names truncated to fit
+    Term,
+    /// LaTeX tabular in the style of booktabs with the cells shaded like in
+    /// the HTML table
+    Latex,
+    /// MediaWiki wikitable, sortable, with the cells shaded like in the HTML
+    /// table
+    Mediawiki,
+    /// reStructuredText grid table
+    Rst,
+    /// Emacs Org table
+    Org,
+    /// SQLite database with tables of the translations, their metrics and,
+    /// with --per-article, the metrics of the articles; needs --output
+    Sqlite,
+    /// Apache Parquet file with the columns of the CSV output; needs the
+    /// columnar feature
+    Parquet,
+    /// Arrow IPC file (Feather version 2) with the columns of the CSV
+    /// output; needs the columnar feature
+    Arrow,
+    /// SVG horizontal bar chart of --metric with a bar per translation,
+    /// colored by script
+    SvgBars,
+    /// SVG scatter plot of --y against --x with a point per translation,
+    /// colored by script
+    SvgScatter,
+    /// SVG histogram of --metric with --bins bins
+    SvgHistogram,
+    /// SVG box plot of --metric for each script, with the outliers as
+    /// points
+    SvgBoxplot,
+    /// SVG heatmap of the translations by the columns, colored by the
+    /// deviation from the baseline like the HTML table
+    SvgHeatmap,
+    /// PNG image of the svg-bars chart
+    PngBars,
+    /// PNG image of the svg-scatter chart
+    PngScatter,
+    /// PNG image of the svg-histogram chart
+    PngHistogram,
+    /// PNG image of the svg-boxplot chart
+    PngBoxplot,
+    /// PNG image of the svg-heatmap chart
+    PngHeatmap,
+    /// Vega-Lite specification of the --chart with the data inlined
+    Vega,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Chart {
+    /// Bar chart of --metric
+    Bars,
+    /// Scatter plot of --y against --x
+    Scatter,
+    /// Histogram of --metric
+    Histogram,
+    /// Box plot of --metric for each script
+    Boxplot,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ColorWhen {
+    Auto,
+    Always,
+    Never,
+}
+
+impl Format {
+    /// Whether this is a chart rather than a table.
+    fn is_chart(self) -> bool {
+        matches!(
+            self,
+            Format::SvgBars
+                | Format::SvgScatter
+                | Format::SvgHistogram
+                | Format::SvgBoxplot
+                | Format::SvgHeatmap
+                | Format::PngBars
+                | Format::PngScatter
+                | Format::PngHistogram
+                | Format::PngBoxplot
+                | Format::PngHeatmap
+                | Format::Vega
+        )
+    }
+
+    /// The SVG chart that this PNG chart is rasterized from.
+    fn svg(self) -> Option<Format> {
+        match self {
+            Format::PngBars => Some(Format::SvgBars),
+            Format::PngScatter => Some(Format::SvgScatter),
+            Format::PngHistogram => Some(Format::SvgHistogram),
+            Format::PngBoxplot => Some(Format::SvgBoxplot),
+            Format::PngHeatmap => Some(Format::SvgHeatmap),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map_or_else(String::new, |value| value.get_name().to_string())
+    }
+}
+
+fn corpus_path(arg: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(arg);
+    if path.is_file() {
+        return Ok(path);
+    }
+    if !path.is_dir() {
+        return Err(format!(
+            "{} is not a directory or a zip archive",
+            path.display()
+        ));
+    }
+    if !path.join(Path::new("index.xml")).is_file() {
+        return Err(format!("{} does not contain index.xml", path.display()));
+    }
+    Ok(path)
+}
+
+fn directory(arg: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(arg);
+    if path.is_dir() {
+        Ok(path)
+    } else {
+        Err(format!("{} is not a directory", path.display()))
+    }
+}
+
+fn percentile(arg: &str) -> Result<f64, String> {
+    match arg.trim().parse::<f64>() {
+        Ok(p) if p > 0.0 && p < 100.0 => Ok(p),
+        _ => Err(format!(
+            "{} is not a percentile between 0 and 100",
+            arg.trim()
+        )),
+    }
+}
+
+fn emission(arg: &str) -> Result<(Format, PathBuf), String> {
+    let (format, path) = arg
+        .split_once('=')
+        .ok_or_else(|| format!("{} is not FORMAT=FILE", arg.trim()))?;
+    let format = Format::from_str(format.trim(), true)?;
+    Ok((format, PathBuf::from(path.trim())))
+}
+
+fn gamma(arg: &str) -> Result<f64, String> {
+    match arg.trim().parse::<f64>() {
+        Ok(gamma) if gamma > 0.0 && gamma.is_finite() => Ok(gamma),
+        _ => Err(format!("{} is not a positive exponent", arg.trim())),
+    }
+}
+
+fn saturation(arg: &str) -> Result<f64, String> {
+    match arg.trim().parse::<f64>() {
+        Ok(s) if (0.0..=100.0).contains(&s) => Ok(s),
+        _ => Err(format!(
+            "{} is not a saturation between 0 and 100",
+            arg.trim()
+        )),
+    }
+}
+
+fn clip(arg: &str) -> Result<f64, String> {
+    match arg.trim().parse::<f64>() {
+        Ok(clip) if clip > 0.0 && clip.is_finite() => Ok(clip),
+        _ => Err(format!("{} is not a positive percentage", arg.trim())),
+    }
+}
+
+fn default_cache_dir() -> Result<PathBuf, UdhrError> {
+    fetch::cache_dir().ok_or(UdhrError::NoCacheDir)
+}
+
+/// Falls back to the corpus in the default cache directory.
+fn resolve_corpus(corpus: Option<PathBuf>) -> Result<PathBuf, UdhrError> {
+    if let Some(corpus) = corpus {
+        return Ok(corpus);
+    }
+    fetch::corpus_dir(&default_cache_dir()?).ok_or(UdhrError::NoCorpus)
+}
+
+/// The translations to measure.
+enum Input {
+    /// A corpus directory or zip archive
+    Corpus(PathBuf),
+    /// The translations listed in an index file, or in the index on
+    /// standard input if the path is `-`, with their files in a directory
+    Indexed { index: PathBuf, data_dir: PathBuf },
+    /// The plain text files matching a glob pattern
+    Plain(String),
+    /// The translations saved in a snapshot
+    Snapshot(PathBuf),
+}
+
+impl Input {
+    /// Measures the plain text files if `plain` is given and the corpus
+    /// otherwise.
+    fn resolve(
+        corpus: Option<PathBuf>,
+        layout: Layout,
+        plain: Option<String>,
+    ) -> Result<Input, UdhrError> {
+        match plain {
+            Some(pattern) => Ok(Input::Plain(pattern)),
+            None => layout.input(corpus),
+        }
+    }
+
+    /// What the translations are, for the reader of a report.
+    fn describe(&self) -> String {
+        match self {
+            Input::Corpus(path) => {
+                let version = fetch::cache_dir()
+                    .filter(|cache| path.starts_with(cache))
+                    .and_then(|cache| fetch::DatasetVersion::load(&cache).ok());
+                match version {
+                    Some(version) => format!(
+                        "{} (last modified: {})",
+                        version.url,
+                        version.last_modified.as_deref().unwrap_or("unknown")
+                    ),
+                    None => path.display().to_string(),
                 }
             }
-            Ok(Event::Eof) => break,
-            Ok(_) => {}
-            Err(e) => panic!("Error at position {}: {:?}", xml.buffer_position(), e),
+            Input::Indexed { index, data_dir } if index == Path::new("-") => {
+                format!("{} as listed on standard input", data_dir.display())
+            }
+            Input::Indexed { index, data_dir } => {
+                format!("{} as listed in {}", data_dir.display(), index.display())
+            }
+            Input::Plain(pattern) => format!("the files matching {}", pattern),
+            Input::Snapshot(path) => format!("the snapshot {}", path.display()),
         }
     }
 
-    let dhr = accu.nfc().collect::<String>();
+    fn count(&self, options: &CountOptions) -> Result<Counts, UdhrError> {
+        match self {
+            Input::Corpus(corpus) => udhrlen::count_corpus(corpus, options),
+            Input::Indexed { index, data_dir } => {
+                udhrlen::count_corpus_with_index(data_dir, read_index(index)?, options)
+            }
+            Input::Plain(pattern) => udhrlen::count_plain(pattern, options),
+            Input::Snapshot(path) => Snapshot::load(path).map(Counts::from),
+        }
+    }
+}
+
+/// Reads and parses the index file at `path` or, if it is `-`, standard
+/// input.
+fn read_index(path: &Path) -> Result<Vec<IndexEntry>, UdhrError> {
+    let (text, display) = if path == Path::new("-") {
+        let mut text = String::new();
+        std::io::stdin()
+            .read_to_string(&mut text)
+            .map_err(UdhrError::io("standard input"))?;
+        (text, "standard input".to_string())
+    } else {
+        let text = std::fs::read_to_string(path).map_err(UdhrError::io(path.display()))?;
+        (text, path.display().to_string())
+    };
+    udhrlen::corpus::parse_index(&text).map_err(UdhrError::parse(display))
+}
 
-    Ok(Lang {
-        name: name,
-        utf8: dhr.len(),
-        utf16: dhr.encode_utf16().count(),
-        utf32: dhr.chars().count(),
-        graphemes: Graphemes::new(&dhr).count(),
-        width: dhr.width(),
-        code: Some(code),
-        script: Some(script),
-    })
+fn stdout_error(error: std::io::Error) -> UdhrError {
+    UdhrError::io("standard output")(error)
 }
 
-fn colorize(baseline_result: usize, comparison_result: usize) -> (usize, f64) {
-    let (hue, factor) = if baseline_result < comparison_result {
-        (0, (baseline_result as f64) / (comparison_result as f64))
+fn bench(input: &Input, options: &CountOptions, iterations: u32) -> Result<(), UdhrError> {
+    let timings = match input {
+        Input::Corpus(corpus) => udhrlen::bench::run(corpus, options, iterations)?,
+        Input::Indexed { index, data_dir } => {
+            udhrlen::bench::run_with_index(data_dir, read_index(index)?, options, iterations)?
+        }
+        // Given by `Layout::input`
+        Input::Plain(_) | Input::Snapshot(_) => unreachable!("benchmark of a non-corpus"),
+    };
+    write_bench(&mut std::io::stdout().lock(), &timings).map_err(stdout_error)
+}
+
+fn write_bench<W: Write>(out: &mut W, timings: &[Timing]) -> std::io::Result<()> {
+    write!(out, "code\tname\tfile_bytes\ttext_bytes")?;
+    for step in Step::ALL.iter() {
+        write!(out, "\t{}", step.name())?;
+    }
+    writeln!(out)?;
+    for timing in timings
+        .iter()
+        .chain(std::iter::once(&Timing::total(timings)))
+    {
+        write!(
+            out,
+            "{}\t{}\t{}\t{}",
+            timing.code, timing.name, timing.file_bytes, timing.text_bytes
+        )?;
+        for &step in Step::ALL.iter() {
+            match timing.throughput(step) {
+                Some(throughput) => write!(out, "\t{:.1}", throughput)?,
+                None => write!(out, "\t")?,
+            }
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn serve(input: &Input, address: &str, options: &CountArgs) -> Result<(), UdhrError> {
+    let count_options = options.count_options();
+    let measured = count_options.columns();
+    let columns = options.select_columns(measured.clone())?;
+    let counts = input.count(&count_options)?;
+    warn_about(&counts);
+    let site = Site::new(counts.langs, columns, measured);
+    status(format_args!("Serving the report on http://{}/", address));
+    udhrlen::serve::serve(address, &site)
+}
+
+fn tui(input: &Input, options: &CountArgs) -> Result<(), UdhrError> {
+    let count_options = options.count_options();
+    let measured = count_options.columns();
+    let columns = options.select_columns(measured.clone())?;
+    let counts = input.count(&count_options)?;
+    warn_about(&counts);
+    let mut explorer = Explorer::new(counts.langs, columns, measured);
+    udhrlen::tui::explore(&mut explorer)
+}
+
+fn fetch(url: &str, cache_dir: Option<PathBuf>) -> Result<(), UdhrError> {
+    let cache = match cache_dir {
+        Some(cache) => cache,
+        None => default_cache_dir()?,
+    };
+    let dir = fetch::fetch(url, &cache)?;
+    let version = fetch::DatasetVersion::load(&cache)?;
+    status(format_args!(
+        "Fetched {} (last modified: {})",
+        version.url,
+        version.last_modified.as_deref().unwrap_or("unknown")
+    ));
+    writeln!(std::io::stdout(), "{}", dir.display()).map_err(stdout_error)
+}
+
+fn schema(per_article: bool, detail: Option<Detail>) -> Result<(), UdhrError> {
+    let kind = match (detail, per_article) {
+        (Some(Detail::Blocks), _) => Kind::Blocks,
+        (Some(Detail::Clusters), _) => Kind::Clusters,
+        (None, true) => Kind::Articles,
+        (None, false) => Kind::Report,
+    };
+    let mut out = std::io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, &output::json::schema(kind))
+        .map_err(std::io::Error::from)
+        .and_then(|()| writeln!(out))
+        .map_err(stdout_error)
+}
+
+fn completions(shell: Shell) -> Result<(), UdhrError> {
+    // Generating panics on write errors, so the script is written into a
+    // buffer first.
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "udhrlen", &mut script);
+    std::io::stdout().write_all(&script).map_err(stdout_error)
+}
+
+/// The share of code points in other scripts above which a translation is
+/// reported as mixed-script
+const MIXED_SCRIPT_THRESHOLD: f64 = 0.05;
+
+/// Warns about the skipped translations, about the translations with much
+/// text in other scripts than the declared one and about the translations
+/// whose counts ICU4X disagrees with.
+fn warn_about(counts: &Counts) {
+    for error in counts.skipped.iter() {
+        warn!("skipping {}", error);
+    }
+    for lang in counts
+        .langs
+        .iter()
+        .filter(|l| l.metrics.is_mixed_script(MIXED_SCRIPT_THRESHOLD))
+    {
+        let foreign = lang.metrics.script_runs.unwrap_or_default().foreign;
+        warn!(
+            "{} ({}) has {} of {} code points in scripts other than {}",
+            lang.name,
+            lang.code.as_deref().unwrap_or(""),
+            foreign,
+            lang.metrics.utf32,
+            lang.script.as_deref().unwrap_or("")
+        );
+    }
+    for lang in counts
+        .langs
+        .iter()
+        .filter(|l| !l.metrics.suspicious.is_empty())
+    {
+        let suspicious = lang.metrics.suspicious;
+        warn!(
+            "{} ({}) has {} replacement characters, {} controls and {} private-use characters",
+            lang.name,
+            lang.code.as_deref().unwrap_or(""),
+            suspicious.replacement,
+            suspicious.controls,
+            suspicious.private_use
+        );
+    }
+    for lang in counts.langs.iter().filter(|l| l.metrics.non_nfc > 0) {
+        warn!(
+            "{} ({}) isn't in NFC: normalizing changes {} code points",
+            lang.name,
+            lang.code.as_deref().unwrap_or(""),
+            lang.metrics.non_nfc
+        );
+    }
+    for lang in counts.langs.iter().filter(|l| l.metrics.icu4x_disagrees()) {
+        let (m, icu4x) = (&lang.metrics, lang.metrics.icu4x.unwrap_or_default());
+        warn!(
+            "ICU4X disagrees on {} ({}): {} vs. {} grapheme clusters, width {} vs. {}",
+            lang.name,
+            lang.code.as_deref().unwrap_or(""),
+            m.graphemes,
+            icu4x.graphemes,
+            m.width,
+            icu4x.width
+        );
+    }
+}
+
+/// Measures the corpus, warning about skipped translations.
+fn count_langs(input: &Input, options: &CountArgs) -> Result<Vec<Lang>, UdhrError> {
+    let counts = input.count(&options.count_options())?;
+    warn_about(&counts);
+    Ok(counts.langs)
+}
+
+/// The column heading of `metric` in tab-separated output.
+fn tsv_heading(metric: Metric) -> String {
+    match metric {
+        Metric::Graphemes => "egc".to_string(),
+        Metric::Width => "eaw".to_string(),
+        Metric::WidthCjk => "eaw_cjk".to_string(),
+        _ => metric.name(),
+    }
+}
+
+fn write_headings<W: Write>(out: &mut W, columns: &[Metric]) -> std::io::Result<()> {
+    for &metric in columns {
+        write!(out, "\t{}", tsv_heading(metric))?;
+    }
+    writeln!(out)
+}
+
+fn write_count<W: Write>(out: &mut W, langs: &[Lang], columns: &[Metric]) -> std::io::Result<()> {
+    let direction = columns.contains(&Metric::RtlPercent);
+    write!(out, "code\tname\tscript\tstage")?;
+    for &metric in columns {
+        write!(out, "\t{}", tsv_heading(metric))?;
+    }
+    if direction {
+        write!(out, "\tdirection")?;
+    }
+    writeln!(out)?;
+    for lang in langs {
+        write!(
+            out,
+            "{}\t{}\t{}\t{}",
+            lang.code.as_deref().unwrap_or(""),
+            lang.name,
+            lang.script.as_deref().unwrap_or(""),
+            lang.stage.map_or(String::new(), |s| s.to_string())
+        )?;
+        for &metric in columns {
+            match metric.value(&lang.metrics) {
+                Some(value) => write!(out, "\t{}", metric.format(value))?,
+                None => write!(out, "\t")?,
+            }
+        }
+        if direction {
+            let bidi = lang.metrics.bidi.map(|b| b.direction());
+            write!(out, "\t{}", bidi.unwrap_or(""))?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn count(input: &Input, options: &CountArgs) -> Result<(), UdhrError> {
+    let columns = options.columns()?;
+    let langs = count_langs(input, options)?;
+    write_count(&mut std::io::stdout().lock(), &langs, &columns).map_err(stdout_error)
+}
+
+/// Writes the difference between `new` and `old`, either of which may be
+/// missing, in `metric`.
+fn write_delta<W: Write>(
+    out: &mut W,
+    metric: Metric,
+    new: Option<&Metrics>,
+    old: Option<&Metrics>,
+) -> std::io::Result<()> {
+    let value = |metrics: Option<&Metrics>| match metrics {
+        Some(metrics) => metric.value(metrics),
+        None => Some(0.0),
+    };
+    match (value(new), value(old)) {
+        (Some(new), Some(old)) if metric.is_count() => {
+            write!(out, "\t{:+}", new as i64 - old as i64)
+        }
+        (Some(new), Some(old)) => write!(out, "\t{:+.2}", new - old),
+        _ => write!(out, "\t"),
+    }
+}
+
+/// Writes a row for each translation that was added, removed or changed
+/// between `old_langs` and `new_langs` with the changes in the metrics. The
+/// metrics of added and removed translations change from and to zero.
+fn write_diff<W: Write>(
+    out: &mut W,
+    old_langs: &[Lang],
+    new_langs: &[Lang],
+    columns: &[Metric],
+) -> std::io::Result<()> {
+    write!(out, "code\tname\tchange")?;
+    write_headings(out, columns)?;
+    fn find<'a>(langs: &'a [Lang], code: &Option<String>) -> Option<&'a Metrics> {
+        langs.iter().find(|l| l.code == *code).map(|l| &l.metrics)
+    }
+    let added_or_changed = new_langs
+        .iter()
+        .map(|l| (l, Some(&l.metrics), find(old_langs, &l.code)));
+    let removed = old_langs
+        .iter()
+        .filter(|l| find(new_langs, &l.code).is_none())
+        .map(|l| (l, None, Some(&l.metrics)));
+    for (lang, new, old) in added_or_changed.chain(removed) {
+        let change = match (new, old) {
+            (Some(new), Some(old)) if new == old => continue,
+            (Some(_), Some(_)) => "changed",
+            (Some(_), None) => "added",
+            _ => "removed",
+        };
+        write!(
+            out,
+            "{}\t{}\t{}",
+            lang.code.as_deref().unwrap_or(""),
+            lang.name,
+            change
+        )?;
+        for &metric in columns {
+            write_delta(out, metric, new, old)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+fn diff(old: &Path, new: &Path, options: &CountArgs) -> Result<(), UdhrError> {
+    let columns = options.columns()?;
+    let old_langs = count_langs(&Input::Corpus(old.to_path_buf()), options)?;
+    let new_langs = count_langs(&Input::Corpus(new.to_path_buf()), options)?;
+    write_diff(
+        &mut std::io::stdout().lock(),
+        &old_langs,
+        &new_langs,
+        &columns,
+    )
+    .map_err(stdout_error)
+}
+
+/// Writes the HTML tables written by `body` measured from `input`, as a
+/// complete document if requested.
+fn write_html<W: Write>(
+    out: &mut W,
+    input: &Input,
+    args: &ReportArgs,
+    body: impl FnOnce(&mut W) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    if !args.interactive && !args.standalone {
+        if args.stylesheet.is_none() {
+            writeln!(out, "<style>")?;
+            output::html::write_colors(out, &args.coloring())?;
+            writeln!(out, "</style>")?;
+        }
+        return body(out);
+    }
+    let description = args.standalone.then(|| {
+        format!(
+            "Measured from {} on {}.",
+            input.describe(),
+            output::iso_date(SystemTime::now())
+        )
+    });
+    let document = args.document(description);
+    output::html::write_document(out, &document, body)
+}
+
+fn report(input: &Input, options: &CountArgs, args: &ReportArgs) -> Result<(), UdhrError> {
+    let mut count_options = options.count_options();
+    count_options.per_article = args.per_article;
+    count_options.blocks = args.detail == Some(Detail::Blocks);
+    count_options.cluster_sizes = args.detail == Some(Detail::Clusters);
+    let snapshot = match input {
+        Input::Snapshot(path) => Some(Snapshot::load(path)?),
+        _ => None,
+    };
+    let mut measured = match &snapshot {
+        Some(snapshot) => snapshot.columns.clone(),
+        None => count_options.columns(),
+    };
+    let columns = options.select_columns(measured.clone())?;
+    // The pages of the translations show more than the table.
+    if args.out.is_some() && snapshot.is_none() {
+        count_options.per_article = true;
+        count_options.category_breakdown = true;
+        if count_options.normalizations.is_empty() {
+            count_options.normalizations = NormalizationForm::ALL.to_vec();
+        }
+        measured = count_options.columns();
+    }
+    let sort_metric = match args.sort_by {
+        Some(SortKey::Metric(metric)) => Some(metric),
+        _ => None,
+    };
+    let emissions: Vec<(Format, Option<&Path>)> = if args.emit.is_empty() {
+        vec![(args.format, args.output.as_deref())]
     } else {
-        (120, (comparison_result as f64) / (baseline_result as f64))
+        args.emit
+            .iter()
+            .map(|(format, path)| (*format, Some(path.as_path())))
+            .collect()
     };
-    (hue, (1.0 - factor).powf(0.75) * 100.0)
-}
-
-fn deviation_percent(value: usize, median: usize) -> f64 {
-    let f_value = value as f64;
-    let f_median = median as f64;
-    let delta = f_value - f_median;
-    (delta / f_median) * 100.0
-}
-
-fn print_count(count: usize, median: usize) {
-    let (hue, saturation) = colorize(median, count);
-    println!(
-        "<td style='background-color: hsl({}, {:.*}%, 65%);'>{}</td><td style='background-color: hsl({}, {:.*}%, 65%);'>{:.*}</td>",
-        hue,
-        6,
-        saturation,
-        count,
-        hue,
-        6,
-        saturation,
-        1,
-        deviation_percent(count, median)
-    );
-}
-
-fn print_lang(
-    lang: &Lang,
-    median_utf8: usize,
-    median_utf16: usize,
-    median_utf32: usize,
-    median_graphemes: usize,
-    median_width: usize,
-) {
-    println!("<tr>");
-    if let Some(code) = &lang.code {
-        println!(
-            "<th><a href=\"https://www.unicode.org/udhr/d/udhr_{}.html\">{}</a></th>",
-            code, lang.name
+    let unsupported = emissions.iter().find_map(|&(format, _)| {
+        let without_detail = matches!(
+            format,
+            Format::Xml
+                | Format::Term
+                | Format::Latex
+                | Format::Mediawiki
+                | Format::Rst
+                | Format::Org
+                | Format::Sqlite
+                | Format::Parquet
+                | Format::Arrow
         );
+        match (args.detail, args.per_article) {
+            (Some(_), _) if format.is_chart() || without_detail => Some((format, "--detail")),
+            (None, true) if format.is_chart() => Some((format, "--per-article")),
+            _ => None,
+        }
+    });
+    if let Some((format, option)) = unsupported {
+        return Err(UdhrError::Unsupported {
+            format: format.name(),
+            option: option.to_string(),
+        });
+    }
+    for &(format, path) in emissions.iter() {
+        let columnar = matches!(format, Format::Parquet | Format::Arrow);
+        if columnar && !cfg!(feature = "columnar") {
+            return Err(UdhrError::NeedsFeature {
+                format: format.name(),
+                feature: "columnar".to_string(),
+            });
+        }
+        let to_stdout = path.is_none_or(|path| path == Path::new("-"));
+        if matches!(format, Format::Sqlite) && to_stdout {
+            return Err(UdhrError::NeedsFile {
+                format: format.name(),
+            });
+        }
+    }
+    let filter_metric = (args.top.is_some() || args.bottom.is_some()).then_some(args.by);
+    let chart_metrics = emissions
+        .iter()
+        .flat_map(|&(format, _)| args.chart_metrics(format));
+    for metric in sort_metric
+        .into_iter()
+        .chain(filter_metric)
+        .chain(chart_metrics)
+    {
+        if !metric.is_measured_in(&measured) {
+            return Err(UdhrError::NotMeasured {
+                metric: metric.name(),
+            });
+        }
+    }
+    let stats_options = args.stats_options()?;
+    let counts = match snapshot {
+        Some(snapshot) => Counts::from(snapshot),
+        None => input.count(&count_options)?,
+    };
+    warn_about(&counts);
+    if let Some(path) = &args.save_snapshot {
+        let snapshot = Snapshot {
+            columns: measured.clone(),
+            langs: counts.langs.clone(),
+        };
+        snapshot.save(path)?;
+    }
+    if let Baseline::Lang(code) = &args.baseline() {
+        if !counts.langs.iter().any(|l| l.code.as_ref() == Some(code)) {
+            return Err(UdhrError::NoSuchLang { code: code.clone() });
+        }
+    }
+    if let Some(dir) = &args.out {
+        let mut report = Report::new(counts.langs, columns);
+        args.apply(&mut report, &stats_options);
+        return output::site::write(dir, &report, &measured, &args.style());
+    }
+    if let Some(template) = &args.template {
+        let mut report = Report::new(counts.langs, columns);
+        args.apply(&mut report, &stats_options);
+        let rendered = output::template::render(template, &report, args.locale)?;
+        return write_output(args.output.as_deref(), |out| {
+            out.write_all(rendered.as_bytes())
+        });
+    }
+    if let Some(path) = &args.stylesheet {
+        if emissions.iter().any(|(f, _)| matches!(f, Format::Html)) {
+            output::write_file(path, |out| {
+                output::html::write_stylesheet(
+                    out,
+                    &args.coloring(),
+                    args.standalone,
+                    args.interactive,
+                )
+            })
+            .map_err(UdhrError::io(path.display()))?;
+        }
+    }
+    for (format, path) in emissions {
+        if let (Format::Sqlite, Some(path)) = (format, path) {
+            write_sqlite(path, args, &counts, &columns, &stats_options)?;
+            continue;
+        }
+        let to_stdout = path.is_none_or(|path| path == Path::new("-"));
+        let terminal = args.terminal(to_stdout && std::io::stdout().is_terminal());
+        let start = Instant::now();
+        write_output(path, |mut out| {
+            write_report(
+                &mut out,
+                input,
+                args,
+                format,
+                &terminal,
+                &counts,
+                &columns,
+                &stats_options,
+            )
+        })?;
+        info!(
+            "wrote the {} output to {} in {:.2?}",
+            format.name(),
+            path.filter(|_| !to_stdout).map_or_else(
+                || "standard output".to_string(),
+                |p| p.display().to_string()
+            ),
+            start.elapsed()
+        );
+    }
+    Ok(())
+}
+
+/// The syntax of the structured document `format`.
+fn syntax(format: Format) -> Syntax {
+    match format {
+        Format::Yaml => Syntax::Yaml,
+        Format::Toml => Syntax::Toml,
+        _ => Syntax::Json,
+    }
+}
+
+/// Writes the SQLite database of `counts` requested by `args` at `path`.
+fn write_sqlite(
+    path: &Path,
+    args: &ReportArgs,
+    counts: &Counts,
+    columns: &[Metric],
+    stats_options: &StatsOptions,
+) -> Result<(), UdhrError> {
+    let mut report = Report::new(counts.langs.clone(), columns.to_vec());
+    args.apply(&mut report, stats_options);
+    let articles = args.per_article.then(|| {
+        let mut articles = Report::per_article(&counts.langs, columns);
+        for (_, report) in articles.iter_mut() {
+            args.apply(report, stats_options);
+        }
+        articles
+    });
+    output::sqlite::write(path, &report, articles.as_deref())
+}
+
+/// Writes with `write` into the file at `path` or, if there's none or it is
+/// `-`, standard output.
+fn write_output(
+    path: Option<&Path>,
+    write: impl FnOnce(&mut dyn Write) -> std::io::Result<()>,
+) -> Result<(), UdhrError> {
+    match path {
+        Some(path) if path != Path::new("-") => {
+            output::write_file(path, |out| write(out)).map_err(UdhrError::io(path.display()))
+        }
+        _ => write(&mut std::io::stdout().lock()).map_err(stdout_error),
+    }
+}
+
+/// Writes the table or the details of `counts` requested by `args` in
+/// `format`, for `terminal` with `Format::Term`.
+#[allow(clippy::too_many_arguments)]
+fn write_report<W: Write>(
+    out: &mut W,
+    input: &Input,
+    args: &ReportArgs,
+    format: Format,
+    terminal: &Terminal,
+    counts: &Counts,
+    columns: &[Metric],
+    stats_options: &StatsOptions,
+) -> std::io::Result<()> {
+    if let Some(svg_format) = format.svg() {
+        let mut svg = Vec::new();
+        write_report(
+            &mut svg,
+            input,
+            args,
+            svg_format,
+            terminal,
+            counts,
+            columns,
+            stats_options,
+        )?;
+        return output::png::write(out, &svg);
+    }
+    if let Some(detail) = args.detail {
+        let mut langs = counts.langs.clone();
+        langs.sort_by(|a, b| a.name.cmp(&b.name));
+        match (detail, format) {
+            (Detail::Blocks, Format::Html) => write_html(out, input, args, |out| {
+                output::html::write_blocks(out, &langs)
+            }),
+            (Detail::Blocks, Format::Json | Format::Yaml | Format::Toml) => {
+                output::json::write_blocks(out, &langs, syntax(format))
+            }
+            (Detail::Blocks, Format::Csv) => output::csv::write_blocks(out, &langs),
+            (Detail::Clusters, Format::Html) => write_html(out, input, args, |out| {
+                output::html::write_clusters(out, &langs)
+            }),
+            (Detail::Clusters, Format::Json | Format::Yaml | Format::Toml) => {
+                output::json::write_clusters(out, &langs, syntax(format))
+            }
+            (Detail::Clusters, Format::Csv) => output::csv::write_clusters(out, &langs),
+            // Rejected by `report`
+            (
+                _,
+                Format::Xml
+                | Format::Term
+                | Format::Latex
+                | Format::Mediawiki
+                | Format::Rst
+                | Format::Org
+                | Format::Sqlite
+                | Format::Parquet
+                | Format::Arrow
+                | Format::SvgBars
+                | Format::SvgScatter
+                | Format::SvgHistogram
+                | Format::SvgBoxplot
+                | Format::SvgHeatmap
+                | Format::PngBars
+                | Format::PngScatter
+                | Format::PngHistogram
+                | Format::PngBoxplot
+                | Format::PngHeatmap
+                | Format::Vega,
+            ) => {
+                unreachable!("{} of a detail", format.name())
+            }
+        }
+    } else if args.per_article {
+        let mut articles = Report::per_article(&counts.langs, columns);
+        for (_, report) in articles.iter_mut() {
+            args.apply(report, stats_options);
+        }
+        match format {
+            Format::Html => write_html(out, input, args, |out| {
+                output::html::write_articles(out, &articles, &args.style())
+            }),
+            Format::Json | Format::Yaml | Format::Toml => {
+                output::json::write_articles(out, &articles, syntax(format))
+            }
+            Format::Xml => output::xml::write_articles(out, &articles),
+            Format::Csv => output::csv::write_articles(out, &articles),
+            Format::Term => output::term::write_articles(out, &articles, &args.style(), terminal),
+            Format::Latex => output::latex::write_articles(out, &articles, &args.style()),
+            Format::Mediawiki => output::mediawiki::write_articles(out, &articles, &args.style()),
+            Format::Rst => output::rst::write_articles(out, &articles, &args.style()),
+            Format::Org => output::org::write_articles(out, &articles, &args.style()),
+            // Written by `write_sqlite`
+            Format::Sqlite => unreachable!("SQLite into a stream"),
+            #[cfg(feature = "columnar")]
+            Format::Parquet => output::columnar::write_articles(out, &articles, Container::Parquet),
+            #[cfg(feature = "columnar")]
+            Format::Arrow => output::columnar::write_articles(out, &articles, Container::Arrow),
+            // Rejected by `report`
+            #[cfg(not(feature = "columnar"))]
+            Format::Parquet | Format::Arrow => unreachable!("columnar output without the feature"),
+            Format::SvgBars
+            | Format::SvgScatter
+            | Format::SvgHistogram
+            | Format::SvgBoxplot
+            | Format::SvgHeatmap
+            | Format::PngBars
+            | Format::PngScatter
+            | Format::PngHistogram
+            | Format::PngBoxplot
+            | Format::PngHeatmap
+            | Format::Vega => {
+                unreachable!("chart per article")
+            }
+        }
     } else {
-        println!("<th>{}</th>", lang.name);
-    }
-    print_count(lang.utf8, median_utf8);
-    print_count(lang.utf16, median_utf16);
-    print_count(lang.utf32, median_utf32);
-    print_count(lang.graphemes, median_graphemes);
-    print_count(lang.width, median_width);
-    println!(
-        "<td>{}</td>",
-        match &lang.script {
-            Some(script) => &script[..],
-            None => "",
-        }
-    );
-    println!("</tr>");
-}
-
-fn main() -> std::io::Result<()> {
-    let mut langs = Vec::new();
-
-    let mut args = std::env::args_os();
-    let _ = args.next(); // skip program name
-
-    let dir: PathBuf = Path::new(&args.next().unwrap()).into();
-    assert!(dir.is_dir());
-    let index_path = dir.join(Path::new("index.xml"));
-
-    let mut index_file = File::open(index_path)?;
-    let mut index_text = String::new();
-    index_file.read_to_string(&mut index_text)?;
-
-    let mut buf = Vec::new();
-    let mut index = quick_xml::Reader::from_str(&index_text);
-    loop {
-        match index.read_event(&mut buf) {
-            Ok(Event::Empty(ref e)) if e.name() == b"udhr" => {
-                let mut name = String::new();
-                let mut code = String::new();
-                let mut script = String::new();
-                let mut stage_ok = false;
-                for attr in e.attributes() {
-                    match attr {
-                        Ok(a) => match a.key {
-                            b"stage" => {
-                                let v = a.unescaped_value().unwrap();
-                                stage_ok = (v.len() == 1) && (v[0] == b'4' || v[0] == b'5');
-                            }
-                            b"f" => {
-                                code = a.unescape_and_decode_value(&index).unwrap();
-                            }
-                            b"n" => {
-                                name = a
-                                    .unescape_and_decode_value(&index)
-                                    .unwrap()
-                                    .nfc()
-                                    .collect::<String>();
-                            }
-                            b"iso15924" => {
-                                script = a.unescape_and_decode_value(&index).unwrap();
-                            }
-                            _ => {}
-                        },
-                        Err(_) => {
-                            panic!("Bad attribute");
-                        }
-                    }
-                }
-                if stage_ok {
-                    assert!(!name.is_empty());
-                    assert!(!code.is_empty());
-                    let mut file_name = String::from("udhr_");
-                    file_name.push_str(&code);
-                    file_name.push_str(".xml");
-                    langs.push(count(&dir.join(file_name), name, code, script)?);
-                }
+        let mut report = Report::new(counts.langs.clone(), columns.to_vec());
+        args.apply(&mut report, stats_options);
+        match format {
+            Format::Html => write_html(out, input, args, |out| {
+                output::html::write(out, &report, &args.style())
+            }),
+            Format::Json | Format::Yaml | Format::Toml => {
+                output::json::write(out, &report, syntax(format))
+            }
+            Format::Xml => output::xml::write(out, &report),
+            Format::Csv => output::csv::write(out, &report),
+            Format::Term => output::term::write(out, &report, &args.style(), terminal),
+            Format::Latex => output::latex::write(out, &report, &args.style()),
+            Format::Mediawiki => output::mediawiki::write(out, &report, &args.style()),
+            Format::Rst => output::rst::write(out, &report, &args.style()),
+            Format::Org => output::org::write(out, &report, &args.style()),
+            // Written by `write_sqlite`
+            Format::Sqlite => unreachable!("SQLite into a stream"),
+            #[cfg(feature = "columnar")]
+            Format::Parquet => output::columnar::write(out, &report, Container::Parquet),
+            #[cfg(feature = "columnar")]
+            Format::Arrow => output::columnar::write(out, &report, Container::Arrow),
+            // Rejected by `report`
+            #[cfg(not(feature = "columnar"))]
+            Format::Parquet | Format::Arrow => unreachable!("columnar output without the feature"),
+            Format::SvgBars => output::svg::write_bars(out, &report, args.metric, args.locale),
+            Format::SvgScatter => {
+                output::svg::write_scatter(out, &report, args.x, args.y, args.locale)
+            }
+            Format::SvgHistogram => output::svg::write_histogram(
+                out,
+                &report,
+                args.metric,
+                args.bins.map(usize::from),
+                args.locale,
+            ),
+            Format::SvgBoxplot => {
+                output::svg::write_boxplot(out, &report, args.metric, args.locale)
             }
-            Ok(Event::Eof) => break,
-            Ok(_) => {}
-            Err(e) => panic!("Error at position {}: {:?}", index.buffer_position(), e),
-        }
-    }
-
-    langs.sort_by(|a, b| a.width.cmp(&b.width));
-    let median_width = langs[langs.len() / 2].width;
-    let min_width = langs[0].width;
-    let max_width = langs[langs.len() - 1].width;
-    let max2_width = langs[langs.len() - 2].width;
-
-
-    langs.sort_by(|a, b| a.graphemes.cmp(&b.graphemes));
-    let median_graphemes = langs[langs.len() / 2].graphemes;
-    let min_graphemes = langs[0].graphemes;
-    let max_graphemes = langs[langs.len() - 1].graphemes;
-    let max2_graphemes = langs[langs.len() - 2].graphemes;
-
-    langs.sort_by(|a, b| a.utf32.cmp(&b.utf32));
-    let median_utf32 = langs[langs.len() / 2].utf32;
-    let min_utf32 = langs[0].utf32;
-    let max_utf32 = langs[langs.len() - 1].utf32;
-    let max2_utf32 = langs[langs.len() - 2].utf32;
-
-    langs.sort_by(|a, b| a.utf16.cmp(&b.utf16));
-    let median_utf16 = langs[langs.len() / 2].utf16;
-    let min_utf16 = langs[0].utf16;
-    let max_utf16 = langs[langs.len() - 1].utf16;
-    let max2_utf16 = langs[langs.len() - 2].utf16;
-
-    langs.sort_by(|a, b| a.utf8.cmp(&b.utf8));
-    let median_utf8 = langs[langs.len() / 2].utf8;
-    let min_utf8 = langs[0].utf8;
-    let max_utf8 = langs[langs.len() - 1].utf8;
-    let max2_utf8 = langs[langs.len() - 2].utf8;
-
-    println!("<table id=counts>");
-    println!("<thead>");
-    println!("<tr><th>Name</th><th>UTF-8</th><th>Δ%</th><th>UTF-16</th><th>Δ%</th><th>UTF-32</th><th>Δ%</th><th>EGC</th><th>Δ%</th><th>EAW</th><th>Δ%</th><th>Script</th></tr>");
-    println!("</thead>");
-    println!("<tbody>");
-
-    let mut total_utf8 = 0usize;
-    let mut total_utf16 = 0usize;
-    let mut total_utf32 = 0usize;
-    let mut total_graphemes = 0usize;
-    let mut total_width = 0usize;
-    for lang in langs.iter() {
-        total_utf8 += lang.utf8;
-        total_utf16 += lang.utf16;
-        total_utf32 += lang.utf32;
-        total_graphemes += lang.graphemes;
-        total_width += lang.width;
-    }
-    let mean_utf8 = total_utf8 / langs.len();
-    let mean_utf16 = total_utf16 / langs.len();
-    let mean_utf32 = total_utf32 / langs.len();
-    let mean_graphemes = total_graphemes / langs.len();
-    let mean_width = total_width / langs.len();
+            Format::SvgHeatmap => output::svg::write_heatmap(out, &report, &args.style()),
+            // Written through the SVG chart above
+            Format::PngBars
+            | Format::PngScatter
+            | Format::PngHistogram
+            | Format::PngBoxplot
+            | Format::PngHeatmap => unreachable!("PNG without SVG"),
+            Format::Vega => match args.chart {
+                Chart::Bars => output::vega::write_bars(out, &report, args.metric),
+                Chart::Scatter => output::vega::write_scatter(out, &report, args.x, args.y),
+                Chart::Histogram => output::vega::write_histogram(
+                    out,
+                    &report,
+                    args.metric,
+                    args.bins.map(usize::from),
+                ),
+                Chart::Boxplot => output::vega::write_boxplot(out, &report, args.metric),
+            },
+        }
+    }
+}
 
-    for lang in langs {
-        print_lang(
-            &lang,
-            median_utf8,
-            median_utf16,
-            median_utf32,
-            median_graphemes,
-            median_width,
-        );
+fn run(cli: Cli) -> Result<(), UdhrError> {
+    match cli.command {
+        Command::Count {
+            corpus,
+            layout,
+            plain,
+            options,
+        } => count(&Input::resolve(corpus, layout, plain)?, &options),
+        Command::Report {
+            corpus,
+            layout,
+            plain,
+            from_snapshot,
+            options,
+            report: args,
+        } => {
+            let input = match from_snapshot {
+                Some(path) => Input::Snapshot(path),
+                None => Input::resolve(corpus, layout, plain)?,
+            };
+            report(&input, &options, &args)
+        }
+        Command::Diff { old, new, options } => diff(&old, &new, &options),
+        Command::Bench {
+            corpus,
+            layout,
+            iterations,
+            only,
+            scripts,
+        } => {
+            let options = CountOptions {
+                only,
+                scripts,
+                ..CountOptions::default()
+            };
+            bench(&layout.input(corpus)?, &options, iterations)
+        }
+        Command::Serve {
+            dir,
+            layout,
+            port,
+            bind,
+            options,
+        } => serve(&layout.input(dir)?, &format!("{}:{}", bind, port), &options),
+        Command::Tui {
+            dir,
+            layout,
+            options,
+        } => tui(&layout.input(dir)?, &options),
+        Command::Fetch { url, cache_dir } => fetch(&url, cache_dir),
+        Command::Schema {
+            per_article,
+            detail,
+        } => schema(per_article, detail),
+        Command::Completions { shell } => completions(shell),
     }
+}
 
-    println!("</tbody>");
-    println!("<tfoot>");
-    print_lang(
-        &Lang {
-            name: "Min".to_string(),
-            utf8: min_utf8,
-            utf16: min_utf16,
-            utf32: min_utf32,
-            graphemes: min_graphemes,
-            width: min_width,
-            code: None,
-            script: None,
-        },
-        median_utf8,
-        median_utf16,
-        median_utf32,
-        median_graphemes,
-        median_width,
-    );
-    println!("<tr><th>Median</th><td>{}</td><td></td><td>{}</td><td></td><td>{}</td><td></td><td>{}</td><td></td><td>{}</td><td></td><td></td></tr>", median_utf8, median_utf16, median_utf32, median_graphemes, median_width);
-    print_lang(
-        &Lang {
-            name: "Mean".to_string(),
-            utf8: mean_utf8,
-            utf16: mean_utf16,
-            utf32: mean_utf32,
-            graphemes: mean_graphemes,
-            width: mean_width,
-            code: None,
-            script: None,
-        },
-        median_utf8,
-        median_utf16,
-        median_utf32,
-        median_graphemes,
-        median_width,
-    );
-    print_lang(
-        &Lang {
-            name: "Max (ignoring outlier)".to_string(),
-            utf8: max2_utf8,
-            utf16: max2_utf16,
-            utf32: max2_utf32,
-            graphemes: max2_graphemes,
-            width: max2_width,
-            code: None,
-            script: None,
-        },
-        median_utf8,
-        median_utf16,
-        median_utf32,
-        median_graphemes,
-        median_width,
-    );
-    print_lang(
-        &Lang {
-            name: "Max".to_string(),
-            utf8: max_utf8,
-            utf16: max_utf16,
-            utf32: max_utf32,
-            graphemes: max_graphemes,
-            width: max_width,
-            code: None,
-            script: None,
-        },
-        median_utf8,
-        median_utf16,
-        median_utf32,
-        median_graphemes,
-        median_width,
-    );
-    println!("</tfoot>");
-    println!("</table>");
-    Ok(())
+fn main() {
+    let cli = Cli::parse();
+    init_logging(cli.verbose, cli.quiet);
+    match run(cli) {
+        Ok(()) => {}
+        // Piping into `head` and the like is not an error.
+        Err(UdhrError::Io { source, .. }) if source.kind() == std::io::ErrorKind::BrokenPipe => {}
+        Err(error) => {
+            eprintln!("udhrlen: {}", error);
+            std::process::exit(1);
+        }
+    }
 }
@@ -0,0 +1,1908 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The units of measurement.
+
+use crate::encode;
+use crate::icu4x;
+use crate::tokenize::Tokenizer;
+use crate::CountOptions;
+use crate::IndexEntry;
+use encoding_rs::Encoding;
+use icu_casemap::CaseMapper;
+use icu_locale_core::LanguageIdentifier;
+use icu_properties::props::BidiClass;
+use icu_properties::props::DefaultIgnorableCodePoint;
+use icu_properties::props::EastAsianWidth;
+use icu_properties::props::ExtendedPictographic;
+use icu_properties::props::GeneralCategory;
+use icu_properties::props::Script;
+use icu_properties::props::VariationSelector;
+use icu_properties::CodePointMapData;
+use icu_properties::CodePointSetData;
+use icu_properties::PropertyNamesShortBorrowed;
+use schemars::JsonSchema;
+use schemars::Schema;
+use schemars::SchemaGenerator;
+use serde::Deserialize;
+use serde::Deserializer;
+use serde::Serialize;
+use serde::Serializer;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::io::Write;
+use std::str::FromStr;
+use unic_normal::StrNormalForm;
+use unic_segment::Graphemes;
+use unic_segment::WordBounds;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// A Unicode normalization form.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalizationForm {
+    Nfc,
+    Nfd,
+    Nfkc,
+    Nfkd,
+}
+
+impl NormalizationForm {
+    pub const ALL: [NormalizationForm; 4] = [
+        NormalizationForm::Nfc,
+        NormalizationForm::Nfd,
+        NormalizationForm::Nfkc,
+        NormalizationForm::Nfkd,
+    ];
+
+    pub fn normalize(self, text: &str) -> String {
+        match self {
+            NormalizationForm::Nfc => text.nfc().collect(),
+            NormalizationForm::Nfd => text.nfd().collect(),
+            NormalizationForm::Nfkc => text.nfkc().collect(),
+            NormalizationForm::Nfkd => text.nfkd().collect(),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            NormalizationForm::Nfc => "nfc",
+            NormalizationForm::Nfd => "nfd",
+            NormalizationForm::Nfkc => "nfkc",
+            NormalizationForm::Nfkd => "nfkd",
+        }
+    }
+}
+
+impl FromStr for NormalizationForm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<NormalizationForm, String> {
+        match &s.trim().to_ascii_lowercase()[..] {
+            "nfc" => Ok(NormalizationForm::Nfc),
+            "nfd" => Ok(NormalizationForm::Nfd),
+            "nfkc" => Ok(NormalizationForm::Nfkc),
+            "nfkd" => Ok(NormalizationForm::Nfkd),
+            _ => Err(format!("unknown normalization form '{}'", s.trim())),
+        }
+    }
+}
+
+/// A full case mapping without language-specific tailoring.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CaseMapping {
+    Fold,
+    Upper,
+    Lower,
+    /// The NFKC_Casefold mapping of UAX #44 used for identifiers and
+    /// search keys: case folding and compatibility normalization with the
+    /// default ignorable code points removed
+    NfkcCasefold,
+}
+
+impl CaseMapping {
+    pub fn apply(self, text: &str) -> String {
+        let mapper = CaseMapper::new();
+        let root = LanguageIdentifier::UNKNOWN;
+        match self {
+            CaseMapping::Fold => mapper.fold_string(text).into_owned(),
+            CaseMapping::Upper => mapper.uppercase_to_string(text, &root).into_owned(),
+            CaseMapping::Lower => mapper.lowercase_to_string(text, &root).into_owned(),
+            CaseMapping::NfkcCasefold => {
+                let ignorable = CodePointSetData::new::<DefaultIgnorableCodePoint>();
+                let decomposed: String = text.nfkd().filter(|&c| !ignorable.contains(c)).collect();
+                mapper.fold_string(&decomposed).nfkc().collect()
+            }
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CaseMapping::Fold => "fold",
+            CaseMapping::Upper => "upper",
+            CaseMapping::Lower => "lower",
+            CaseMapping::NfkcCasefold => "nfkc_casefold",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CaseMapping::Fold => "Folded",
+            CaseMapping::Upper => "Upper",
+            CaseMapping::Lower => "Lower",
+            CaseMapping::NfkcCasefold => "NFKC_CF",
+        }
+    }
+}
+
+impl FromStr for CaseMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<CaseMapping, String> {
+        match &s.trim().to_ascii_lowercase()[..] {
+            "fold" | "casefold" => Ok(CaseMapping::Fold),
+            "upper" | "uppercase" => Ok(CaseMapping::Upper),
+            "lower" | "lowercase" => Ok(CaseMapping::Lower),
+            "nfkc_casefold" | "nfkc_cf" => Ok(CaseMapping::NfkcCasefold),
+            _ => Err(format!("unknown case mapping '{}'", s.trim())),
+        }
+    }
+}
+
+/// A Unicode encoding form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EncodingForm {
+    Utf8,
+    Utf16,
+    Utf32,
+}
+
+impl EncodingForm {
+    pub const ALL: [EncodingForm; 3] =
+        [EncodingForm::Utf8, EncodingForm::Utf16, EncodingForm::Utf32];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EncodingForm::Utf8 => "utf8",
+            EncodingForm::Utf16 => "utf16",
+            EncodingForm::Utf32 => "utf32",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EncodingForm::Utf8 => "UTF-8",
+            EncodingForm::Utf16 => "UTF-16",
+            EncodingForm::Utf32 => "UTF-32",
+        }
+    }
+}
+
+/// The length of a piece of text in code units of each encoding form.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EncodedLengths {
+    pub utf8: usize,
+    pub utf16: usize,
+    pub utf32: usize,
+}
+
+impl EncodedLengths {
+    pub fn new(text: &str) -> EncodedLengths {
+        EncodedLengths {
+            utf8: text.len(),
+            utf16: text.encode_utf16().count(),
+            utf32: text.chars().count(),
+        }
+    }
+
+    pub fn get(&self, form: EncodingForm) -> usize {
+        match form {
+            EncodingForm::Utf8 => self.utf8,
+            EncodingForm::Utf16 => self.utf16,
+            EncodingForm::Utf32 => self.utf32,
+        }
+    }
+}
+
+/// A value of the East_Asian_Width property of UAX #11.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EastAsianWidthCategory {
+    Neutral,
+    Narrow,
+    Wide,
+    Ambiguous,
+    Halfwidth,
+    Fullwidth,
+}
+
+impl EastAsianWidthCategory {
+    pub const ALL: [EastAsianWidthCategory; 6] = [
+        EastAsianWidthCategory::Neutral,
+        EastAsianWidthCategory::Narrow,
+        EastAsianWidthCategory::Wide,
+        EastAsianWidthCategory::Ambiguous,
+        EastAsianWidthCategory::Halfwidth,
+        EastAsianWidthCategory::Fullwidth,
+    ];
+
+    /// The number of scalar values of `text` in each category, including
+    /// the empty ones.
+    pub fn count(text: &str) -> BTreeMap<EastAsianWidthCategory, usize> {
+        let property = CodePointMapData::<EastAsianWidth>::new();
+        let mut counts: BTreeMap<_, _> = EastAsianWidthCategory::ALL
+            .iter()
+            .map(|&category| (category, 0))
+            .collect();
+        for c in text.chars() {
+            let category = match property.get(c) {
+                EastAsianWidth::Narrow => EastAsianWidthCategory::Narrow,
+                EastAsianWidth::Wide => EastAsianWidthCategory::Wide,
+                EastAsianWidth::Ambiguous => EastAsianWidthCategory::Ambiguous,
+                EastAsianWidth::Halfwidth => EastAsianWidthCategory::Halfwidth,
+                EastAsianWidth::Fullwidth => EastAsianWidthCategory::Fullwidth,
+                _ => EastAsianWidthCategory::Neutral,
+            };
+            *counts.entry(category).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EastAsianWidthCategory::Neutral => "neutral",
+            EastAsianWidthCategory::Narrow => "narrow",
+            EastAsianWidthCategory::Wide => "wide",
+            EastAsianWidthCategory::Ambiguous => "ambiguous",
+            EastAsianWidthCategory::Halfwidth => "halfwidth",
+            EastAsianWidthCategory::Fullwidth => "fullwidth",
+        }
+    }
+
+    /// The short property value alias, e.g. `Na`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            EastAsianWidthCategory::Neutral => "N",
+            EastAsianWidthCategory::Narrow => "Na",
+            EastAsianWidthCategory::Wide => "W",
+            EastAsianWidthCategory::Ambiguous => "A",
+            EastAsianWidthCategory::Halfwidth => "H",
+            EastAsianWidthCategory::Fullwidth => "F",
+        }
+    }
+}
+
+/// A major class of the General_Category property, e.g. letters for Lu, Ll,
+/// Lt, Lm and Lo.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CategoryClass {
+    Letter,
+    Mark,
+    Number,
+    Punctuation,
+    Symbol,
+    Separator,
+    Other,
+}
+
+impl CategoryClass {
+    pub const ALL: [CategoryClass; 7] = [
+        CategoryClass::Letter,
+        CategoryClass::Mark,
+        CategoryClass::Number,
+        CategoryClass::Punctuation,
+        CategoryClass::Symbol,
+        CategoryClass::Separator,
+        CategoryClass::Other,
+    ];
+
+    /// The number of scalar values of `text` in each class, including the
+    /// empty ones.
+    pub fn count(text: &str) -> BTreeMap<CategoryClass, usize> {
+        let property = CodePointMapData::<GeneralCategory>::new();
+        let mut counts: BTreeMap<_, _> =
+            CategoryClass::ALL.iter().map(|&class| (class, 0)).collect();
+        for c in text.chars() {
+            let class = match property.get(c) {
+                GeneralCategory::UppercaseLetter
+                | GeneralCategory::LowercaseLetter
+                | GeneralCategory::TitlecaseLetter
+                | GeneralCategory::ModifierLetter
+                | GeneralCategory::OtherLetter => CategoryClass::Letter,
+                GeneralCategory::NonspacingMark
+                | GeneralCategory::SpacingMark
+                | GeneralCategory::EnclosingMark => CategoryClass::Mark,
+                GeneralCategory::DecimalNumber
+                | GeneralCategory::LetterNumber
+                | GeneralCategory::OtherNumber => CategoryClass::Number,
+                GeneralCategory::ConnectorPunctuation
+                | GeneralCategory::DashPunctuation
+                | GeneralCategory::OpenPunctuation
+                | GeneralCategory::ClosePunctuation
+                | GeneralCategory::InitialPunctuation
+                | GeneralCategory::FinalPunctuation
+                | GeneralCategory::OtherPunctuation => CategoryClass::Punctuation,
+                GeneralCategory::MathSymbol
+                | GeneralCategory::CurrencySymbol
+                | GeneralCategory::ModifierSymbol
+                | GeneralCategory::OtherSymbol => CategoryClass::Symbol,
+                GeneralCategory::SpaceSeparator
+                | GeneralCategory::LineSeparator
+                | GeneralCategory::ParagraphSeparator => CategoryClass::Separator,
+                _ => CategoryClass::Other,
+            };
+            *counts.entry(class).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CategoryClass::Letter => "letter",
+            CategoryClass::Mark => "mark",
+            CategoryClass::Number => "number",
+            CategoryClass::Punctuation => "punctuation",
+            CategoryClass::Symbol => "symbol",
+            CategoryClass::Separator => "separator",
+            CategoryClass::Other => "other",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CategoryClass::Letter => "Letters",
+            CategoryClass::Mark => "Marks",
+            CategoryClass::Number => "Numbers",
+            CategoryClass::Punctuation => "Punctuation",
+            CategoryClass::Symbol => "Symbols",
+            CategoryClass::Separator => "Separators",
+            CategoryClass::Other => "Other",
+        }
+    }
+}
+
+/// The number of scalar values of a text in a Unicode block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BlockCount {
+    /// The name of the block, or `No_Block` for scalar values outside the
+    /// blocks
+    pub block: Cow<'static, str>,
+    pub plane: u32,
+    pub count: usize,
+}
+
+/// The number of scalar values of `text` in each block that has any, in
+/// code point order.
+pub fn count_blocks(text: &str) -> Vec<BlockCount> {
+    let mut counts: BTreeMap<(u32, &'static str), usize> = BTreeMap::new();
+    for c in text.chars() {
+        let key = match unicode_blocks::find_unicode_block(c) {
+            Some(block) => (block.start(), block.name()),
+            None => (u32::from(c), "No_Block"),
+        };
+        *counts.entry(key).or_insert(0) += 1;
+    }
+    let mut blocks: Vec<BlockCount> = Vec::new();
+    for ((start, block), count) in counts {
+        // Merge the scalar values outside the blocks of each plane.
+        match blocks.last_mut() {
+            Some(last) if last.block == block && last.plane == start >> 16 => last.count += count,
+            _ => blocks.push(BlockCount {
+                block: Cow::Borrowed(block),
+                plane: start >> 16,
+                count,
+            }),
+        }
+    }
+    blocks
+}
+
+/// The number of scalar values in each plane according to `blocks`.
+pub fn count_planes(blocks: &[BlockCount]) -> BTreeMap<u32, usize> {
+    let mut planes = BTreeMap::new();
+    for block in blocks {
+        *planes.entry(block.plane).or_insert(0) += block.count;
+    }
+    planes
+}
+
+/// The script runs of a text in the sense of UAX #24.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptRuns {
+    /// The number of runs of scalar values in the same script, with Common
+    /// and Inherited ones continuing the run they are in
+    pub runs: usize,
+    /// The number of scalar values in scripts other than the declared one,
+    /// not counting Common and Inherited ones
+    pub foreign: usize,
+}
+
+impl ScriptRuns {
+    /// Analyzes `text` written in the ISO 15924 script `declared`.
+    pub fn new(text: &str, declared: &str) -> ScriptRuns {
+        let property = CodePointMapData::<Script>::new();
+        let names = PropertyNamesShortBorrowed::<Script>::new();
+        // The scripts that the compound codes stand for
+        let expected: &[&str] = match declared {
+            "Jpan" => &["Hira", "Kana", "Hani"],
+            "Kore" => &["Hang", "Hani"],
+            "Hans" | "Hant" | "Hanb" => &["Hani", "Bopo"],
+            _ => &[declared],
+        };
+        let mut analysis = ScriptRuns::default();
+        let mut current = None;
+        for c in text.chars() {
+            let script = property.get(c);
+            if script == Script::Common || script == Script::Inherited {
+                continue;
+            }
+            if current != Some(script) {
+                analysis.runs += 1;
+                current = Some(script);
+            }
+            if !names
+                .get(script)
+                .is_some_and(|name| expected.contains(&name))
+            {
+                analysis.foreign += 1;
+            }
+        }
+        if analysis.runs == 0 && !text.is_empty() {
+            // Only Common and Inherited
+            analysis.runs = 1;
+        }
+        analysis
+    }
+}
+
+/// The repertoire of a text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Distinct {
+    pub chars: usize,
+    pub graphemes: usize,
+}
+
+impl Distinct {
+    pub fn new(text: &str) -> Distinct {
+        Distinct {
+            chars: text.chars().collect::<HashSet<_>>().len(),
+            graphemes: Graphemes::new(text).collect::<HashSet<_>>().len(),
+        }
+    }
+}
+
+/// The lengths of the longest extended grapheme clusters of a text, which
+/// need not be the same cluster.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LongestGrapheme {
+    /// Scalar values
+    pub chars: usize,
+    /// UTF-8 bytes
+    pub bytes: usize,
+}
+
+impl LongestGrapheme {
+    pub fn new(text: &str) -> LongestGrapheme {
+        let mut longest = LongestGrapheme::default();
+        for grapheme in Graphemes::new(text) {
+            longest.chars = longest.chars.max(grapheme.chars().count());
+            longest.bytes = longest.bytes.max(grapheme.len());
+        }
+        longest
+    }
+}
+
+/// The emoji-related characters of a text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Emoji {
+    /// Scalar values with the Extended_Pictographic property
+    pub pictographic: usize,
+    /// U+200D ZERO WIDTH JOINER
+    pub zwj: usize,
+    /// Scalar values with the Variation_Selector property
+    pub variation_selectors: usize,
+}
+
+impl Emoji {
+    pub fn new(text: &str) -> Emoji {
+        let pictographic = CodePointSetData::new::<ExtendedPictographic>();
+        let variation_selector = CodePointSetData::new::<VariationSelector>();
+        let mut emoji = Emoji::default();
+        for c in text.chars() {
+            if pictographic.contains(c) {
+                emoji.pictographic += 1;
+            } else if c == '\u{200D}' {
+                emoji.zwj += 1;
+            } else if variation_selector.contains(c) {
+                emoji.variation_selectors += 1;
+            }
+        }
+        emoji
+    }
+}
+
+/// Characters that indicate corrupt source data or a botched conversion and
+/// skew the counts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Suspicious {
+    /// U+FFFD REPLACEMENT CHARACTER
+    pub replacement: usize,
+    /// C0 and C1 controls other than whitespace
+    pub controls: usize,
+    /// Private-use code points
+    pub private_use: usize,
+}
+
+impl Suspicious {
+    pub fn new(text: &str) -> Suspicious {
+        let mut suspicious = Suspicious::default();
+        for c in text.chars() {
+            suspicious.add(c);
+        }
+        suspicious
+    }
+
+    /// Counts `c` if it's suspicious.
+    pub fn add(&mut self, c: char) {
+        match c {
+            '\u{FFFD}' => self.replacement += 1,
+            _ if c.is_control() && !c.is_whitespace() => self.controls += 1,
+            '\u{E000}'..='\u{F8FF}' | '\u{F0000}'..='\u{FFFFD}' | '\u{100000}'..='\u{10FFFD}' => {
+                self.private_use += 1
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Suspicious::default()
+    }
+}
+
+fn grapheme_segments(text: &str) -> Vec<&str> {
+    Graphemes::new(text).collect()
+}
+
+/// The words of `text` together with the text between them.
+fn word_segments(text: &str) -> Vec<&str> {
+    WordBounds::new(text).collect()
+}
+
+fn sentence_segments(text: &str) -> Vec<&str> {
+    text.split_sentence_bounds().collect()
+}
+
+/// Whether a word or sentence segment counts, which is the filter of
+/// `Words` in the default mode and of `unicode_sentences`.
+fn has_alphanumeric(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+/// Text that arrives in pieces and is segmented as far as the text still to
+/// come can't move the boundaries.
+struct Pending {
+    text: String,
+    segments: fn(&str) -> Vec<&str>,
+}
+
+impl Pending {
+    fn new(segments: fn(&str) -> Vec<&str>) -> Pending {
+        Pending {
+            text: String::new(),
+            segments,
+        }
+    }
+
+    /// Appends `text` and passes the segments that are settled to `settled`.
+    /// The UAX #29 rules that look ahead of a position, like SB8 past any
+    /// number of closing punctuation and spaces, don't look past the next
+    /// boundary, so only the last two segments can still change.
+    fn push(&mut self, text: &str, mut settled: impl FnMut(&str)) {
+        self.text.push_str(text);
+        let segments = (self.segments)(&self.text);
+        let mut end = 0;
+        for segment in segments.iter().take(segments.len().saturating_sub(2)) {
+            settled(segment);
+            end += segment.len();
+        }
+        self.text.drain(..end);
+    }
+
+    /// Passes the remaining segments to `settled`.
+    fn finish(&mut self, mut settled: impl FnMut(&str)) {
+        let text = std::mem::take(&mut self.text);
+        for segment in (self.segments)(&text) {
+            settled(segment);
+        }
+    }
+}
+
+/// Whether text can be measured in two parts split right before `c` as
+/// well as in one. The width of a character depends on the ones after it,
+/// but these characters hide them from the ones before. This follows the
+/// contextual rules of unicode-width 0.1.14, which `Cargo.toml` pins: CR
+/// LF, the keycap and VS16 emoji sequences, the lam-alef ligature and the
+/// other ligatures and emoji sequences, none of which pass through these
+/// characters.
+fn is_width_boundary(c: char) -> bool {
+    match c {
+        // Line feeds, which make a preceding CR zero width, and the keycap
+        // emoji bases
+        '\n' | '#' | '*' | '0'..='9' => false,
+        '\0'..='\u{A0}' => true,
+        // Hiragana, katakana, CJK ideographs and Hangul syllables, for the
+        // scripts without spaces
+        '\u{3041}'..='\u{3096}'
+        | '\u{30A1}'..='\u{30FA}'
+        | '\u{4E00}'..='\u{9FFF}'
+        | '\u{AC00}'..='\u{D7A3}' => true,
+        _ => false,
+    }
+}
+
+/// Measures text that arrives in pieces like `Metrics::new` measures it at
+/// once, keeping the segmentations and the width going across the pieces.
+struct Measure {
+    metrics: Metrics,
+    graphemes: Pending,
+    words: Pending,
+    sentences: Pending,
+    /// The text after the last width boundary
+    width: String,
+}
+
+impl Measure {
+    fn new() -> Measure {
+        Measure {
+            metrics: Metrics::default(),
+            graphemes: Pending::new(grapheme_segments),
+            words: Pending::new(word_segments),
+            sentences: Pending::new(sentence_segments),
+            width: String::new(),
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        let metrics = &mut self.metrics;
+        metrics.utf8 += text.len();
+        for c in text.chars() {
+            metrics.utf16 += c.len_utf16();
+            metrics.utf32 += 1;
+            metrics.suspicious.add(c);
+        }
+        self.graphemes.push(text, |_| metrics.graphemes += 1);
+        self.words.push(text, |word| {
+            if has_alphanumeric(word) {
+                metrics.words += 1;
+            }
+        });
+        self.sentences.push(text, |sentence| {
+            if has_alphanumeric(sentence) {
+                metrics.sentences += 1;
+            }
+        });
+        self.width.push_str(text);
+        if let Some(end) = self.width.rfind(is_width_boundary) {
+            metrics.width += self.width[..end].width();
+            self.width.drain(..end);
+        }
+    }
+
+    fn finish(mut self) -> Metrics {
+        let metrics = &mut self.metrics;
+        self.graphemes.finish(|_| metrics.graphemes += 1);
+        self.words.finish(|word| {
+            if has_alphanumeric(word) {
+                metrics.words += 1;
+            }
+        });
+        self.sentences.finish(|sentence| {
+            if has_alphanumeric(sentence) {
+                metrics.sentences += 1;
+            }
+        });
+        metrics.width += self.width.width();
+        self.metrics
+    }
+}
+
+/// The number of scalar values of `raw` between the longest prefix and the
+/// longest suffix that it shares with `normalized`: the ones that
+/// normalizing replaces, reorders or removes.
+fn changed_chars(raw: &str, normalized: &str) -> usize {
+    let raw: Vec<char> = raw.chars().collect();
+    let normalized: Vec<char> = normalized.chars().collect();
+    let prefix = raw
+        .iter()
+        .zip(normalized.iter())
+        .take_while(|(r, n)| r == n)
+        .count();
+    let suffix = raw[prefix..]
+        .iter()
+        .rev()
+        .zip(normalized[prefix..].iter().rev())
+        .take_while(|(r, n)| r == n)
+        .count();
+    raw.len() - prefix - suffix
+}
+
+/// The NFC length of text that is normalized one grapheme cluster at a
+/// time. Normalization doesn't interact across grapheme cluster
+/// boundaries, so each cluster can be normalized on its own.
+#[derive(Default)]
+struct Nfc {
+    len: usize,
+    /// The scalar values that normalizing changes
+    changed: usize,
+}
+
+impl Nfc {
+    /// Appends `cluster` to `out`, normalized unless `as_is`.
+    fn add(&mut self, cluster: &str, as_is: bool, out: &mut String) {
+        // A single byte is ASCII, which is always in NFC.
+        if cluster.len() == 1 || cluster.nfc().eq(cluster.chars()) {
+            self.len += cluster.len();
+            out.push_str(cluster);
+            return;
+        }
+        let normalized: String = cluster.nfc().collect();
+        self.len += normalized.len();
+        self.changed += changed_chars(cluster, &normalized);
+        out.push_str(if as_is { cluster } else { &normalized });
+    }
+}
+
+/// Measures the extracted text of a translation as it arrives in pieces,
+/// like `Metrics::compute` measures it at once. Only the optional metrics
+/// that need the whole text keep a copy of it.
+pub struct Counter<'a> {
+    entry: &'a IndexEntry,
+    options: &'a CountOptions,
+    /// The raw text in grapheme clusters, to be normalized one at a time
+    clusters: Pending,
+    nfc: Nfc,
+    raw_len: usize,
+    measure: Measure,
+    /// The raw and the measured text
+    text: Option<(String, String)>,
+}
+
+impl<'a> Counter<'a> {
+    pub fn new(entry: &'a IndexEntry, options: &'a CountOptions) -> Counter<'a> {
+        // The blocks and the cluster sizes aren't columns.
+        let whole_text = options.blocks
+            || options.cluster_sizes
+            || options
+                .columns()
+                .iter()
+                .any(|m| !Metric::BASE.contains(m) && *m != Metric::NfcDelta);
+        Counter {
+            entry,
+            options,
+            clusters: Pending::new(grapheme_segments),
+            nfc: Nfc::default(),
+            raw_len: 0,
+            measure: Measure::new(),
+            text: whole_text.then(Default::default),
+        }
+    }
+
+    /// Appends the raw text `raw`.
+    pub fn push(&mut self, raw: &str) {
+        self.raw_len += raw.len();
+        let mut measured = String::with_capacity(raw.len());
+        let nfc = &mut self.nfc;
+        let as_is = self.options.no_normalize;
+        self.clusters
+            .push(raw, |cluster| nfc.add(cluster, as_is, &mut measured));
+        self.measure.push(&measured);
+        if let Some((whole_raw, whole_measured)) = &mut self.text {
+            whole_raw.push_str(raw);
+            whole_measured.push_str(&measured);
+        }
+    }
+
+    /// The metrics of all the text pushed.
+    pub fn finish(mut self) -> Metrics {
+        let mut measured = String::new();
+        let nfc = &mut self.nfc;
+        let as_is = self.options.no_normalize;
+        self.clusters
+            .finish(|cluster| nfc.add(cluster, as_is, &mut measured));
+        self.measure.push(&measured);
+        let mut metrics = self.measure.finish();
+        metrics.non_nfc = self.nfc.changed;
+        if self.options.no_normalize {
+            metrics.nfc_delta = Some(self.nfc.len as i64 - self.raw_len as i64);
+        }
+        if let Some((raw, mut whole_measured)) = self.text {
+            whole_measured.push_str(&measured);
+            metrics.add_optional(&raw, &whole_measured, self.entry, self.options);
+        }
+        metrics
+    }
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// The strongly directional characters of a text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Bidi {
+    /// Scalar values of the bidirectional class L
+    pub ltr: usize,
+    /// Scalar values of the bidirectional classes R and AL
+    pub rtl: usize,
+}
+
+impl Bidi {
+    pub fn new(text: &str) -> Bidi {
+        let property = CodePointMapData::<BidiClass>::new();
+        let mut bidi = Bidi::default();
+        for c in text.chars() {
+            match property.get(c) {
+                BidiClass::LeftToRight => bidi.ltr += 1,
+                BidiClass::RightToLeft | BidiClass::ArabicLetter => bidi.rtl += 1,
+                _ => {}
+            }
+        }
+        bidi
+    }
+
+    /// `rtl` if there are more right-to-left than left-to-right characters
+    /// and `ltr` otherwise.
+    pub fn direction(self) -> &'static str {
+        if self.rtl > self.ltr {
+            "rtl"
+        } else {
+            "ltr"
+        }
+    }
+}
+
+/// An encoding for all of Unicode beyond the UTFs.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EncodingScheme {
+    Scsu,
+    Bocu1,
+    Gb18030,
+    Cesu8,
+    Mutf8,
+    Utf7,
+    /// UTF-8 with the markup syntax characters escaped
+    Html,
+    /// ASCII with the markup syntax characters escaped and the rest as
+    /// numeric character references
+    Ncr,
+    /// A JSON string literal in UTF-8
+    Json,
+    /// A JSON string literal with the non-ASCII characters escaped
+    JsonAscii,
+    /// Quoted-printable UTF-8
+    QuotedPrintable,
+    /// RFC 2047 encoded-words in the B encoding
+    MimeB,
+    /// RFC 2047 encoded-words in the Q encoding
+    MimeQ,
+    /// Base64-encoded UTF-8
+    Base64,
+    /// Base64-encoded UTF-16LE
+    Base64Utf16,
+}
+
+impl EncodingScheme {
+    /// The number of bytes `text` takes in this encoding.
+    pub fn encoded_len(self, text: &str) -> usize {
+        match self {
+            EncodingScheme::Scsu => encode::scsu::encode(text).len(),
+            EncodingScheme::Bocu1 => encode::bocu1::encode(text).len(),
+            // GB18030 can represent every scalar value.
+            EncodingScheme::Gb18030 => encoding_rs::GB18030.encode(text).0.len(),
+            EncodingScheme::Cesu8 => encode::cesu8::encode(text).len(),
+            EncodingScheme::Mutf8 => encode::cesu8::encode_modified(text).len(),
+            EncodingScheme::Utf7 => encode::utf7::encode(text).len(),
+            EncodingScheme::Html => encode::markup::escape(text).len(),
+            EncodingScheme::Ncr => encode::markup::escape_ncr(text).len(),
+            EncodingScheme::Json => encode::json::escape(text).len(),
+            EncodingScheme::JsonAscii => encode::json::escape_ascii(text).len(),
+            EncodingScheme::QuotedPrintable => encode::mime::quoted_printable(text).len(),
+            EncodingScheme::MimeB => encode::mime::encoded_words_b(text).len(),
+            EncodingScheme::MimeQ => encode::mime::encoded_words_q(text).len(),
+            EncodingScheme::Base64 => encode::base64::encode(text).len(),
+            EncodingScheme::Base64Utf16 => encode::base64::encode_utf16(text).len(),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EncodingScheme::Scsu => "scsu",
+            EncodingScheme::Bocu1 => "bocu1",
+            EncodingScheme::Gb18030 => "gb18030",
+            EncodingScheme::Cesu8 => "cesu8",
+            EncodingScheme::Mutf8 => "mutf8",
+            EncodingScheme::Utf7 => "utf7",
+            EncodingScheme::Html => "html",
+            EncodingScheme::Ncr => "ncr",
+            EncodingScheme::Json => "json",
+            EncodingScheme::JsonAscii => "json_ascii",
+            EncodingScheme::QuotedPrintable => "qp",
+            EncodingScheme::MimeB => "mime_b",
+            EncodingScheme::MimeQ => "mime_q",
+            EncodingScheme::Base64 => "base64",
+            EncodingScheme::Base64Utf16 => "base64_utf16",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EncodingScheme::Scsu => "SCSU",
+            EncodingScheme::Bocu1 => "BOCU-1",
+            EncodingScheme::Gb18030 => "GB18030",
+            EncodingScheme::Cesu8 => "CESU-8",
+            EncodingScheme::Mutf8 => "MUTF-8",
+            EncodingScheme::Utf7 => "UTF-7",
+            EncodingScheme::Html => "HTML-escaped",
+            EncodingScheme::Ncr => "NCR",
+            EncodingScheme::Json => "JSON",
+            EncodingScheme::JsonAscii => "JSON (ASCII)",
+            EncodingScheme::QuotedPrintable => "QP",
+            EncodingScheme::MimeB => "MIME B",
+            EncodingScheme::MimeQ => "MIME Q",
+            EncodingScheme::Base64 => "Base64",
+            EncodingScheme::Base64Utf16 => "Base64 UTF-16",
+        }
+    }
+}
+
+impl FromStr for EncodingScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<EncodingScheme, String> {
+        match &s.trim().to_ascii_lowercase().replace(['-', '_'], "")[..] {
+            "scsu" => Ok(EncodingScheme::Scsu),
+            "bocu1" => Ok(EncodingScheme::Bocu1),
+            "gb18030" => Ok(EncodingScheme::Gb18030),
+            "cesu8" => Ok(EncodingScheme::Cesu8),
+            "mutf8" => Ok(EncodingScheme::Mutf8),
+            "utf7" => Ok(EncodingScheme::Utf7),
+            "html" => Ok(EncodingScheme::Html),
+            "ncr" | "xml" => Ok(EncodingScheme::Ncr),
+            "json" => Ok(EncodingScheme::Json),
+            "jsonascii" => Ok(EncodingScheme::JsonAscii),
+            "qp" | "quotedprintable" => Ok(EncodingScheme::QuotedPrintable),
+            "mimeb" => Ok(EncodingScheme::MimeB),
+            "mimeq" => Ok(EncodingScheme::MimeQ),
+            "base64" => Ok(EncodingScheme::Base64),
+            "base64utf16" => Ok(EncodingScheme::Base64Utf16),
+            _ => Err(format!("unknown encoding '{}'", s.trim())),
+        }
+    }
+}
+
+/// A general-purpose compression algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CompressionAlgorithm {
+    Gzip,
+    Brotli,
+    Zstd,
+}
+
+impl CompressionAlgorithm {
+    pub fn name(self) -> &'static str {
+        match self {
+            CompressionAlgorithm::Gzip => "gzip",
+            CompressionAlgorithm::Brotli => "brotli",
+            CompressionAlgorithm::Zstd => "zstd",
+        }
+    }
+
+    /// The levels the algorithm supports.
+    pub fn levels(self) -> std::ops::RangeInclusive<u32> {
+        match self {
+            CompressionAlgorithm::Gzip => 0..=9,
+            CompressionAlgorithm::Brotli => 0..=11,
+            CompressionAlgorithm::Zstd => 1..=22,
+        }
+    }
+
+    /// The level of the command line tool of the same name.
+    pub fn default_level(self) -> u32 {
+        match self {
+            CompressionAlgorithm::Gzip => 6,
+            CompressionAlgorithm::Brotli => 11,
+            CompressionAlgorithm::Zstd => 3,
+        }
+    }
+}
+
+/// A compression algorithm at a particular level, written like `gzip` or
+/// `zstd:19`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Compression {
+    pub algorithm: CompressionAlgorithm,
+    pub level: u32,
+}
+
+impl Compression {
+    /// The number of bytes `text` takes as UTF-8 after compression.
+    pub fn compressed_len(self, text: &str) -> usize {
+        // Compressing into memory only fails on invalid parameters, which
+        // `FromStr` rules out.
+        let bytes = text.as_bytes();
+        match self.algorithm {
+            CompressionAlgorithm::Gzip => {
+                let level = flate2::Compression::new(self.level);
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), level);
+                encoder.write_all(bytes).expect("gzip into memory");
+                encoder.finish().expect("gzip into memory").len()
+            }
+            CompressionAlgorithm::Brotli => {
+                let params = brotli::enc::BrotliEncoderParams {
+                    quality: self.level as i32,
+                    ..Default::default()
+                };
+                let mut compressed = Vec::new();
+                brotli::BrotliCompress(&mut &bytes[..], &mut compressed, &params)
+                    .expect("brotli into memory");
+                compressed.len()
+            }
+            CompressionAlgorithm::Zstd => zstd::bulk::compress(bytes, self.level as i32)
+                .expect("zstd into memory")
+                .len(),
+        }
+    }
+
+    /// The identifier used in machine-readable output, e.g. `gzip_6`.
+    pub fn name(self) -> String {
+        format!("{}_{}", self.algorithm.name(), self.level)
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Compression, String> {
+        let s = s.trim();
+        let (name, level) = match s.split_once(':') {
+            Some((name, level)) => (name, Some(level)),
+            None => (s, None),
+        };
+        let algorithm = match &name.to_ascii_lowercase()[..] {
+            "gzip" => CompressionAlgorithm::Gzip,
+            "brotli" => CompressionAlgorithm::Brotli,
+            "zstd" => CompressionAlgorithm::Zstd,
+            _ => return Err(format!("unknown compression algorithm '{}'", name)),
+        };
+        let level = match level {
+            Some(level) => level
+                .parse()
+                .ok()
+                .filter(|level| algorithm.levels().contains(level))
+                .ok_or_else(|| {
+                    let levels = algorithm.levels();
+                    format!(
+                        "invalid {} level '{}', expected {} to {}",
+                        algorithm.name(),
+                        level,
+                        levels.start(),
+                        levels.end()
+                    )
+                })?,
+            None => algorithm.default_level(),
+        };
+        Ok(Compression { algorithm, level })
+    }
+}
+
+impl Serialize for Compression {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+/// Described as the string that it is serialized as.
+impl JsonSchema for Compression {
+    fn schema_name() -> Cow<'static, str> {
+        "Compression".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        String::json_schema(generator)
+    }
+}
+
+impl<'de> Deserialize<'de> for Compression {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Compression, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.replacen('_', ":", 1)
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// The length of a piece of text in a legacy encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct LegacyLength {
+    pub encoding: &'static str,
+    /// Not counting the unmappable characters
+    pub bytes: usize,
+    /// The number of characters that the encoding can't represent
+    pub unmappable: usize,
+}
+
+/// Looks up the `encoding_rs` encoding by its name.
+impl<'de> Deserialize<'de> for LegacyLength {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LegacyLength, D::Error> {
+        #[derive(Deserialize)]
+        struct Named {
+            encoding: String,
+            bytes: usize,
+            unmappable: usize,
+        }
+        let named = Named::deserialize(deserializer)?;
+        let encoding = Encoding::for_label(named.encoding.as_bytes()).ok_or_else(|| {
+            serde::de::Error::custom(format!("unknown encoding '{}'", named.encoding))
+        })?;
+        Ok(LegacyLength {
+            encoding: encoding.name(),
+            bytes: named.bytes,
+            unmappable: named.unmappable,
+        })
+    }
+}
+
+/// The order-0 Shannon entropy of a piece of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Entropy {
+    /// Bits per scalar value
+    pub per_char: f64,
+    /// Bits per UTF-8 byte
+    pub per_byte: f64,
+}
+
+impl Entropy {
+    pub fn new(text: &str) -> Entropy {
+        Entropy {
+            per_char: shannon_entropy(text.chars()),
+            per_byte: shannon_entropy(text.bytes()),
+        }
+    }
+}
+
+/// The grapheme cluster and width counts according to ICU4X.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Icu4xCounts {
+    pub graphemes: usize,
+    pub width: usize,
+}
+
+impl Icu4xCounts {
+    pub fn new(text: &str) -> Icu4xCounts {
+        Icu4xCounts {
+            graphemes: icu4x::graphemes(text),
+            width: icu4x::width(text),
+        }
+    }
+}
+
+/// The entropy in bits per symbol of the distribution of `symbols`.
+fn shannon_entropy<T: Hash + Eq>(symbols: impl Iterator<Item = T>) -> f64 {
+    let mut frequencies = HashMap::new();
+    let mut total = 0usize;
+    for symbol in symbols {
+        *frequencies.entry(symbol).or_insert(0usize) += 1;
+        total += 1;
+    }
+    let total = total as f64;
+    frequencies
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The lengths of a piece of text in the different units.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct Metrics {
+    /// UTF-8 code units
+    pub utf8: usize,
+    /// UTF-16 code units
+    pub utf16: usize,
+    /// Unicode scalar values
+    pub utf32: usize,
+    /// Extended grapheme clusters
+    pub graphemes: usize,
+    /// East Asian Width
+    pub width: usize,
+    /// East Asian Width with the ambiguous characters taken as wide, as in
+    /// East Asian contexts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width_cjk: Option<usize>,
+    /// The number of scalar values in each East Asian Width category
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub east_asian_widths: BTreeMap<EastAsianWidthCategory, usize>,
+    /// The number of scalar values in each major General_Category class
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub categories: BTreeMap<CategoryClass, usize>,
+    /// Scalar values with the General_Category Mn, Mc or Me
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combining_marks: Option<usize>,
+    /// Scalar values outside the Basic Multilingual Plane
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub astral: Option<usize>,
+    /// The script runs and the amount of text in other scripts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub script_runs: Option<ScriptRuns>,
+    /// The strongly directional characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bidi: Option<Bidi>,
+    /// The length of the longest extended grapheme cluster
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longest_grapheme: Option<LongestGrapheme>,
+    /// The number of emoji, zero width joiners and variation selectors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emoji: Option<Emoji>,
+    /// The number of distinct scalar values and extended grapheme clusters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinct: Option<Distinct>,
+    /// The number of scalar values in each Unicode block
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<BlockCount>,
+    /// The number of extended grapheme clusters of each length in scalar
+    /// values
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub cluster_sizes: BTreeMap<usize, usize>,
+    /// UAX #29 words, i.e. the segments between word boundaries that
+    /// contain a letter or a digit. A run of katakana is one word, but each
+    /// ideograph and hiragana is a word of its own
+    pub words: usize,
+    /// UAX #29 sentences that contain a letter or a digit
+    pub sentences: usize,
+    /// The replacement characters, controls and private-use characters,
+    /// which shouldn't occur in the translations
+    #[serde(skip_serializing_if = "Suspicious::is_empty")]
+    pub suspicious: Suspicious,
+    /// The scalar values of the distributed text that normalizing to NFC
+    /// changes
+    #[serde(skip_serializing_if = "is_zero")]
+    pub non_nfc: usize,
+    /// The encoded lengths after normalizing to the requested forms
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub normalized: BTreeMap<NormalizationForm, EncodedLengths>,
+    /// The encoded lengths after applying the requested case mappings
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub case_mapped: BTreeMap<CaseMapping, EncodedLengths>,
+    /// The lengths in bytes in the requested encoding schemes
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub encoded: BTreeMap<EncodingScheme, usize>,
+    /// The length in the legacy encoding of the language
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub legacy: Option<LegacyLength>,
+    /// The entropy of the scalar values and of the UTF-8 bytes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<Entropy>,
+    /// The compressed UTF-8 lengths with the requested algorithms
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub compressed: BTreeMap<Compression, usize>,
+    /// The number of tokens with the requested vocabularies
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tokens: BTreeMap<Tokenizer, usize>,
+    /// How many more UTF-8 bytes the text takes after normalizing it to
+    /// NFC, when the text is measured as-is
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nfc_delta: Option<i64>,
+    /// The grapheme cluster and width counts according to ICU4X
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icu4x: Option<Icu4xCounts>,
+}
+
+impl Metrics {
+    /// Measures `text` as-is. Normalize first if that's desired.
+    pub fn new(text: &str) -> Metrics {
+        let mut measure = Measure::new();
+        measure.push(text);
+        measure.finish()
+    }
+
+    /// The name of the measured encoding that takes the fewest bytes,
+    /// preferring the earlier of UTF-8, UTF-16, UTF-32, the requested
+    /// encoding schemes and the legacy encoding on ties. The legacy encoding
+    /// only counts if it can represent the whole text.
+    pub fn smallest_encoding(&self) -> &'static str {
+        let mut candidates = vec![
+            ("UTF-8", self.utf8),
+            ("UTF-16", 2 * self.utf16),
+            ("UTF-32", 4 * self.utf32),
+        ];
+        candidates.extend(
+            self.encoded
+                .iter()
+                .map(|(scheme, &len)| (scheme.label(), len)),
+        );
+        if let Some(legacy) = self.legacy.filter(|l| l.unmappable == 0) {
+            candidates.push((legacy.encoding, legacy.bytes));
+        }
+        candidates
+            .into_iter()
+            .min_by_key(|&(_, len)| len)
+            .map_or("UTF-8", |(name, _)| name)
+    }
+
+    /// Normalizes the extracted text `raw` of the translation `entry` to
+    /// NFC, unless `options` say otherwise, and measures it together with
+    /// the optional metrics requested in `options`.
+    pub fn compute(raw: &str, entry: &IndexEntry, options: &CountOptions) -> Metrics {
+        let mut counter = Counter::new(entry, options);
+        counter.push(raw);
+        counter.finish()
+    }
+
+    /// Computes the optional metrics requested in `options` of the
+    /// extracted text `raw`, which is `measured` after normalization.
+    fn add_optional(
+        &mut self,
+        raw: &str,
+        measured: &str,
+        entry: &IndexEntry,
+        options: &CountOptions,
+    ) {
+        if options.ambiguous_wide {
+            self.width_cjk = Some(measured.width_cjk());
+        }
+        if options.eaw_breakdown {
+            self.east_asian_widths = EastAsianWidthCategory::count(measured);
+        }
+        if options.category_breakdown {
+            self.categories = CategoryClass::count(measured);
+        }
+        if options.composition {
+            let general_category = CodePointMapData::<GeneralCategory>::new();
+            self.combining_marks = Some(
+                measured
+                    .chars()
+                    .filter(|&c| {
+                        matches!(
+                            general_category.get(c),
+                            GeneralCategory::NonspacingMark
+                                | GeneralCategory::SpacingMark
+                                | GeneralCategory::EnclosingMark
+                        )
+                    })
+                    .count(),
+            );
+            self.astral = Some(measured.chars().filter(|&c| c > '\u{FFFF}').count());
+        }
+        if options.script_runs {
+            self.script_runs = Some(ScriptRuns::new(measured, &entry.script));
+        }
+        if options.distinct {
+            self.distinct = Some(Distinct::new(measured));
+        }
+        if options.longest_grapheme {
+            self.longest_grapheme = Some(LongestGrapheme::new(measured));
+        }
+        if options.emoji {
+            self.emoji = Some(Emoji::new(measured));
+        }
+        if options.bidi {
+            self.bidi = Some(Bidi::new(measured));
+        }
+        if options.blocks {
+            self.blocks = count_blocks(measured);
+        }
+        if options.cluster_sizes {
+            for grapheme in Graphemes::new(measured) {
+                *self
+                    .cluster_sizes
+                    .entry(grapheme.chars().count())
+                    .or_insert(0) += 1;
+            }
+        }
+        for &form in options.normalizations.iter() {
+            let normalized = form.normalize(raw);
+            self.normalized
+                .insert(form, EncodedLengths::new(&normalized));
+        }
+        for &mapping in options.case_mappings.iter() {
+            self.case_mapped
+                .insert(mapping, EncodedLengths::new(&mapping.apply(measured)));
+        }
+        for &scheme in options.encodings.iter() {
+            self.encoded.insert(scheme, scheme.encoded_len(measured));
+        }
+        if options.legacy {
+            self.legacy =
+                encode::legacy::encoding_for(entry).map(|e| encode::legacy::measure(measured, e));
+        }
+        if options.entropy {
+            self.entropy = Some(Entropy::new(measured));
+        }
+        for &compression in options.compressions.iter() {
+            self.compressed
+                .insert(compression, compression.compressed_len(measured));
+        }
+        #[cfg(feature = "tokenizer")]
+        for &tokenizer in options.tokenizers.iter() {
+            self.tokens.insert(tokenizer, tokenizer.count(measured));
+        }
+        if options.icu4x {
+            self.icu4x = Some(Icu4xCounts::new(measured));
+        }
+    }
+
+    /// Whether more than `threshold` of the scalar values are in scripts
+    /// other than the declared one, if the script runs were analyzed.
+    pub fn is_mixed_script(&self, threshold: f64) -> bool {
+        self.script_runs
+            .is_some_and(|runs| runs.foreign as f64 > threshold * self.utf32 as f64)
+    }
+
+    /// Whether ICU4X was asked for and disagrees with the grapheme cluster
+    /// or the width count.
+    pub fn icu4x_disagrees(&self) -> bool {
+        self.icu4x
+            .is_some_and(|icu4x| icu4x.graphemes != self.graphemes || icu4x.width != self.width)
+    }
+}
+
+/// Identifies one of the values in `Metrics`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Utf8,
+    Utf16,
+    Utf32,
+    Graphemes,
+    Width,
+    Words,
+    Sentences,
+    WidthCjk,
+    EastAsianWidth(EastAsianWidthCategory),
+    Category(CategoryClass),
+    CombiningMarks,
+    Astral,
+    ScriptRuns,
+    ForeignScript,
+    DistinctChars,
+    DistinctGraphemes,
+    LongestGraphemeChars,
+    LongestGraphemeBytes,
+    Emoji,
+    Zwj,
+    VariationSelectors,
+    /// The percentage of scalar values that are strongly right-to-left
+    RtlPercent,
+    Normalized(NormalizationForm, EncodingForm),
+    CaseMapped(CaseMapping, EncodingForm),
+    Encoded(EncodingScheme),
+    Legacy,
+    LegacyUnmappable,
+    Compressed(Compression),
+    Tokens(Tokenizer),
+    CharEntropy,
+    ByteEntropy,
+    NfcDelta,
+    Icu4xGraphemes,
+    Icu4xWidth,
+    /// UTF-16 bytes per UTF-8 byte
+    Utf16PerUtf8,
+    /// UTF-8 bytes per extended grapheme cluster
+    Utf8PerGrapheme,
+    /// Scalar values per extended grapheme cluster
+    Utf32PerGrapheme,
+    /// UTF-8 bytes per word
+    Utf8PerWord,
+    /// Extended grapheme clusters per word
+    GraphemesPerWord,
+}
+
+impl Serialize for Metric {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for Metric {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Metric, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// `numerator / denominator` unless the denominator is zero.
+fn ratio(numerator: usize, denominator: usize) -> Option<f64> {
+    if denominator == 0 {
+        None
+    } else {
+        Some(numerator as f64 / denominator as f64)
+    }
+}
+
+impl Metric {
+    /// Whether this metric is one of the measured `columns` or derived from
+    /// the base metrics.
+    pub fn is_measured_in(self, columns: &[Metric]) -> bool {
+        Metric::RATIOS.contains(&self) || columns.contains(&self)
+    }
+
+    /// The metrics that are always computed.
+    pub const BASE: [Metric; 7] = [
+        Metric::Utf8,
+        Metric::Utf16,
+        Metric::Utf32,
+        Metric::Graphemes,
+        Metric::Width,
+        Metric::Words,
+        Metric::Sentences,
+    ];
+
+    /// The ratios between the base metrics, which are only reported when
+    /// selected.
+    pub const RATIOS: [Metric; 5] = [
+        Metric::Utf16PerUtf8,
+        Metric::Utf8PerGrapheme,
+        Metric::Utf32PerGrapheme,
+        Metric::Utf8PerWord,
+        Metric::GraphemesPerWord,
+    ];
+
+    /// The value of this metric in `metrics`, if it was computed.
+    pub fn value(self, metrics: &Metrics) -> Option<f64> {
+        let count = match self {
+            Metric::Utf8 => metrics.utf8,
+            Metric::Utf16 => metrics.utf16,
+            Metric::Utf32 => metrics.utf32,
+            Metric::Graphemes => metrics.graphemes,
+            Metric::Width => metrics.width,
+            Metric::Words => metrics.words,
+            Metric::Sentences => metrics.sentences,
+            Metric::WidthCjk => metrics.width_cjk?,
+            Metric::EastAsianWidth(category) => *metrics.east_asian_widths.get(&category)?,
+            Metric::Category(class) => *metrics.categories.get(&class)?,
+            Metric::CombiningMarks => metrics.combining_marks?,
+            Metric::Astral => metrics.astral?,
+            Metric::ScriptRuns => metrics.script_runs?.runs,
+            Metric::ForeignScript => metrics.script_runs?.foreign,
+            Metric::DistinctChars => metrics.distinct?.chars,
+            Metric::DistinctGraphemes => metrics.distinct?.graphemes,
+            Metric::LongestGraphemeChars => metrics.longest_grapheme?.chars,
+            Metric::LongestGraphemeBytes => metrics.longest_grapheme?.bytes,
+            Metric::Emoji => metrics.emoji?.pictographic,
+            Metric::Zwj => metrics.emoji?.zwj,
+            Metric::VariationSelectors => metrics.emoji?.variation_selectors,
+            Metric::RtlPercent => {
+                let rtl = metrics.bidi?.rtl as f64;
+                return Some(if metrics.utf32 == 0 {
+                    0.0
+                } else {
+                    rtl / metrics.utf32 as f64 * 100.0
+                });
+            }
+            Metric::Normalized(form, encoding) => metrics.normalized.get(&form)?.get(encoding),
+            Metric::CaseMapped(mapping, encoding) => {
+                metrics.case_mapped.get(&mapping)?.get(encoding)
+            }
+            Metric::Encoded(scheme) => *metrics.encoded.get(&scheme)?,
+            Metric::Legacy => metrics.legacy?.bytes,
+            Metric::LegacyUnmappable => metrics.legacy?.unmappable,
+            Metric::Compressed(compression) => *metrics.compressed.get(&compression)?,
+            Metric::Tokens(tokenizer) => *metrics.tokens.get(&tokenizer)?,
+            Metric::CharEntropy => return Some(metrics.entropy?.per_char),
+            Metric::ByteEntropy => return Some(metrics.entropy?.per_byte),
+            Metric::NfcDelta => return metrics.nfc_delta.map(|delta| delta as f64),
+            Metric::Icu4xGraphemes => metrics.icu4x?.graphemes,
+            Metric::Icu4xWidth => metrics.icu4x?.width,
+            Metric::Utf16PerUtf8 => return ratio(2 * metrics.utf16, metrics.utf8),
+            Metric::Utf8PerGrapheme => return ratio(metrics.utf8, metrics.graphemes),
+            Metric::Utf32PerGrapheme => return ratio(metrics.utf32, metrics.graphemes),
+            Metric::Utf8PerWord => return ratio(metrics.utf8, metrics.words),
+            Metric::GraphemesPerWord => return ratio(metrics.graphemes, metrics.words),
+        };
+        Some(count as f64)
+    }
+
+    /// Whether the values are whole numbers.
+    pub fn is_count(self) -> bool {
+        !matches!(
+            self,
+            Metric::CharEntropy | Metric::ByteEntropy | Metric::RtlPercent
+        ) && !Metric::RATIOS.contains(&self)
+    }
+
+    /// The identifier used in machine-readable output.
+    pub fn name(self) -> String {
+        match self {
+            Metric::Utf8 => "utf8".to_string(),
+            Metric::Utf16 => "utf16".to_string(),
+            Metric::Utf32 => "utf32".to_string(),
+            Metric::Graphemes => "graphemes".to_string(),
+            Metric::Width => "width".to_string(),
+            Metric::Words => "words".to_string(),
+            Metric::Sentences => "sentences".to_string(),
+            Metric::WidthCjk => "width_cjk".to_string(),
+            Metric::EastAsianWidth(category) => format!("eaw_{}", category.name()),
+            Metric::Category(class) => format!("gc_{}", class.name()),
+            Metric::CombiningMarks => "combining_marks".to_string(),
+            Metric::Astral => "astral".to_string(),
+            Metric::ScriptRuns => "script_runs".to_string(),
+            Metric::ForeignScript => "foreign_script".to_string(),
+            Metric::DistinctChars => "distinct_chars".to_string(),
+            Metric::DistinctGraphemes => "distinct_graphemes".to_string(),
+            Metric::LongestGraphemeChars => "longest_grapheme_chars".to_string(),
+            Metric::LongestGraphemeBytes => "longest_grapheme_bytes".to_string(),
+            Metric::Emoji => "emoji".to_string(),
+            Metric::Zwj => "zwj".to_string(),
+            Metric::VariationSelectors => "variation_selectors".to_string(),
+            Metric::RtlPercent => "rtl_percent".to_string(),
+            Metric::Normalized(form, encoding) => format!("{}_{}", form.name(), encoding.name()),
+            Metric::CaseMapped(mapping, encoding) => {
+                format!("{}_{}", mapping.name(), encoding.name())
+            }
+            Metric::Encoded(scheme) => scheme.name().to_string(),
+            Metric::Legacy => "legacy".to_string(),
+            Metric::LegacyUnmappable => "legacy_unmappable".to_string(),
+            Metric::Compressed(compression) => compression.name(),
+            Metric::Tokens(tokenizer) => format!("{}_tokens", tokenizer.name()),
+            Metric::CharEntropy => "char_entropy".to_string(),
+            Metric::ByteEntropy => "byte_entropy".to_string(),
+            Metric::NfcDelta => "nfc_delta".to_string(),
+            Metric::Icu4xGraphemes => "icu4x_graphemes".to_string(),
+            Metric::Icu4xWidth => "icu4x_width".to_string(),
+            Metric::Utf16PerUtf8 => "utf16_per_utf8".to_string(),
+            Metric::Utf8PerGrapheme => "utf8_per_egc".to_string(),
+            Metric::Utf32PerGrapheme => "utf32_per_egc".to_string(),
+            Metric::Utf8PerWord => "utf8_per_word".to_string(),
+            Metric::GraphemesPerWord => "egc_per_word".to_string(),
+        }
+    }
+
+    /// The column heading used in reports.
+    pub fn label(self) -> String {
+        match self {
+            Metric::Utf8 => "UTF-8".to_string(),
+            Metric::Utf16 => "UTF-16".to_string(),
+            Metric::Utf32 => "UTF-32".to_string(),
+            Metric::Graphemes => "EGC".to_string(),
+            Metric::Width => "EAW".to_string(),
+            Metric::Words => "Words".to_string(),
+            Metric::Sentences => "Sentences".to_string(),
+            Metric::WidthCjk => "EAW (CJK)".to_string(),
+            Metric::EastAsianWidth(category) => format!("EAW={}", category.abbreviation()),
+            Metric::Category(class) => class.label().to_string(),
+            Metric::CombiningMarks => "Combining".to_string(),
+            Metric::Astral => "Astral".to_string(),
+            Metric::ScriptRuns => "Script runs".to_string(),
+            Metric::ForeignScript => "Other script".to_string(),
+            Metric::DistinctChars => "Distinct CPs".to_string(),
+            Metric::DistinctGraphemes => "Distinct EGCs".to_string(),
+            Metric::LongestGraphemeChars => "Longest EGC (CPs)".to_string(),
+            Metric::LongestGraphemeBytes => "Longest EGC (bytes)".to_string(),
+            Metric::Emoji => "Emoji".to_string(),
+            Metric::Zwj => "ZWJ".to_string(),
+            Metric::VariationSelectors => "VS".to_string(),
+            Metric::RtlPercent => "RTL %".to_string(),
+            Metric::Normalized(form, encoding) => {
+                format!("{} {}", form.name().to_ascii_uppercase(), encoding.label())
+            }
+            Metric::CaseMapped(mapping, encoding) => {
+                format!("{} {}", mapping.label(), encoding.label())
+            }
+            Metric::Encoded(scheme) => scheme.label().to_string(),
+            Metric::Legacy => "Legacy".to_string(),
+            Metric::LegacyUnmappable => "Unmappable".to_string(),
+            Metric::Compressed(compression) => {
+                format!("{} {}", compression.algorithm.name(), compression.level)
+            }
+            Metric::Tokens(tokenizer) => format!("{} tokens", tokenizer.label()),
+            Metric::CharEntropy => "Bits/char".to_string(),
+            Metric::ByteEntropy => "Bits/byte".to_string(),
+            Metric::NfcDelta => "NFC Δ UTF-8".to_string(),
+            Metric::Icu4xGraphemes => "ICU4X EGC".to_string(),
+            Metric::Icu4xWidth => "ICU4X EAW".to_string(),
+            Metric::Utf16PerUtf8 => "UTF-16÷UTF-8".to_string(),
+            Metric::Utf8PerGrapheme => "UTF-8÷EGC".to_string(),
+            Metric::Utf32PerGrapheme => "UTF-32÷EGC".to_string(),
+            Metric::Utf8PerWord => "UTF-8÷word".to_string(),
+            Metric::GraphemesPerWord => "EGC÷word".to_string(),
+        }
+    }
+
+    /// Formats `value` of this metric for display.
+    pub fn format(self, value: f64) -> String {
+        if self.is_count() {
+            format!("{}", value as i64)
+        } else {
+            format!("{:.2}", value)
+        }
+    }
+}
+
+impl FromStr for Metric {
+    type Err = String;
+
+    /// Parses the names returned by `name()` as well as `egc` and `eaw`
+    /// and, for compressed lengths, the syntax of `Compression`.
+    fn from_str(s: &str) -> Result<Metric, String> {
+        let name = s.trim().to_ascii_lowercase();
+        let metric = match &name[..] {
+            "utf8" => Metric::Utf8,
+            "utf16" => Metric::Utf16,
+            "utf32" => Metric::Utf32,
+            "graphemes" | "egc" => Metric::Graphemes,
+            "width" | "eaw" => Metric::Width,
+            "words" => Metric::Words,
+            "sentences" => Metric::Sentences,
+            "width_cjk" | "eaw_cjk" => Metric::WidthCjk,
+            "combining_marks" => Metric::CombiningMarks,
+            "astral" => Metric::Astral,
+            "script_runs" => Metric::ScriptRuns,
+            "foreign_script" => Metric::ForeignScript,
+            "distinct_chars" => Metric::DistinctChars,
+            "distinct_graphemes" | "distinct_egc" => Metric::DistinctGraphemes,
+            "longest_grapheme_chars" | "longest_egc_chars" => Metric::LongestGraphemeChars,
+            "longest_grapheme_bytes" | "longest_egc_bytes" => Metric::LongestGraphemeBytes,
+            "emoji" => Metric::Emoji,
+            "zwj" => Metric::Zwj,
+            "variation_selectors" | "vs" => Metric::VariationSelectors,
+            "rtl_percent" | "rtl" => Metric::RtlPercent,
+            "legacy" => Metric::Legacy,
+            "legacy_unmappable" => Metric::LegacyUnmappable,
+            "char_entropy" => Metric::CharEntropy,
+            "byte_entropy" => Metric::ByteEntropy,
+            "nfc_delta" => Metric::NfcDelta,
+            "icu4x_graphemes" | "icu4x_egc" => Metric::Icu4xGraphemes,
+            "icu4x_width" | "icu4x_eaw" => Metric::Icu4xWidth,
+            "utf16_per_utf8" => Metric::Utf16PerUtf8,
+            "utf8_per_egc" | "utf8_per_grapheme" => Metric::Utf8PerGrapheme,
+            "utf32_per_egc" | "utf32_per_grapheme" => Metric::Utf32PerGrapheme,
+            "utf8_per_word" => Metric::Utf8PerWord,
+            "egc_per_word" | "graphemes_per_word" => Metric::GraphemesPerWord,
+            _ => {
+                if let Some(category) = EastAsianWidthCategory::ALL
+                    .iter()
+                    .find(|c| name.strip_prefix("eaw_") == Some(c.name()))
+                {
+                    return Ok(Metric::EastAsianWidth(*category));
+                }
+                if let Some(class) = CategoryClass::ALL
+                    .iter()
+                    .find(|c| name.strip_prefix("gc_") == Some(c.name()))
+                {
+                    return Ok(Metric::Category(*class));
+                }
+                if let Ok(scheme) = name.parse() {
+                    return Ok(Metric::Encoded(scheme));
+                }
+                if let Some(tokenizer) = name.strip_suffix("_tokens") {
+                    return tokenizer.parse().map(Metric::Tokens);
+                }
+                if let Some((form, encoding)) = name.rsplit_once('_') {
+                    let encoding = EncodingForm::ALL.iter().find(|e| e.name() == encoding);
+                    if let (Ok(form), Some(&encoding)) = (form.parse(), encoding) {
+                        return Ok(Metric::Normalized(form, encoding));
+                    }
+                    if let (Ok(mapping), Some(&encoding)) = (form.parse(), encoding) {
+                        return Ok(Metric::CaseMapped(mapping, encoding));
+                    }
+                }
+                match name.replacen('_', ":", 1).parse() {
+                    Ok(compression) => Metric::Compressed(compression),
+                    Err(_) => return Err(format!("unknown metric '{}'", s.trim())),
+                }
+            }
+        };
+        Ok(metric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+    use super::Metrics;
+    use crate::CountOptions;
+    use crate::IndexEntry;
+
+    fn entry() -> IndexEntry {
+        IndexEntry {
+            name: String::new(),
+            code: String::new(),
+            script: String::new(),
+            stage: 0,
+        }
+    }
+
+    /// Asserts that pushing `parts` one at a time measures the same as
+    /// measuring them joined, with and without normalizing.
+    fn check_parts(parts: &[&str]) {
+        let entry = entry();
+        let joined = parts.concat();
+        for &no_normalize in [false, true].iter() {
+            let options = CountOptions {
+                no_normalize,
+                ..CountOptions::default()
+            };
+            let mut counter = Counter::new(&entry, &options);
+            for part in parts {
+                counter.push(part);
+            }
+            assert_eq!(
+                counter.finish(),
+                Metrics::compute(&joined, &entry, &options),
+                "{:?}",
+                parts
+            );
+        }
+    }
+
+    /// Checks `text` pushed a scalar value at a time and split in two at
+    /// every scalar value.
+    fn check(text: &str) {
+        let chars: Vec<String> = text.chars().map(String::from).collect();
+        check_parts(&chars.iter().map(String::as_str).collect::<Vec<_>>());
+        for (i, _) in text.char_indices() {
+            check_parts(&[&text[..i], &text[i..]]);
+        }
+    }
+
+    #[test]
+    fn chunk_boundaries() {
+        check_parts(&["a\r", "\nb"]);
+        check_parts(&["#", "\u{FE0F}\u{20E3}"]);
+        check_parts(&["\u{1F1EB}", "\u{1F1EE}\u{1F1F8}", "\u{1F1EE}"]);
+        check_parts(&["\u{1100}", "\u{1161}\u{11A8}"]);
+        check_parts(&["e", "\u{301}"]);
+        check_parts(&["Viet", "\u{323}\u{302}"]);
+        check_parts(&["It costs 5 etc. ", "5 lower. And more."]);
+        check_parts(&["\u{1F468}\u{200D}", "\u{1F469}\u{200D}\u{1F467}"]);
+        check_parts(&["\u{644}", "\u{627} \u{644}\u{627}"]);
+    }
+
+    #[test]
+    fn non_nfc() {
+        let non_nfc =
+            |text: &str| Metrics::compute(text, &entry(), &CountOptions::default()).non_nfc;
+        assert_eq!(non_nfc("caf\u{E9}"), 0);
+        assert_eq!(non_nfc("cafe\u{301}"), 2);
+        // Only the decomposed letter changes, not the marks after it.
+        assert_eq!(non_nfc("e\u{301}\u{302}\u{303}\u{304}"), 2);
+        // Reordered marks
+        assert_eq!(non_nfc("a\u{301}\u{316}"), 2);
+        assert_eq!(non_nfc("\u{1100}\u{1161}\u{11A8}"), 3);
+    }
+
+    #[test]
+    fn every_split() {
+        check("a\r\nb\r\n\r");
+        check("#\u{FE0F}\u{20E3} 1\u{20E3}*\u{FE0F}");
+        check("\u{1F1EB}\u{1F1EE}\u{1F1F8}\u{1F1EE}\u{1F1EB} x");
+        check("\u{1100}\u{1161}\u{11A8}\u{AC00}\u{11A8}");
+        check("e\u{301}\u{323}A\u{30A}\u{301}\u{316}\u{301}");
+        check("Mr. Smith etc. 5 lower. (a) The end.\u{201D} Next? yes! No.");
+        check("\u{1F468}\u{1F3FB}\u{200D}\u{1F469}\u{200D}\u{1F467} \u{26A0}\u{FE0E}");
+        check("\u{644}\u{64E}\u{627} \u{1780}\u{17D2}\u{1780} \u{5D0}\u{200D}\u{5DC}");
+        check("\u{65E5}\u{672C}\u{8A9E}\u{3002}\u{30AB}\u{30BF}\u{30AB}\u{30CA}\u{3042}");
+        check("a.b 3.5 can\u{2019}t \u{915}\u{94D}\u{937}\u{93F}");
+    }
+}
@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Rendering a `Report` in the supported output formats.
+
+#[cfg(feature = "columnar")]
+pub mod columnar;
+pub mod csv;
+pub mod html;
+pub mod json;
+pub mod latex;
+pub mod mediawiki;
+pub mod org;
+pub mod png;
+pub mod rst;
+pub mod site;
+pub mod sqlite;
+pub mod svg;
+pub mod table;
+pub mod template;
+pub mod term;
+pub mod vega;
+pub mod xml;
+
+use crate::Metric;
+use num_format::Locale;
+use num_format::ToFormattedString;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// The temporary file next to `path` that `write_file` writes before
+/// renaming it to `path`.
+pub(crate) fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}.tmp", std::process::id()));
+    path.with_file_name(name)
+}
+
+/// Writes the file at `path` with `write` through a temporary file next to
+/// it, so that the file is replaced only once it is complete and is left
+/// alone if writing fails.
+pub fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let temporary = temporary_path(path);
+    let result = File::create(&temporary).and_then(|file| {
+        let mut out = BufWriter::new(file);
+        write(&mut out)?;
+        out.into_inner()?.sync_all()?;
+        std::fs::rename(&temporary, path)
+    });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
+
+/// How to write the numbers in the cells of the tables for people to read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numbers {
+    /// Digits with `.` before the decimals, as in the CSV and JSON output
+    Plain,
+    /// With the separators of the locale between groups of digits and
+    /// before the decimals
+    Locale(Locale),
+}
+
+impl Default for Numbers {
+    fn default() -> Numbers {
+        Numbers::Locale(Locale::en)
+    }
+}
+
+impl FromStr for Numbers {
+    type Err = String;
+
+    /// Parses `none` or a CLDR locale name such as `de` or `en-IN`.
+    fn from_str(s: &str) -> Result<Numbers, String> {
+        if s.trim().eq_ignore_ascii_case("none") {
+            return Ok(Numbers::Plain);
+        }
+        Locale::from_name(s.trim())
+            .map(Numbers::Locale)
+            .map_err(|_| format!("unknown locale '{}'", s.trim()))
+    }
+}
+
+impl Numbers {
+    /// The separator between groups of digits, if any.
+    pub fn separator(self) -> &'static str {
+        match self {
+            Numbers::Plain => "",
+            Numbers::Locale(locale) => locale.separator(),
+        }
+    }
+
+    /// The separator before the decimals.
+    pub fn decimal(self) -> &'static str {
+        match self {
+            Numbers::Plain => ".",
+            Numbers::Locale(locale) => locale.decimal(),
+        }
+    }
+
+    /// The sign of negative numbers.
+    pub fn minus_sign(self) -> &'static str {
+        match self {
+            Numbers::Plain => "-",
+            Numbers::Locale(locale) => locale.minus_sign(),
+        }
+    }
+
+    /// Writes `value` rounded to `decimals` decimals.
+    pub fn format(self, value: f64, decimals: usize) -> String {
+        let plain = format!("{:.*}", decimals, value);
+        let locale = match self {
+            Numbers::Plain => return plain,
+            Numbers::Locale(locale) => locale,
+        };
+        let (negative, digits) = match plain.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, &plain[..]),
+        };
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+        // Infinities and NaNs
+        let integer = match integer.parse::<u64>() {
+            Ok(integer) => integer.to_formatted_string(&locale),
+            Err(_) => return plain,
+        };
+        let mut formatted = String::new();
+        if negative {
+            formatted.push_str(locale.minus_sign());
+        }
+        formatted.push_str(&integer);
+        if let Some(fraction) = fraction {
+            formatted.push_str(locale.decimal());
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+
+    /// Writes `value` of `metric` like `Metric::format`.
+    pub fn metric(self, metric: Metric, value: f64) -> String {
+        if metric.is_count() {
+            self.format(value.trunc(), 0)
+        } else {
+            self.format(value, 2)
+        }
+    }
+}
+
+/// The UTC date of `time` as YYYY-MM-DD.
+pub fn iso_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    // The conversion from days since the epoch to the proleptic Gregorian
+    // calendar in http://howardhinnant.github.io/date_algorithms.html
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
@@ -0,0 +1,165 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The metrics as Apache Parquet or Arrow IPC files with the columns of the
+//! CSV output, for loading into DuckDB, Polars or pandas.
+
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use arrow_array::builder::Float64Builder;
+use arrow_array::builder::Int64Builder;
+use arrow_array::builder::StringBuilder;
+use arrow_array::builder::UInt32Builder;
+use arrow_array::builder::UInt8Builder;
+use arrow_array::ArrayRef;
+use arrow_array::RecordBatch;
+use arrow_schema::ArrowError;
+use arrow_schema::DataType;
+use arrow_schema::Field;
+use arrow_schema::Schema;
+use std::io::Write;
+use std::sync::Arc;
+
+/// The file format of the columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Container {
+    Parquet,
+    /// The Arrow IPC file format, also known as Feather version 2
+    Arrow,
+}
+
+/// The columns of the rows: the translations with their article numbers
+/// when measured per article.
+struct Columns<'a> {
+    metrics: &'a [Metric],
+    article: bool,
+    smallest_encoding: bool,
+    direction: bool,
+}
+
+fn strings<'a>(values: impl Iterator<Item = Option<&'a str>>) -> ArrayRef {
+    let mut builder = StringBuilder::new();
+    for value in values {
+        builder.append_option(value);
+    }
+    Arc::new(builder.finish())
+}
+
+fn batch(columns: &Columns, rows: &[(Option<u32>, &Lang)]) -> Result<RecordBatch, ArrowError> {
+    let mut fields = vec![
+        Field::new("code", DataType::Utf8, true),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("script", DataType::Utf8, true),
+        Field::new("stage", DataType::UInt8, true),
+    ];
+    let mut arrays = vec![
+        strings(rows.iter().map(|(_, lang)| lang.code.as_deref())),
+        strings(rows.iter().map(|(_, lang)| Some(&lang.name[..]))),
+        strings(rows.iter().map(|(_, lang)| lang.script.as_deref())),
+    ];
+    let mut stages = UInt8Builder::new();
+    for (_, lang) in rows {
+        stages.append_option(lang.stage);
+    }
+    arrays.push(Arc::new(stages.finish()));
+    if columns.article {
+        fields.push(Field::new("article", DataType::UInt32, false));
+        let mut articles = UInt32Builder::new();
+        for (article, _) in rows {
+            articles.append_option(*article);
+        }
+        arrays.push(Arc::new(articles.finish()));
+    }
+    for &metric in columns.metrics {
+        let values = rows.iter().map(|(_, lang)| metric.value(&lang.metrics));
+        // Counts as integers, like in the JSON output
+        if metric.is_count() {
+            fields.push(Field::new(metric.name(), DataType::Int64, true));
+            let mut builder = Int64Builder::new();
+            for value in values {
+                builder.append_option(value.map(|v| v as i64));
+            }
+            arrays.push(Arc::new(builder.finish()));
+        } else {
+            fields.push(Field::new(metric.name(), DataType::Float64, true));
+            let mut builder = Float64Builder::new();
+            for value in values {
+                builder.append_option(value);
+            }
+            arrays.push(Arc::new(builder.finish()));
+        }
+    }
+    if columns.smallest_encoding {
+        fields.push(Field::new("smallest_encoding", DataType::Utf8, true));
+        arrays.push(strings(rows.iter().map(|(_, lang)| lang.smallest_encoding)));
+    }
+    if columns.direction {
+        fields.push(Field::new("direction", DataType::Utf8, true));
+        arrays.push(strings(
+            rows.iter()
+                .map(|(_, lang)| lang.metrics.bidi.map(|b| b.direction())),
+        ));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)
+}
+
+fn write_batch<W: Write>(
+    out: &mut W,
+    batch: &RecordBatch,
+    container: Container,
+) -> std::io::Result<()> {
+    // The writers need to own their output.
+    let mut buffer = Vec::new();
+    match container {
+        Container::Parquet => {
+            let mut writer =
+                parquet::arrow::ArrowWriter::try_new(&mut buffer, batch.schema(), None)
+                    .map_err(std::io::Error::other)?;
+            writer.write(batch).map_err(std::io::Error::other)?;
+            writer.close().map_err(std::io::Error::other)?;
+        }
+        Container::Arrow => {
+            let mut writer = arrow_ipc::writer::FileWriter::try_new(&mut buffer, &batch.schema())
+                .map_err(std::io::Error::other)?;
+            writer.write(batch).map_err(std::io::Error::other)?;
+            writer.finish().map_err(std::io::Error::other)?;
+        }
+    }
+    out.write_all(&buffer)
+}
+
+/// Writes one row per translation.
+pub fn write<W: Write>(out: &mut W, report: &Report, container: Container) -> std::io::Result<()> {
+    let columns = Columns {
+        metrics: &report.columns,
+        article: false,
+        smallest_encoding: !report.encoding_wins.is_empty(),
+        direction: report.has_direction(),
+    };
+    let rows: Vec<(Option<u32>, &Lang)> = report.langs.iter().map(|lang| (None, lang)).collect();
+    let batch = batch(&columns, &rows).map_err(std::io::Error::other)?;
+    write_batch(out, &batch, container)
+}
+
+/// Writes one row per article per translation.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    container: Container,
+) -> std::io::Result<()> {
+    let first = articles.first().map(|(_, report)| report);
+    let columns = Columns {
+        metrics: first.map_or(&[], |report| &report.columns),
+        article: true,
+        smallest_encoding: first.is_some_and(|report| !report.encoding_wins.is_empty()),
+        direction: first.is_some_and(|report| report.has_direction()),
+    };
+    let rows: Vec<(Option<u32>, &Lang)> = articles
+        .iter()
+        .flat_map(|(number, report)| report.langs.iter().map(move |lang| (Some(*number), lang)))
+        .collect();
+    let batch = batch(&columns, &rows).map_err(std::io::Error::other)?;
+    write_batch(out, &batch, container)
+}
@@ -0,0 +1,125 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use crate::Lang;
+use crate::Report;
+use std::io::Write;
+
+/// Quotes `field` per RFC 4180 if needed.
+fn field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn write_header<W: Write>(out: &mut W, report: &Report, extra: &str) -> std::io::Result<()> {
+    write!(out, "code,name,script,stage{}", extra)?;
+    for metric in report.columns.iter() {
+        write!(out, ",{}", metric.name())?;
+    }
+    if !report.encoding_wins.is_empty() {
+        write!(out, ",smallest_encoding")?;
+    }
+    if report.has_direction() {
+        write!(out, ",direction")?;
+    }
+    writeln!(out)
+}
+
+fn write_lang<W: Write>(
+    out: &mut W,
+    report: &Report,
+    lang: &Lang,
+    extra: &str,
+) -> std::io::Result<()> {
+    write!(
+        out,
+        "{},{},{},{}{}",
+        field(lang.code.as_deref().unwrap_or("")),
+        field(&lang.name),
+        field(lang.script.as_deref().unwrap_or("")),
+        lang.stage.map_or(String::new(), |s| s.to_string()),
+        extra
+    )?;
+    for &metric in report.columns.iter() {
+        match metric.value(&lang.metrics) {
+            Some(value) => write!(out, ",{}", value)?,
+            None => write!(out, ",")?,
+        }
+    }
+    if let Some(encoding) = lang.smallest_encoding {
+        write!(out, ",{}", field(encoding))?;
+    }
+    if report.has_direction() {
+        let direction = lang.metrics.bidi.map(|b| b.direction());
+        write!(out, ",{}", direction.unwrap_or(""))?;
+    }
+    writeln!(out)
+}
+
+/// Writes one row per translation.
+pub fn write<W: Write>(out: &mut W, report: &Report) -> std::io::Result<()> {
+    write_header(out, report, "")?;
+    for lang in report.langs.iter() {
+        write_lang(out, report, lang, "")?;
+    }
+    Ok(())
+}
+
+/// Writes one row per article per translation.
+pub fn write_articles<W: Write>(out: &mut W, articles: &[(u32, Report)]) -> std::io::Result<()> {
+    match articles.first() {
+        Some((_, report)) => write_header(out, report, ",article")?,
+        None => writeln!(out, "code,name,script,stage,article")?,
+    }
+    for (number, report) in articles {
+        let extra = format!(",{}", number);
+        for lang in report.langs.iter() {
+            write_lang(out, report, lang, &extra)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a row for each block that each translation has code points in.
+pub fn write_blocks<W: Write>(out: &mut W, langs: &[Lang]) -> std::io::Result<()> {
+    writeln!(out, "code,name,script,plane,block,count")?;
+    for lang in langs {
+        for block in lang.metrics.blocks.iter() {
+            writeln!(
+                out,
+                "{},{},{},{},{},{}",
+                field(lang.code.as_deref().unwrap_or("")),
+                field(&lang.name),
+                field(lang.script.as_deref().unwrap_or("")),
+                block.plane,
+                field(&block.block),
+                block.count
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes a row for each grapheme cluster length in code points that each
+/// translation has clusters of.
+pub fn write_clusters<W: Write>(out: &mut W, langs: &[Lang]) -> std::io::Result<()> {
+    writeln!(out, "code,name,script,size,count")?;
+    for lang in langs {
+        for (size, count) in lang.metrics.cluster_sizes.iter() {
+            writeln!(
+                out,
+                "{},{},{},{},{}",
+                field(lang.code.as_deref().unwrap_or("")),
+                field(&lang.name),
+                field(lang.script.as_deref().unwrap_or("")),
+                size,
+                count
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,850 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use super::Numbers;
+use crate::stats::Stats;
+use crate::Lang;
+use crate::Metric;
+use crate::Metrics;
+use crate::Report;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Sorts the rows of the body of each table by the column whose heading is
+/// clicked or activated with the keyboard, numerically if both cells are
+/// numbers, and hides the rows that don't contain the text in the filter
+/// box. The order is in the `aria-sort` attribute of the heading for screen
+/// readers and the stylesheet. The separators in the numbers are in
+/// `numbers`, which is defined before it.
+const INTERACTIVE_SCRIPT: &str = r#"for (const table of document.querySelectorAll("table")) {
+  const body = table.tBodies[0];
+  if (!body || !table.tHead) {
+    continue;
+  }
+  const headings = table.tHead.rows[0].cells;
+  for (let i = 0; i < headings.length; i++) {
+    const heading = headings[i];
+    heading.tabIndex = 0;
+    heading.setAttribute("aria-sort", "none");
+    heading.addEventListener("keydown", event => {
+      if (event.key === "Enter" || event.key === " ") {
+        event.preventDefault();
+        heading.click();
+      }
+    });
+    heading.addEventListener("click", () => {
+      const descending = heading.getAttribute("aria-sort") === "ascending";
+      for (const h of headings) {
+        h.setAttribute("aria-sort", "none");
+      }
+      heading.setAttribute("aria-sort", descending ? "descending" : "ascending");
+      const key = row => (row.cells[i] ? row.cells[i].textContent.trim() : "");
+      const number = text =>
+        parseFloat(
+          text
+            .replace(/[▲▼]/g, "")
+            .split(numbers.separator)
+            .join("")
+            .replace(numbers.minus, "-")
+            .replace(numbers.decimal, ".")
+        );
+      const rows = Array.from(body.rows);
+      rows.sort((a, b) => {
+        const x = key(a);
+        const y = key(b);
+        const m = number(x);
+        const n = number(y);
+        const order = isNaN(m) || isNaN(n) ? x.localeCompare(y) : m - n;
+        return descending ? -order : order;
+      });
+      body.append(...rows);
+    });
+  }
+}
+const filter = document.getElementById("filter");
+filter.addEventListener("input", () => {
+  const text = filter.value.toLowerCase();
+  for (const row of document.querySelectorAll("tbody tr")) {
+    row.hidden = text !== "" && !row.textContent.toLowerCase().includes(text);
+  }
+});
+"#;
+
+/// Writes the start of an HTML document titled `title`.
+pub(crate) fn write_head<W: Write>(out: &mut W, title: &str) -> std::io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=en>")?;
+    writeln!(out, "<meta charset=utf-8>")?;
+    writeln!(out, "<title>{}</title>", title)
+}
+
+/// The stylesheet of a standalone document, besides the colors.
+const STYLESHEET: &str = "body { font-family: sans-serif; }
+table { border-collapse: collapse; margin-block: 1em; }
+caption { font-weight: bold; text-align: start; }
+th, td { border: 1px solid #ccc; padding: 0.2em 0.4em; }
+td { text-align: end; font-variant-numeric: tabular-nums; }
+tbody th { text-align: start; font-weight: normal; }
+thead th { position: sticky; top: 0; background: #fff; }
+";
+
+/// How to wrap the tables into a complete HTML document.
+#[derive(Debug, Clone, Default)]
+pub struct Document {
+    /// Style the document and describe what was measured, e.g. the version
+    /// of the corpus and when
+    pub description: Option<String>,
+    /// Make the tables sortable by clicking a column heading and the rows
+    /// filterable by their text
+    pub interactive: bool,
+    /// Link to the stylesheet at this URL, as written by `write_stylesheet`,
+    /// instead of embedding it
+    pub stylesheet: Option<String>,
+    pub style: Style,
+}
+
+/// How to write the cells of the tables.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Style {
+    pub coloring: Coloring,
+    pub numbers: Numbers,
+}
+
+/// The colors of the deviations from the baseline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Palette {
+    /// Red above the baseline and green below it
+    #[default]
+    Default,
+    /// The viridis color map from purple far below the baseline through
+    /// teal to yellow far above it
+    Viridis,
+    /// Orange above the baseline and blue below it, which readers with a
+    /// red-green color vision deficiency can tell apart
+    Colorblind,
+    /// No colors, e.g. for printing
+    None,
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Palette, String> {
+        match &s.trim().to_ascii_lowercase()[..] {
+            "default" => Ok(Palette::Default),
+            "viridis" => Ok(Palette::Viridis),
+            "colorblind" => Ok(Palette::Colorblind),
+            "none" => Ok(Palette::None),
+            _ => Err(format!("unknown palette '{}'", s.trim())),
+        }
+    }
+}
+
+/// How to color the cells by their deviation from the baseline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coloring {
+    pub palette: Palette,
+    /// The exponent of the magnitude of a deviation, which makes small
+    /// deviations stand out more the smaller it is
+    pub gamma: f64,
+    /// The saturation in percent of the largest deviations
+    pub max_saturation: f64,
+    /// The deviation in percent at and beyond which cells get the full
+    /// saturation, with the smaller ones scaled up to it
+    pub clip: Option<f64>,
+}
+
+impl Default for Coloring {
+    fn default() -> Coloring {
+        Coloring {
+            palette: Palette::default(),
+            gamma: 0.75,
+            max_saturation: 100.0,
+            clip: None,
+        }
+    }
+}
+
+/// Samples of the viridis color map at even intervals
+const VIRIDIS: [(u8, u8, u8); 9] = [
+    (68, 1, 84),
+    (71, 44, 122),
+    (59, 81, 139),
+    (44, 113, 142),
+    (33, 144, 141),
+    (39, 173, 129),
+    (92, 200, 99),
+    (170, 220, 50),
+    (253, 231, 37),
+];
+
+/// The color of the viridis color map at `t` from 0 to 1.
+fn viridis(t: f64) -> (u8, u8, u8) {
+    let position = t.clamp(0.0, 1.0) * (VIRIDIS.len() - 1) as f64;
+    let i = (position as usize).min(VIRIDIS.len() - 2);
+    let fraction = position - i as f64;
+    let mix =
+        |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * fraction).round() as u8;
+    let (from, to) = (VIRIDIS[i], VIRIDIS[i + 1]);
+    (mix(from.0, to.0), mix(from.1, to.1), mix(from.2, to.2))
+}
+
+/// The number of classes of the magnitude of the deviation from the
+/// baseline in each direction
+pub(crate) const BUCKETS: usize = 10;
+
+/// Whether a value of `comparison_result` is above `baseline_result` and
+/// its class of the magnitude of the deviation from 0 for the smallest
+/// deviations to `BUCKETS - 1` for the largest.
+pub(crate) fn deviation_bucket(
+    baseline_result: f64,
+    comparison_result: f64,
+    coloring: &Coloring,
+) -> (bool, usize) {
+    let (above, magnitude) = colorize(baseline_result, comparison_result, coloring);
+    let bucket = ((magnitude * BUCKETS as f64) as usize).min(BUCKETS - 1);
+    (above, bucket)
+}
+
+/// The class of the cells of a value of `comparison_result` against
+/// `baseline_result`: `above-N` or `below-N` with N from
+/// `deviation_bucket`.
+fn deviation_class(baseline_result: f64, comparison_result: f64, coloring: &Coloring) -> String {
+    let (above, bucket) = deviation_bucket(baseline_result, comparison_result, coloring);
+    let direction = if above { "above" } else { "below" };
+    format!("{}-{}", direction, bucket)
+}
+
+/// A background color of the cells, which displays as CSS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Color {
+    /// The hue in degrees and the saturation in percent, with a lightness
+    /// of 65%
+    Hsl(u16, f64),
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// The red, green and blue components.
+    pub(crate) fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Hsl(hue, saturation) => {
+                let (s, l) = (saturation / 100.0, 0.65_f64);
+                let chroma = (1.0 - (2.0 * l - 1.0).abs()) * s;
+                let component = |n: f64| {
+                    let k = (n + f64::from(hue) / 30.0) % 12.0;
+                    let value = l - chroma / 2.0 * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0);
+                    (value * 255.0).round() as u8
+                };
+                (component(0.0), component(8.0), component(4.0))
+            }
+            Color::Rgb(r, g, b) => (r, g, b),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Color::Hsl(hue, saturation) => write!(f, "hsl({}, {}%, 65%)", hue, saturation),
+            Color::Rgb(r, g, b) => write!(f, "rgb({}, {}, {})", r, g, b),
+        }
+    }
+}
+
+/// The background color and the text color of the class `bucket` of
+/// deviations above or below the baseline, or `None` without a palette.
+pub(crate) fn bucket_colors(
+    above: bool,
+    bucket: usize,
+    coloring: &Coloring,
+) -> Option<(Color, &'static str)> {
+    let (hue, sign) = match (coloring.palette, above) {
+        (Palette::Default, true) => (0, 1.0),
+        (Palette::Default, false) => (120, -1.0),
+        (Palette::Colorblind, true) => (30, 1.0),
+        (Palette::Colorblind, false) => (210, -1.0),
+        (Palette::Viridis, true) => (0, 1.0),
+        (Palette::Viridis, false) => (0, -1.0),
+        (Palette::None, _) => return None,
+    };
+    let magnitude = (bucket as f64 + 0.5) / BUCKETS as f64 * coloring.max_saturation / 100.0;
+    if coloring.palette == Palette::Viridis {
+        let t = 0.5 + sign * magnitude / 2.0;
+        let (r, g, b) = viridis(t);
+        // The dark end needs light text.
+        let text = if t < 0.4 { "#fff" } else { "#000" };
+        Some((Color::Rgb(r, g, b), text))
+    } else {
+        let saturation = (bucket as f64 + 0.5) * coloring.max_saturation / BUCKETS as f64;
+        Some((Color::Hsl(hue, saturation), "#000"))
+    }
+}
+
+/// Writes the rules coloring the cells by their class of deviation.
+pub fn write_colors<W: Write>(out: &mut W, coloring: &Coloring) -> std::io::Result<()> {
+    for &(direction, above) in [("above", true), ("below", false)].iter() {
+        for bucket in 0..BUCKETS {
+            if let Some((background, text)) = bucket_colors(above, bucket, coloring) {
+                writeln!(
+                    out,
+                    ".{}-{} {{ background-color: {}; color: {}; }}",
+                    direction, bucket, background, text
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the stylesheet of the tables, including the styling of a
+/// `standalone` document and of `interactive` tables if requested.
+pub fn write_stylesheet<W: Write>(
+    out: &mut W,
+    coloring: &Coloring,
+    standalone: bool,
+    interactive: bool,
+) -> std::io::Result<()> {
+    write_colors(out, coloring)?;
+    if standalone {
+        write!(out, "{}", STYLESHEET)?;
+    }
+    if interactive {
+        writeln!(out, "thead th {{ cursor: pointer; }}")?;
+        writeln!(
+            out,
+            "thead th[aria-sort=ascending]::after {{ content: \" ▲\"; }}"
+        )?;
+        writeln!(
+            out,
+            "thead th[aria-sort=descending]::after {{ content: \" ▼\"; }}"
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the tables written by `body` as an HTML document.
+pub fn write_document<W: Write>(
+    out: &mut W,
+    document: &Document,
+    body: impl FnOnce(&mut W) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    write_head(out, "UDHR lengths")?;
+    match &document.stylesheet {
+        Some(href) => writeln!(out, "<link rel=stylesheet href=\"{}\">", href)?,
+        None => {
+            writeln!(out, "<style>")?;
+            write_stylesheet(
+                out,
+                &document.style.coloring,
+                document.description.is_some(),
+                document.interactive,
+            )?;
+            writeln!(out, "</style>")?;
+        }
+    }
+    if let Some(description) = &document.description {
+        writeln!(out, "<h1>UDHR lengths</h1>")?;
+        writeln!(out, "<p>{}</p>", description)?;
+    }
+    if document.interactive {
+        writeln!(
+            out,
+            "<p><label>Filter: <input id=filter type=search></label></p>"
+        )?;
+    }
+    body(out)?;
+    if document.interactive {
+        let numbers = document.style.numbers;
+        writeln!(out, "<script>")?;
+        writeln!(
+            out,
+            "const numbers = {};",
+            serde_json::json!({
+                "separator": numbers.separator(),
+                "decimal": numbers.decimal(),
+                "minus": numbers.minus_sign(),
+            })
+        )?;
+        write!(out, "{}", INTERACTIVE_SCRIPT)?;
+        writeln!(out, "</script>")?;
+    }
+    Ok(())
+}
+
+/// Whether `comparison_result` is above `baseline_result` and the magnitude
+/// of its deviation from 0 to 1.
+fn colorize(baseline_result: f64, comparison_result: f64, coloring: &Coloring) -> (bool, f64) {
+    let above = baseline_result < comparison_result;
+    let linear = match coloring.clip {
+        Some(clip) => (deviation_percent(comparison_result, baseline_result).abs() / clip).min(1.0),
+        None if above => 1.0 - baseline_result / comparison_result,
+        None => 1.0 - comparison_result / baseline_result,
+    };
+    (above, linear.powf(coloring.gamma))
+}
+
+fn deviation_percent(value: f64, baseline: f64) -> f64 {
+    let delta = value - baseline;
+    (delta / baseline) * 100.0
+}
+
+fn write_count<W: Write>(
+    out: &mut W,
+    metric: Metric,
+    count: Option<f64>,
+    baseline: Option<f64>,
+    style: &Style,
+) -> std::io::Result<()> {
+    let count = match count {
+        Some(count) => count,
+        None => return writeln!(out, "<td></td><td></td>"),
+    };
+    // Deviations from a baseline that isn't positive aren't meaningful.
+    let baseline = match baseline {
+        Some(baseline) if baseline > 0.0 => baseline,
+        _ => {
+            return writeln!(
+                out,
+                "<td>{}</td><td></td>",
+                style.numbers.metric(metric, count)
+            )
+        }
+    };
+    let class = deviation_class(baseline, count, &style.coloring);
+    let deviation = deviation_percent(count, baseline);
+    // The direction mustn't be conveyed by the color alone. The sign of the
+    // number already tells it to screen readers.
+    let arrow = if deviation > 0.0 {
+        "<span aria-hidden=true>▲</span> "
+    } else if deviation < 0.0 {
+        "<span aria-hidden=true>▼</span> "
+    } else {
+        ""
+    };
+    writeln!(
+        out,
+        "<td class={}>{}</td><td class={}>{}{}</td>",
+        class,
+        style.numbers.metric(metric, count),
+        class,
+        arrow,
+        style.numbers.format(deviation, 1)
+    )
+}
+
+/// Writes the cells after the metrics: the smallest encoding and the
+/// direction of `lang`, if the report has them, `script` and the stage of
+/// `lang`.
+fn write_trailer<W: Write>(
+    out: &mut W,
+    lang: Option<&Lang>,
+    script: &str,
+    report: &Report,
+) -> std::io::Result<()> {
+    if !report.encoding_wins.is_empty() {
+        let smallest_encoding = lang.and_then(|l| l.smallest_encoding);
+        write!(out, "<td>{}</td>", smallest_encoding.unwrap_or(""))?;
+    }
+    if report.has_direction() {
+        let direction = lang.and_then(|l| l.metrics.bidi).map(|b| b.direction());
+        write!(out, "<td>{}</td>", direction.unwrap_or(""))?;
+    }
+    write!(out, "<td>{}</td>", script)?;
+    match lang.and_then(|l| l.stage) {
+        Some(stage) => write!(out, "<td>{}</td>", stage),
+        None => write!(out, "<td></td>"),
+    }
+}
+
+/// The secondary metrics of a row, which don't get columns of their own:
+/// the words, the distinct code points and how much normalizing changes.
+fn tooltip(metrics: &Metrics, numbers: Numbers) -> String {
+    let mut lines = vec![format!(
+        "Words: {}",
+        numbers.format(metrics.words as f64, 0)
+    )];
+    if let Some(distinct) = &metrics.distinct {
+        lines.push(format!(
+            "Distinct code points: {}",
+            numbers.format(distinct.chars as f64, 0)
+        ));
+    }
+    lines.push(format!(
+        "Changed by NFC: {} code points",
+        numbers.format(metrics.non_nfc as f64, 0)
+    ));
+    for (form, lengths) in metrics.normalized.iter() {
+        let delta = lengths.utf8 as f64 - metrics.utf8 as f64;
+        let sign = if delta > 0.0 { "+" } else { "" };
+        lines.push(format!(
+            "{}: {}{} UTF-8 bytes",
+            form.name().to_ascii_uppercase(),
+            sign,
+            numbers.format(delta, 0)
+        ));
+    }
+    lines.join("&#10;")
+}
+
+fn write_row<W: Write>(
+    out: &mut W,
+    heading: &str,
+    value: &dyn Fn(Metric) -> Option<f64>,
+    lang: Option<&Lang>,
+    script: &str,
+    report: &Report,
+    style: &Style,
+) -> std::io::Result<()> {
+    match lang {
+        Some(lang) => writeln!(
+            out,
+            "<tr title=\"{}\">",
+            tooltip(&lang.metrics, style.numbers)
+        )?,
+        None => writeln!(out, "<tr>")?,
+    }
+    writeln!(out, "<th scope=row>{}</th>", heading)?;
+    for &metric in report.columns.iter() {
+        write_count(
+            out,
+            metric,
+            value(metric),
+            report.baseline_value(value, metric),
+            style,
+        )?;
+    }
+    write_trailer(out, lang, script, report)?;
+    writeln!(out)?;
+    writeln!(out, "</tr>")
+}
+
+fn write_summary_row<W: Write>(
+    out: &mut W,
+    heading: &str,
+    stat: &dyn Fn(&Stats) -> f64,
+    report: &Report,
+    style: &Style,
+) -> std::io::Result<()> {
+    write_row(
+        out,
+        heading,
+        &|metric| report.summary.get(metric).map(stat),
+        None,
+        "",
+        report,
+        style,
+    )
+}
+
+/// Writes a footer row without deviations.
+fn write_plain_row<W: Write>(
+    out: &mut W,
+    heading: &str,
+    stat: fn(Numbers, Metric, &Stats) -> String,
+    report: &Report,
+    style: &Style,
+) -> std::io::Result<()> {
+    write!(out, "<tr><th scope=row>{}</th>", heading)?;
+    for &metric in report.columns.iter() {
+        match report.summary.get(metric) {
+            Some(stats) => write!(
+                out,
+                "<td>{}</td><td></td>",
+                stat(style.numbers, metric, stats)
+            )?,
+            None => write!(out, "<td></td><td></td>")?,
+        }
+    }
+    write_trailer(out, None, "", report)?;
+    writeln!(out, "</tr>")
+}
+
+/// The caption of the table of the whole declaration
+pub(crate) const CAPTION: &str = "Lengths of the translations";
+
+/// The page of the translation `code` on unicode.org.
+pub(crate) fn udhr_href(code: &str) -> String {
+    format!("https://www.unicode.org/udhr/d/udhr_{}.html", code)
+}
+
+/// Writes the table of `report`, linking the name of each translation to
+/// `href` of its code.
+pub(crate) fn write_table<W: Write>(
+    out: &mut W,
+    report: &Report,
+    id: &str,
+    caption: &str,
+    href: &dyn Fn(&str) -> String,
+    style: &Style,
+) -> std::io::Result<()> {
+    writeln!(out, "<table id={}>", id)?;
+    writeln!(out, "<caption>{}</caption>", caption)?;
+    writeln!(out, "<thead>")?;
+    write!(out, "<tr><th scope=col>Name</th>")?;
+    for &metric in report.columns.iter() {
+        write!(
+            out,
+            "<th scope=col>{}</th><th scope=col><abbr title=\"Deviation from the baseline in percent\">Δ%</abbr></th>",
+            metric.label()
+        )?;
+    }
+    if !report.encoding_wins.is_empty() {
+        write!(out, "<th scope=col>Smallest</th>")?;
+    }
+    if report.has_direction() {
+        write!(out, "<th scope=col>Direction</th>")?;
+    }
+    writeln!(
+        out,
+        "<th scope=col>Script</th><th scope=col>Stage</th></tr>"
+    )?;
+    writeln!(out, "</thead>")?;
+    writeln!(out, "<tbody>")?;
+
+    let mut groups = report.groups.iter();
+    let mut group = groups.next();
+    let mut remaining = group.map_or(0, |g| g.count);
+    for lang in report.langs.iter() {
+        let heading = match &lang.code {
+            Some(code) => format!("<a href=\"{}\">{}</a>", href(code), lang.name),
+            None => lang.name.clone(),
+        };
+        write_row(
+            out,
+            &heading,
+            &|metric| metric.value(&lang.metrics),
+            Some(lang),
+            lang.script.as_deref().unwrap_or(""),
+            report,
+            style,
+        )?;
+        if let Some(g) = group {
+            remaining -= 1;
+            if remaining == 0 {
+                write_row(
+                    out,
+                    &format!("{} median", g.script),
+                    &|metric| g.summary.get(metric).map(|s| s.median),
+                    None,
+                    &g.script,
+                    report,
+                    style,
+                )?;
+                group = groups.next();
+                remaining = group.map_or(0, |g| g.count);
+            }
+        }
+    }
+
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "<tfoot>")?;
+    write_summary_row(out, "Min", &|s| s.min, report, style)?;
+    write_summary_row(out, "Q1", &|s| s.q1, report, style)?;
+    write_plain_row(
+        out,
+        "Median",
+        |n, m, s| n.metric(m, s.median),
+        report,
+        style,
+    )?;
+    write_summary_row(out, "Q3", &|s| s.q3, report, style)?;
+    for (i, p) in report.stats_options.percentiles.iter().enumerate() {
+        write_summary_row(
+            out,
+            &format!("P{}", p),
+            &|s| s.percentiles[i].1,
+            report,
+            style,
+        )?;
+    }
+    write_summary_row(out, "Mean", &|s| s.mean, report, style)?;
+    if report.stats_options.populations.is_some() {
+        write_row(
+            out,
+            "Weighted mean",
+            &|metric| report.summary.get(metric).and_then(|s| s.weighted_mean),
+            None,
+            "",
+            report,
+            style,
+        )?;
+        write_row(
+            out,
+            "Weighted median",
+            &|metric| report.summary.get(metric).and_then(|s| s.weighted_median),
+            None,
+            "",
+            report,
+            style,
+        )?;
+    }
+    write_summary_row(
+        out,
+        "Max (ignoring outliers)",
+        &|s| s.max_ignoring_outliers,
+        report,
+        style,
+    )?;
+    write_summary_row(out, "Max", &|s| s.max, report, style)?;
+    write!(out, "<tr><th scope=row>Outliers</th>")?;
+    for &metric in report.columns.iter() {
+        match report.summary.get(metric) {
+            Some(stats) if stats.outliers.is_empty() => write!(out, "<td>0</td><td></td>")?,
+            Some(stats) => write!(
+                out,
+                "<td>{}<br>{}</td><td></td>",
+                stats.outliers.len(),
+                stats.outliers.join(", ")
+            )?,
+            None => write!(out, "<td></td><td></td>")?,
+        }
+    }
+    write_trailer(out, None, "", report)?;
+    writeln!(out, "</tr>")?;
+    write_plain_row(
+        out,
+        "Std. dev.",
+        |n, _, s| n.format(s.std_dev, 1),
+        report,
+        style,
+    )?;
+    write_plain_row(
+        out,
+        "CV",
+        |n, _, s| format!("{}%", n.format(s.cv * 100.0, 1)),
+        report,
+        style,
+    )?;
+    writeln!(out, "</tfoot>")?;
+    writeln!(out, "</table>")?;
+    if !report.encoding_wins.is_empty() {
+        writeln!(out, "<table id={}-wins>", id)?;
+        writeln!(out, "<caption>Smallest encoding</caption>")?;
+        writeln!(out, "<thead>")?;
+        writeln!(
+            out,
+            "<tr><th scope=col>Encoding</th><th scope=col>Translations</th></tr>"
+        )?;
+        writeln!(out, "</thead>")?;
+        writeln!(out, "<tbody>")?;
+        for wins in report.encoding_wins.iter() {
+            writeln!(
+                out,
+                "<tr><th scope=row>{}</th><td>{}</td></tr>",
+                wins.encoding, wins.count
+            )?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+    }
+    write_warnings(out, report, id)
+}
+
+/// Writes the table of the translations with characters that indicate
+/// corrupt source data, if there are any.
+fn write_warnings<W: Write>(out: &mut W, report: &Report, id: &str) -> std::io::Result<()> {
+    let mut suspicious = report
+        .langs
+        .iter()
+        .filter(|lang| !lang.metrics.suspicious.is_empty())
+        .peekable();
+    if suspicious.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(out, "<table id={}-warnings>", id)?;
+    writeln!(out, "<caption>Warnings</caption>")?;
+    writeln!(out, "<thead>")?;
+    writeln!(
+        out,
+        "<tr><th scope=col>Name</th><th scope=col>U+FFFD</th><th scope=col>Controls</th><th scope=col>Private use</th></tr>"
+    )?;
+    writeln!(out, "</thead>")?;
+    writeln!(out, "<tbody>")?;
+    for lang in suspicious {
+        let counts = lang.metrics.suspicious;
+        writeln!(
+            out,
+            "<tr><th scope=row>{}</th><td>{}</td><td>{}</td><td>{}</td></tr>",
+            lang.name, counts.replacement, counts.controls, counts.private_use
+        )?;
+    }
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "</table>")
+}
+
+/// Writes `report` as an HTML table fragment with cells colored by their
+/// deviation from the median and numbers written in `style`.
+pub fn write<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    write_table(out, report, "counts", CAPTION, &udhr_href, style)
+}
+
+/// Writes a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+) -> std::io::Result<()> {
+    for (number, report) in articles {
+        let id = format!("article-{}", number);
+        let caption = format!("Article {}", number);
+        write_table(out, report, &id, &caption, &udhr_href, style)?;
+    }
+    Ok(())
+}
+
+/// Writes a table of the number of code points of each translation in each
+/// block.
+pub fn write_blocks<W: Write>(out: &mut W, langs: &[Lang]) -> std::io::Result<()> {
+    writeln!(out, "<table id=blocks>")?;
+    writeln!(out, "<caption>Code points by block</caption>")?;
+    writeln!(out, "<thead>")?;
+    writeln!(
+        out,
+        "<tr><th scope=col>Name</th><th scope=col>Plane</th><th scope=col>Block</th><th scope=col>Code points</th></tr>"
+    )?;
+    writeln!(out, "</thead>")?;
+    writeln!(out, "<tbody>")?;
+    for lang in langs {
+        for block in lang.metrics.blocks.iter() {
+            writeln!(
+                out,
+                "<tr><th scope=row>{}</th><td>{}</td><td>{}</td><td>{}</td></tr>",
+                lang.name, block.plane, block.block, block.count
+            )?;
+        }
+    }
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "</table>")
+}
+
+/// Writes a table of the number of grapheme clusters of each length in code
+/// points of each translation.
+pub fn write_clusters<W: Write>(out: &mut W, langs: &[Lang]) -> std::io::Result<()> {
+    writeln!(out, "<table id=clusters>")?;
+    writeln!(out, "<caption>Grapheme clusters by length</caption>")?;
+    writeln!(out, "<thead>")?;
+    writeln!(
+        out,
+        "<tr><th scope=col>Name</th><th scope=col>Code points</th><th scope=col>Clusters</th><th scope=col>%</th></tr>"
+    )?;
+    writeln!(out, "</thead>")?;
+    writeln!(out, "<tbody>")?;
+    for lang in langs {
+        let total: usize = lang.metrics.cluster_sizes.values().sum();
+        for (size, count) in lang.metrics.cluster_sizes.iter() {
+            writeln!(
+                out,
+                "<tr><th scope=row>{}</th><td>{}</td><td>{}</td><td>{:.1}</td></tr>",
+                lang.name,
+                size,
+                count,
+                *count as f64 / total as f64 * 100.0
+            )?;
+        }
+    }
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "</table>")
+}
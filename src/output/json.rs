@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The results as JSON documents or, in the same structure, as YAML or
+//! TOML.
+
+use crate::metrics::BlockCount;
+use crate::Lang;
+use crate::Report;
+use schemars::JsonSchema;
+use schemars::Schema;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// The syntax of the documents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Syntax {
+    #[default]
+    Json,
+    Yaml,
+    /// TOML, in which the arrays at the top are under the key `items`,
+    /// since a TOML document is a table
+    Toml,
+}
+
+/// Writes `value` as a pretty-printed document in `syntax`.
+fn write_value<W: Write>(
+    out: &mut W,
+    value: &impl Serialize,
+    syntax: Syntax,
+) -> std::io::Result<()> {
+    match syntax {
+        Syntax::Json => {
+            serde_json::to_writer_pretty(&mut *out, value)?;
+            writeln!(out)
+        }
+        Syntax::Yaml => serde_yaml_ng::to_writer(&mut *out, value).map_err(std::io::Error::other),
+        Syntax::Toml => {
+            let document = toml::to_string_pretty(value).map_err(std::io::Error::other)?;
+            out.write_all(document.as_bytes())
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Items<'a, T> {
+    items: &'a [T],
+}
+
+/// Writes `items` as an array in `syntax`.
+fn write_items<W: Write, T: Serialize>(
+    out: &mut W,
+    items: &[T],
+    syntax: Syntax,
+) -> std::io::Result<()> {
+    match syntax {
+        Syntax::Toml => write_value(out, &Items { items }, syntax),
+        Syntax::Json | Syntax::Yaml => write_value(out, &items, syntax),
+    }
+}
+
+/// Writes `report` as a pretty-printed document in `syntax`.
+pub fn write<W: Write>(out: &mut W, report: &Report, syntax: Syntax) -> std::io::Result<()> {
+    write_value(out, report, syntax)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct ArticleReport<'a> {
+    article: u32,
+    #[serde(flatten)]
+    report: &'a Report,
+}
+
+/// Writes an array with a report like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    syntax: Syntax,
+) -> std::io::Result<()> {
+    let articles: Vec<ArticleReport> = articles
+        .iter()
+        .map(|(article, report)| ArticleReport {
+            article: *article,
+            report,
+        })
+        .collect();
+    write_items(out, &articles, syntax)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct LangBlocks<'a> {
+    code: Option<&'a str>,
+    name: &'a str,
+    script: Option<&'a str>,
+    planes: BTreeMap<u32, usize>,
+    blocks: &'a [BlockCount],
+}
+
+/// Writes the number of code points of each translation in each plane and
+/// block.
+pub fn write_blocks<W: Write>(out: &mut W, langs: &[Lang], syntax: Syntax) -> std::io::Result<()> {
+    let langs: Vec<LangBlocks> = langs
+        .iter()
+        .map(|lang| LangBlocks {
+            code: lang.code.as_deref(),
+            name: &lang.name,
+            script: lang.script.as_deref(),
+            planes: crate::metrics::count_planes(&lang.metrics.blocks),
+            blocks: &lang.metrics.blocks,
+        })
+        .collect();
+    write_items(out, &langs, syntax)
+}
+
+#[derive(Serialize, JsonSchema)]
+struct LangClusters<'a> {
+    code: Option<&'a str>,
+    name: &'a str,
+    script: Option<&'a str>,
+    /// The number of grapheme clusters by length in code points
+    sizes: &'a BTreeMap<usize, usize>,
+}
+
+/// Writes the number of grapheme clusters of each length of each
+/// translation.
+pub fn write_clusters<W: Write>(
+    out: &mut W,
+    langs: &[Lang],
+    syntax: Syntax,
+) -> std::io::Result<()> {
+    let langs: Vec<LangClusters> = langs
+        .iter()
+        .map(|lang| LangClusters {
+            code: lang.code.as_deref(),
+            name: &lang.name,
+            script: lang.script.as_deref(),
+            sizes: &lang.metrics.cluster_sizes,
+        })
+        .collect();
+    write_items(out, &langs, syntax)
+}
+
+/// The version of the structure of the JSON documents, incremented when it
+/// changes incompatibly.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// The kinds of documents in the JSON output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Kind {
+    /// The output of `write`
+    #[default]
+    Report,
+    /// The output of `write_articles`
+    Articles,
+    /// The output of `write_blocks`
+    Blocks,
+    /// The output of `write_clusters`
+    Clusters,
+}
+
+/// The JSON Schema of the documents of `kind`, with the `SCHEMA_VERSION` as `version`.
+pub fn schema(kind: Kind) -> Schema {
+    let (mut schema, title) = match kind {
+        Kind::Report => (schemars::schema_for!(Report), "udhrlen report"),
+        Kind::Articles => (
+            schemars::schema_for!(Vec<ArticleReport>),
+            "udhrlen report per article",
+        ),
+        Kind::Blocks => (
+            schemars::schema_for!(Vec<LangBlocks>),
+            "udhrlen code points by block",
+        ),
+        Kind::Clusters => (
+            schemars::schema_for!(Vec<LangClusters>),
+            "udhrlen grapheme clusters by length",
+        ),
+    };
+    schema.insert("title".to_string(), title.into());
+    schema.insert("version".to_string(), SCHEMA_VERSION.into());
+    schema
+}
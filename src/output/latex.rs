@@ -0,0 +1,107 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The table of a `Report` as a LaTeX `tabular` in the style of the
+//! booktabs package, with the cells shaded by their deviation from the
+//! baseline like in the HTML table unless the palette is `none`.
+
+use super::html::Style;
+use super::table::lines;
+use super::table::warning_lines;
+use super::table::Line;
+use crate::Report;
+use std::io::Write;
+
+/// `text` with the characters that are special in LaTeX escaped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes `lines` as a `tabular` with the columns aligned like the cells
+/// of the header.
+fn write_tabular<W: Write>(out: &mut W, lines: &[Line]) -> std::io::Result<()> {
+    let columns: String = match lines.first() {
+        Some(Line::Row(cells)) => cells
+            .iter()
+            .map(|cell| if cell.left { 'l' } else { 'r' })
+            .collect(),
+        _ => String::new(),
+    };
+    writeln!(out, "\\begin{{tabular}}{{{}}}", columns)?;
+    writeln!(out, "\\toprule")?;
+    for line in lines.iter() {
+        let cells = match line {
+            Line::Row(cells) => cells,
+            Line::Rule => {
+                writeln!(out, "\\midrule")?;
+                continue;
+            }
+        };
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|cell| {
+                let text = escape(&cell.text);
+                match cell.colors {
+                    Some((background, foreground)) => {
+                        let (r, g, b) = background.rgb();
+                        let text = if foreground == "#fff" {
+                            format!("\\textcolor{{white}}{{{}}}", text)
+                        } else {
+                            text
+                        };
+                        format!("\\cellcolor[RGB]{{{},{},{}}}{}", r, g, b, text)
+                    }
+                    None => text,
+                }
+            })
+            .collect();
+        writeln!(out, "{} \\\\", cells.join(" & "))?;
+    }
+    writeln!(out, "\\bottomrule")?;
+    writeln!(out, "\\end{{tabular}}")
+}
+
+/// Writes the table of `report` with the numbers written in `style`,
+/// followed by the table of the warnings, if any.
+pub fn write<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "% Needs \\usepackage{{booktabs}} and, for the shading, \\usepackage[table]{{xcolor}}"
+    )?;
+    write_tabular(out, &lines(report, style))?;
+    if let Some(lines) = warning_lines(report) {
+        writeln!(out)?;
+        write_tabular(out, &lines)?;
+    }
+    Ok(())
+}
+
+/// Writes a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+) -> std::io::Result<()> {
+    for (i, (number, report)) in articles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "% Article {}", number)?;
+        write(out, report, style)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The table of a `Report` as a sortable MediaWiki `wikitable`, with the
+//! cells shaded by their deviation from the baseline like in the HTML table
+//! unless the palette is `none`.
+
+use super::html::Style;
+use super::table::lines;
+use super::table::warning_lines;
+use super::table::Cell;
+use super::table::Line;
+use crate::Report;
+use std::io::Write;
+
+/// `text` with the characters that are markup in wikitext or HTML written
+/// as character references.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '|' | '[' | ']' | '{' | '}' | '\'' | '~' => {
+                escaped.push_str(&format!("&#{};", u32::from(c)))
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The attributes of `cell`, if it has any.
+fn attributes(cell: &Cell) -> Option<String> {
+    let mut style = Vec::new();
+    if !cell.left {
+        style.push("text-align:right".to_string());
+    }
+    if let Some((background, foreground)) = cell.colors {
+        let (r, g, b) = background.rgb();
+        style.push(format!("background:#{:02x}{:02x}{:02x}", r, g, b));
+        style.push(format!("color:{}", foreground));
+    }
+    (!style.is_empty()).then(|| format!("style=\"{}\"", style.join(";")))
+}
+
+fn write_lines<W: Write>(out: &mut W, lines: &[Line]) -> std::io::Result<()> {
+    writeln!(out, "{{| class=\"wikitable sortable\"")?;
+    let mut rules = 0;
+    for line in lines.iter() {
+        let cells = match line {
+            Line::Row(cells) => cells,
+            Line::Rule => {
+                rules += 1;
+                continue;
+            }
+        };
+        // The summary statistics stay at the bottom when sorting.
+        if rules > 1 {
+            writeln!(out, "|- class=\"sortbottom\"")?;
+        } else {
+            writeln!(out, "|-")?;
+        }
+        let marker = if rules == 0 { '!' } else { '|' };
+        for cell in cells.iter() {
+            let text = escape(&cell.text);
+            match attributes(cell).filter(|_| rules > 0) {
+                Some(attributes) => writeln!(out, "{} {} | {}", marker, attributes, text)?,
+                None if text.is_empty() => writeln!(out, "{}", marker)?,
+                None => writeln!(out, "{} {}", marker, text)?,
+            }
+        }
+    }
+    writeln!(out, "|}}")
+}
+
+/// Writes the table of `report` with the numbers written in `style`,
+/// followed by the table of the warnings, if any.
+pub fn write<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    write_lines(out, &lines(report, style))?;
+    if let Some(lines) = warning_lines(report) {
+        writeln!(out)?;
+        write_lines(out, &lines)?;
+    }
+    Ok(())
+}
+
+/// Writes a section with a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+) -> std::io::Result<()> {
+    for (i, (number, report)) in articles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "== Article {} ==", number)?;
+        write(out, report, style)?;
+    }
+    Ok(())
+}
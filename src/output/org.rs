@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The table of a `Report` as an Emacs Org table, aligned like Org aligns
+//! it, with hlines between the header, the translations and the summary
+//! statistics.
+
+use super::html::Style;
+use super::table::lines;
+use super::table::warning_lines;
+use super::table::Line;
+use crate::Report;
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// `text` with the column separator written as an entity.
+fn escape(text: &str) -> String {
+    text.replace('|', "\\vert{}")
+}
+
+fn write_lines<W: Write>(out: &mut W, lines: &[Line]) -> std::io::Result<()> {
+    let mut widths: Vec<usize> = Vec::new();
+    for line in lines.iter() {
+        if let Line::Row(cells) = line {
+            widths.resize(widths.len().max(cells.len()), 0);
+            for (width, cell) in widths.iter_mut().zip(cells.iter()) {
+                *width = (*width).max(escape(&cell.text).width());
+            }
+        }
+    }
+
+    for line in lines.iter() {
+        let cells = match line {
+            Line::Row(cells) => cells,
+            Line::Rule => {
+                let dashes: Vec<String> = widths.iter().map(|w| "-".repeat(w + 2)).collect();
+                writeln!(out, "|{}|", dashes.join("+"))?;
+                continue;
+            }
+        };
+        let mut text = String::from("|");
+        for (cell, &width) in cells.iter().zip(widths.iter()) {
+            let content = escape(&cell.text);
+            let padding = " ".repeat(width - content.width());
+            if cell.left {
+                text.push_str(&format!(" {}{} |", content, padding));
+            } else {
+                text.push_str(&format!(" {}{} |", padding, content));
+            }
+        }
+        writeln!(out, "{}", text)?;
+    }
+    Ok(())
+}
+
+/// Writes the table of `report` with the numbers written in `style`,
+/// followed by the table of the warnings, if any.
+pub fn write<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    write_lines(out, &lines(report, style))?;
+    if let Some(lines) = warning_lines(report) {
+        writeln!(out)?;
+        write_lines(out, &lines)?;
+    }
+    Ok(())
+}
+
+/// Writes a heading with a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+) -> std::io::Result<()> {
+    for (i, (number, report)) in articles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "* Article {}", number)?;
+        write(out, report, style)?;
+    }
+    Ok(())
+}
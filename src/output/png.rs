@@ -0,0 +1,71 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The SVG charts rasterized as PNG images, e.g. for pasting into documents
+//! and issue trackers that don't take SVG.
+
+use resvg::tiny_skia::Pixmap;
+use resvg::tiny_skia::Transform;
+use resvg::usvg::fontdb::Database;
+use resvg::usvg::fontdb::Family;
+use resvg::usvg::fontdb::Query;
+use resvg::usvg::Options;
+use resvg::usvg::Tree;
+use std::io::Write;
+use tracing::warn;
+
+/// Pixels per unit of the SVG image, so that the image stays sharp on
+/// high-density screens.
+const SCALE: f32 = 2.0;
+
+/// Loads the system fonts into `fonts`, with a sans-serif one that is
+/// installed standing for `sans-serif` if Arial, which fontdb takes it to
+/// mean, isn't.
+fn load_fonts(fonts: &mut Database) {
+    fonts.load_system_fonts();
+    let query = Query {
+        families: &[Family::SansSerif],
+        ..Query::default()
+    };
+    if fonts.query(&query).is_some() {
+        return;
+    }
+    let families: Vec<&str> = fonts
+        .faces()
+        .flat_map(|face| face.families.iter().map(|(family, _)| &family[..]))
+        .collect();
+    let sans_serif = families
+        .iter()
+        .find(|family| family.contains("Sans") && !family.contains("Mono"))
+        .or_else(|| families.first());
+    match sans_serif {
+        Some(family) => {
+            let family = family.to_string();
+            fonts.set_sans_serif_family(family);
+        }
+        None => warn!("no system fonts found, so the PNG image has no text"),
+    }
+}
+
+/// Writes the SVG image `svg` as a PNG image, with the text in the system
+/// fonts.
+pub fn write<W: Write>(out: &mut W, svg: &[u8]) -> std::io::Result<()> {
+    let mut options = Options::default();
+    load_fonts(options.fontdb_mut());
+    let tree = Tree::from_data(svg, &options).map_err(std::io::Error::other)?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(SCALE)
+        .ok_or_else(|| std::io::Error::other("the image is too large"))?;
+    let mut pixmap = Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| std::io::Error::other("the image is too large"))?;
+    resvg::render(
+        &tree,
+        Transform::from_scale(SCALE, SCALE),
+        &mut pixmap.as_mut(),
+    );
+    let png = pixmap.encode_png().map_err(std::io::Error::other)?;
+    out.write_all(&png)
+}
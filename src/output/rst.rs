@@ -0,0 +1,107 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The table of a `Report` as a reStructuredText grid table for Sphinx and
+//! other docutils-based documentation. The cells aren't colored, since
+//! reStructuredText has no syntax for it.
+
+use super::html::Style;
+use super::table::lines;
+use super::table::warning_lines;
+use super::table::Line;
+use crate::Report;
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// `text` with the characters that start inline markup escaped.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '`' | '|' | '_') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// The border between rows, with `=` under the header and `-` elsewhere.
+fn border(widths: &[usize], fill: char) -> String {
+    let mut border = String::from("+");
+    for &width in widths {
+        border.extend(std::iter::repeat_n(fill, width + 2));
+        border.push('+');
+    }
+    border
+}
+
+fn write_lines<W: Write>(out: &mut W, lines: Vec<Line>) -> std::io::Result<()> {
+    // The rows without the rules, since a grid table has a border between
+    // every pair of rows anyway and only the header may be set apart.
+    let rows: Vec<Vec<(String, bool)>> = lines
+        .into_iter()
+        .filter_map(|line| match line {
+            Line::Row(cells) => Some(
+                cells
+                    .into_iter()
+                    .map(|cell| (escape(&cell.text), cell.left))
+                    .collect(),
+            ),
+            Line::Rule => None,
+        })
+        .collect();
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows.iter() {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, (text, _)) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(text.width());
+        }
+    }
+
+    writeln!(out, "{}", border(&widths, '-'))?;
+    for (i, row) in rows.iter().enumerate() {
+        let mut text = String::from("|");
+        for (&width, (content, left)) in widths.iter().zip(row.iter()) {
+            let padding = " ".repeat(width - content.width());
+            if *left {
+                text.push_str(&format!(" {}{} |", content, padding));
+            } else {
+                text.push_str(&format!(" {}{} |", padding, content));
+            }
+        }
+        writeln!(out, "{}", text)?;
+        writeln!(out, "{}", border(&widths, if i == 0 { '=' } else { '-' }))?;
+    }
+    Ok(())
+}
+
+/// Writes the table of `report` with the numbers written in `style`,
+/// followed by the table of the warnings, if any.
+pub fn write<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    write_lines(out, lines(report, style))?;
+    if let Some(lines) = warning_lines(report) {
+        writeln!(out)?;
+        write_lines(out, lines)?;
+    }
+    Ok(())
+}
+
+/// Writes a section with a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+) -> std::io::Result<()> {
+    for (i, (number, report)) in articles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let title = format!("Article {}", number);
+        writeln!(out, "{}", title)?;
+        writeln!(out, "{}", "=".repeat(title.len()))?;
+        writeln!(out)?;
+        write(out, report, style)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! A static site of a report: `index.html` with the table, linking to a
+//! page per translation with its measured metrics, its lengths in the
+//! normalization forms and its per-article counts.
+
+use crate::output;
+use crate::output::html;
+use crate::output::Numbers;
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use crate::UdhrError;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+
+/// The file name of the page of the translation `code`.
+fn page_name(code: &str) -> String {
+    format!("{}.html", code)
+}
+
+/// The stylesheet shared by the pages
+const STYLESHEET: &str = "style.css";
+
+fn write_head<W: Write>(out: &mut W, title: &str) -> std::io::Result<()> {
+    html::write_head(out, title)?;
+    writeln!(out, "<link rel=stylesheet href=\"{}\">", STYLESHEET)
+}
+
+fn write_index<W: Write>(out: &mut W, report: &Report, style: &html::Style) -> std::io::Result<()> {
+    write_head(out, "UDHR lengths")?;
+    writeln!(out, "<h1>UDHR lengths</h1>")?;
+    html::write_table(out, report, "counts", html::CAPTION, &page_name, style)
+}
+
+fn write_lang<W: Write>(
+    out: &mut W,
+    lang: &Lang,
+    code: &str,
+    measured: &[Metric],
+    numbers: Numbers,
+) -> std::io::Result<()> {
+    let metrics = &lang.metrics;
+    write_head(out, &lang.name)?;
+    writeln!(out, "<p><a href=\"index.html\">All translations</a></p>")?;
+    writeln!(out, "<h1>{}</h1>", lang.name)?;
+    writeln!(out, "<dl>")?;
+    writeln!(out, "<dt>Code<dd>{}", code)?;
+    if let Some(script) = &lang.script {
+        writeln!(out, "<dt>Script<dd>{}", script)?;
+    }
+    if let Some(stage) = lang.stage {
+        writeln!(out, "<dt>Stage<dd>{}", stage)?;
+    }
+    writeln!(
+        out,
+        "<dt>Text<dd><a href=\"{}\">unicode.org</a>",
+        html::udhr_href(code)
+    )?;
+    writeln!(out, "</dl>")?;
+
+    writeln!(out, "<table id=metrics>")?;
+    writeln!(out, "<caption>Metrics</caption>")?;
+    writeln!(out, "<thead>")?;
+    writeln!(
+        out,
+        "<tr><th scope=col>Metric</th><th scope=col>Value</th></tr>"
+    )?;
+    writeln!(out, "</thead>")?;
+    writeln!(out, "<tbody>")?;
+    // The normalization forms have a table of their own.
+    for &metric in measured
+        .iter()
+        .filter(|m| !matches!(m, Metric::Normalized(..)))
+    {
+        if let Some(value) = metric.value(metrics) {
+            writeln!(
+                out,
+                "<tr><th scope=row>{}</th><td>{}</td></tr>",
+                metric.label(),
+                numbers.metric(metric, value)
+            )?;
+        }
+    }
+    writeln!(out, "</tbody>")?;
+    writeln!(out, "</table>")?;
+
+    if !metrics.normalized.is_empty() {
+        writeln!(out, "<table id=normalization>")?;
+        writeln!(out, "<caption>Normalization forms</caption>")?;
+        writeln!(out, "<thead>")?;
+        writeln!(
+            out,
+            "<tr><th scope=col>Form</th><th scope=col>UTF-8</th><th scope=col>UTF-16</th><th scope=col>UTF-32</th><th scope=col>Δ UTF-8</th></tr>"
+        )?;
+        writeln!(out, "</thead>")?;
+        writeln!(out, "<tbody>")?;
+        for (form, lengths) in metrics.normalized.iter() {
+            writeln!(
+                out,
+                "<tr><th scope=row>{}</th><td>{}</td><td>{}</td><td>{}</td><td>{:+}</td></tr>",
+                form.name().to_ascii_uppercase(),
+                numbers.format(lengths.utf8 as f64, 0),
+                numbers.format(lengths.utf16 as f64, 0),
+                numbers.format(lengths.utf32 as f64, 0),
+                lengths.utf8 as i64 - metrics.utf8 as i64
+            )?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+    }
+
+    if !lang.articles.is_empty() {
+        writeln!(out, "<table id=articles>")?;
+        writeln!(out, "<caption>Articles</caption>")?;
+        writeln!(out, "<thead>")?;
+        write!(out, "<tr><th scope=col>Article</th>")?;
+        for metric in Metric::BASE.iter() {
+            write!(out, "<th scope=col>{}</th>", metric.label())?;
+        }
+        writeln!(out, "</tr>")?;
+        writeln!(out, "</thead>")?;
+        writeln!(out, "<tbody>")?;
+        for article in lang.articles.iter() {
+            write!(out, "<tr><th scope=row>{}</th>", article.number)?;
+            for metric in Metric::BASE.iter() {
+                match metric.value(&article.metrics) {
+                    Some(value) => write!(out, "<td>{}</td>", numbers.metric(*metric, value))?,
+                    None => write!(out, "<td></td>")?,
+                }
+            }
+            writeln!(out, "</tr>")?;
+        }
+        writeln!(out, "</tbody>")?;
+        writeln!(out, "</table>")?;
+    }
+    Ok(())
+}
+
+fn write_file(
+    path: &Path,
+    write: impl FnOnce(&mut BufWriter<File>) -> std::io::Result<()>,
+) -> Result<(), UdhrError> {
+    output::write_file(path, write).map_err(UdhrError::io(path.display()))
+}
+
+/// Writes the site of `report` into the directory `dir`, creating it if
+/// needed, with the cells written in `style`. The translation pages list the
+/// metrics in `measured`.
+pub fn write(
+    dir: &Path,
+    report: &Report,
+    measured: &[Metric],
+    style: &html::Style,
+) -> Result<(), UdhrError> {
+    std::fs::create_dir_all(dir).map_err(UdhrError::io(dir.display()))?;
+    write_file(&dir.join(STYLESHEET), |out| {
+        html::write_stylesheet(out, &style.coloring, true, false)
+    })?;
+    write_file(&dir.join("index.html"), |out| {
+        write_index(out, report, style)
+    })?;
+    for lang in report.langs.iter() {
+        if let Some(code) = &lang.code {
+            write_file(&dir.join(page_name(code)), |out| {
+                write_lang(out, lang, code, measured, style.numbers)
+            })?;
+        }
+    }
+    Ok(())
+}
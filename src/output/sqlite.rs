@@ -0,0 +1,141 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The metrics of a `Report` as an SQLite database for querying with SQL.
+//!
+//! * `languages`: the `id`, `code`, `name`, `script`, `stage`,
+//!   `smallest_encoding` and `direction` of each translation
+//! * `metrics`: the `value` of each column (`metric`, by the name used on
+//!   the command line) of each translation (`language`, its `id`)
+//! * `articles`: likewise for each `article`, when measured per article
+
+use super::temporary_path;
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use crate::UdhrError;
+use rusqlite::params;
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use rusqlite::Transaction;
+use std::path::Path;
+
+const SCHEMA: &str = "
+CREATE TABLE languages (
+  id INTEGER PRIMARY KEY,
+  code TEXT,
+  name TEXT NOT NULL,
+  script TEXT,
+  stage INTEGER,
+  smallest_encoding TEXT,
+  direction TEXT
+);
+CREATE TABLE metrics (
+  language INTEGER NOT NULL REFERENCES languages (id),
+  metric TEXT NOT NULL,
+  value NUMERIC NOT NULL,
+  PRIMARY KEY (language, metric)
+);
+";
+
+const ARTICLES_SCHEMA: &str = "
+CREATE TABLE articles (
+  language INTEGER NOT NULL REFERENCES languages (id),
+  article INTEGER NOT NULL,
+  metric TEXT NOT NULL,
+  value NUMERIC NOT NULL,
+  PRIMARY KEY (language, article, metric)
+);
+";
+
+/// The value of `metric` of `lang`, the counts as integers like in the
+/// JSON output.
+fn value(lang: &Lang, metric: Metric) -> Option<Value> {
+    let value = metric.value(&lang.metrics)?;
+    Some(if metric.is_count() {
+        Value::Integer(value as i64)
+    } else {
+        Value::Real(value)
+    })
+}
+
+fn insert(
+    transaction: &Transaction,
+    report: &Report,
+    articles: Option<&[(u32, Report)]>,
+) -> rusqlite::Result<()> {
+    let mut language = transaction.prepare(
+        "INSERT INTO languages (id, code, name, script, stage, smallest_encoding, direction) \
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+    )?;
+    let mut metric =
+        transaction.prepare("INSERT INTO metrics (language, metric, value) VALUES (?1, ?2, ?3)")?;
+    for (i, lang) in report.langs.iter().enumerate() {
+        let id = i as i64 + 1;
+        language.execute(params![
+            id,
+            lang.code,
+            lang.name,
+            lang.script,
+            lang.stage,
+            lang.smallest_encoding,
+            lang.metrics.bidi.map(|b| b.direction()),
+        ])?;
+        for &column in report.columns.iter() {
+            if let Some(value) = value(lang, column) {
+                metric.execute(params![id, column.name(), value])?;
+            }
+        }
+    }
+    let articles = match articles {
+        Some(articles) => articles,
+        None => return Ok(()),
+    };
+    transaction.execute_batch(ARTICLES_SCHEMA)?;
+    let mut article = transaction.prepare(
+        "INSERT INTO articles (language, article, metric, value) VALUES (?1, ?2, ?3, ?4)",
+    )?;
+    for (number, article_report) in articles {
+        for lang in article_report.langs.iter() {
+            let id = report
+                .langs
+                .iter()
+                .position(|l| l.name == lang.name && l.code == lang.code)
+                .map(|i| i as i64 + 1);
+            for &column in article_report.columns.iter() {
+                if let Some(value) = value(lang, column) {
+                    article.execute(params![id, number, column.name(), value])?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the translations of `report` and, if given, of `articles` into a
+/// new SQLite database at `path`, replacing the file only once the
+/// database is complete.
+pub fn write(
+    path: &Path,
+    report: &Report,
+    articles: Option<&[(u32, Report)]>,
+) -> Result<(), UdhrError> {
+    let temporary = temporary_path(path);
+    // Left behind by a process with the same id that was killed
+    let _ = std::fs::remove_file(&temporary);
+    let result = Connection::open(&temporary)
+        .and_then(|mut connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(SCHEMA)?;
+            insert(&transaction, report, articles)?;
+            transaction.commit()?;
+            connection.close().map_err(|(_, e)| e)
+        })
+        .map_err(UdhrError::sqlite(path.display()))
+        .and_then(|()| std::fs::rename(&temporary, path).map_err(UdhrError::io(path.display())));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
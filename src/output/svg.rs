@@ -0,0 +1,798 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Charts of a `Report` as standalone SVG images.
+
+use super::html::bucket_colors;
+use super::html::deviation_bucket;
+use super::html::Palette;
+use super::html::Style;
+use super::html::BUCKETS;
+use super::html::CAPTION;
+use super::Numbers;
+use crate::encode::markup::escape;
+use crate::stats::Stats;
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use std::io::Write;
+
+/// The colors of the scripts, in the order in which they first occur: the
+/// Tableau 10 palette
+const SCRIPT_COLORS: [&str; 10] = [
+    "#4e79a7", "#f28e2b", "#e15759", "#76b7b2", "#59a14f", "#edc948", "#b07aa1", "#ff9da7",
+    "#9c755f", "#bab0ac",
+];
+
+/// The color of the translations without a script
+const NO_SCRIPT_COLOR: &str = "#888";
+
+const FONT_SIZE: f64 = 12.0;
+
+/// A generous estimate of the width of a character at `FONT_SIZE`, since
+/// the width of the text isn't known without the font
+const CHAR_WIDTH: f64 = 7.0;
+
+const MARGIN: f64 = 10.0;
+
+/// The height of a line of the legend
+const LINE_HEIGHT: f64 = FONT_SIZE + 4.0;
+
+/// The ticks of an axis from 0 that reaches at least `max`: the step
+/// between them and the end of the axis.
+fn axis(max: f64) -> (f64, f64) {
+    let step = tick_step(max);
+    (step, (max / step).ceil().max(1.0) * step)
+}
+
+/// The values of the ticks of an axis from 0 to `end` by `step`.
+fn ticks(step: f64, end: f64) -> impl Iterator<Item = f64> {
+    (0..=(end / step).round() as usize).map(move |i| i as f64 * step)
+}
+
+/// The scripts of the translations of `report` in the order in which they
+/// first occur, each with its color.
+fn script_colors(report: &Report) -> Vec<(&str, &'static str)> {
+    let mut scripts: Vec<(&str, &str)> = Vec::new();
+    for lang in report.langs.iter() {
+        if let Some(script) = lang.script.as_deref() {
+            if !scripts.iter().any(|&(s, _)| s == script) {
+                let color = SCRIPT_COLORS[scripts.len() % SCRIPT_COLORS.len()];
+                scripts.push((script, color));
+            }
+        }
+    }
+    scripts
+}
+
+/// The color of the script of `lang` in `scripts`.
+fn script_color(scripts: &[(&str, &'static str)], lang: &Lang) -> &'static str {
+    lang.script
+        .as_deref()
+        .and_then(|script| scripts.iter().find(|&&(s, _)| s == script))
+        .map_or(NO_SCRIPT_COLOR, |&(_, color)| color)
+}
+
+/// The entries of the legend of the script colors of `langs`.
+fn legend<'a>(
+    scripts: &'a [(&'a str, &'static str)],
+    langs: impl IntoIterator<Item = &'a Lang>,
+) -> Vec<(&'a str, &'static str)> {
+    let mut legend = scripts.to_vec();
+    if langs.into_iter().any(|lang| lang.script.is_none()) {
+        legend.push(("No script", NO_SCRIPT_COLOR));
+    }
+    legend
+}
+
+/// Writes `legend` in rows from (`x`, `y`).
+fn write_legend<W: Write>(
+    out: &mut W,
+    legend: &[(&str, &str)],
+    x: f64,
+    y: f64,
+) -> std::io::Result<()> {
+    for (i, (script, color)) in legend.iter().enumerate() {
+        let y = y + i as f64 * LINE_HEIGHT;
+        writeln!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{2}\" height=\"{2}\" fill=\"{3}\"/>",
+            x, y, FONT_SIZE, color
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" dominant-baseline=\"central\">{}</text>",
+            x + FONT_SIZE + MARGIN / 2.0,
+            y + FONT_SIZE / 2.0,
+            escape(script)
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes the start of an SVG image of `width` by `height` titled `title`,
+/// with the title on top.
+fn write_start<W: Write>(out: &mut W, width: f64, height: f64, title: &str) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0:.0}\" height=\"{1:.0}\" viewBox=\"0 0 {0:.0} {1:.0}\" font-family=\"sans-serif\" font-size=\"{2}\">",
+        width, height, FONT_SIZE
+    )?;
+    writeln!(out, "<title>{}</title>", escape(title))?;
+    writeln!(out, "<rect width=\"100%\" height=\"100%\" fill=\"#fff\"/>")?;
+    writeln!(
+        out,
+        "<text x=\"{}\" y=\"{}\" font-weight=\"bold\">{}</text>",
+        MARGIN,
+        MARGIN + FONT_SIZE,
+        escape(title)
+    )
+}
+
+/// A round step between the ticks of an axis up to `max` that makes about
+/// five ticks.
+fn tick_step(max: f64) -> f64 {
+    if max <= 0.0 {
+        return 1.0;
+    }
+    let rough = max / 5.0;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= rough)
+        .unwrap_or(10.0 * magnitude)
+}
+
+/// Writes a horizontal bar chart of `metric` with a bar for each
+/// translation of `report`, in its order, colored by script and labeled
+/// with the value written with `numbers`.
+pub fn write_bars<W: Write>(
+    out: &mut W,
+    report: &Report,
+    metric: Metric,
+    numbers: Numbers,
+) -> std::io::Result<()> {
+    const BAR_HEIGHT: f64 = 16.0;
+    const BAR_GAP: f64 = 4.0;
+    const PLOT_WIDTH: f64 = 500.0;
+
+    let bars: Vec<_> = report
+        .langs
+        .iter()
+        .filter_map(|lang| Some((lang, metric.value(&lang.metrics)?)))
+        .collect();
+    let scripts = script_colors(report);
+    let max = bars.iter().map(|&(_, value)| value).fold(0.0, f64::max);
+    let (step, axis_max) = axis(max);
+    let longest_name = bars
+        .iter()
+        .map(|(lang, _)| lang.name.chars().count())
+        .max()
+        .unwrap_or(0);
+    let longest_value = numbers.metric(metric, axis_max).chars().count();
+
+    let title_height = FONT_SIZE * 2.0;
+    let label_width = longest_name as f64 * CHAR_WIDTH + MARGIN;
+    let plot_x = MARGIN + label_width;
+    let plot_y = MARGIN + title_height;
+    let plot_height = bars.len() as f64 * (BAR_HEIGHT + BAR_GAP);
+    let axis_height = FONT_SIZE * 2.0;
+    let legend_y = plot_y + plot_height + axis_height + FONT_SIZE;
+    let width = plot_x + PLOT_WIDTH + longest_value as f64 * CHAR_WIDTH + 2.0 * MARGIN;
+    let legend = legend(&scripts, bars.iter().map(|&(lang, _)| lang));
+    let height = legend_y + legend.len() as f64 * LINE_HEIGHT + MARGIN;
+    let x = |value: f64| plot_x + value / axis_max * PLOT_WIDTH;
+
+    write_start(
+        out,
+        width,
+        height,
+        &format!("{} by translation", metric.label()),
+    )?;
+
+    // The grid and the axis
+    for tick in ticks(step, axis_max) {
+        writeln!(
+            out,
+            "<line x1=\"{0:.1}\" y1=\"{1:.1}\" x2=\"{0:.1}\" y2=\"{2:.1}\" stroke=\"#ddd\"/>",
+            x(tick),
+            plot_y,
+            plot_y + plot_height
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            x(tick),
+            plot_y + plot_height + FONT_SIZE * 1.5,
+            numbers.metric(metric, tick)
+        )?;
+    }
+
+    for (i, &(lang, value)) in bars.iter().enumerate() {
+        let y = plot_y + i as f64 * (BAR_HEIGHT + BAR_GAP);
+        let color = script_color(&scripts, lang);
+        let formatted = numbers.metric(metric, value);
+        writeln!(out, "<g>")?;
+        writeln!(out, "<title>{}: {}</title>", escape(&lang.name), formatted)?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" dominant-baseline=\"central\">{}</text>",
+            plot_x - MARGIN / 2.0,
+            y + BAR_HEIGHT / 2.0,
+            escape(&lang.name)
+        )?;
+        writeln!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\"/>",
+            plot_x,
+            y,
+            x(value) - plot_x,
+            BAR_HEIGHT,
+            color
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" dominant-baseline=\"central\">{}</text>",
+            x(value) + MARGIN / 2.0,
+            y + BAR_HEIGHT / 2.0,
+            formatted
+        )?;
+        writeln!(out, "</g>")?;
+    }
+
+    write_legend(out, &legend, plot_x, legend_y)?;
+    writeln!(out, "</svg>")
+}
+
+/// Writes a scatter plot of `y` against `x` with a point for each
+/// translation of `report`, colored by script and labeled with its name and
+/// values written with `numbers` on hover.
+pub fn write_scatter<W: Write>(
+    out: &mut W,
+    report: &Report,
+    x: Metric,
+    y: Metric,
+    numbers: Numbers,
+) -> std::io::Result<()> {
+    const PLOT_WIDTH: f64 = 500.0;
+    const PLOT_HEIGHT: f64 = 400.0;
+    const RADIUS: f64 = 4.0;
+
+    let points: Vec<_> = report
+        .langs
+        .iter()
+        .filter_map(|lang| Some((lang, x.value(&lang.metrics)?, y.value(&lang.metrics)?)))
+        .collect();
+    let scripts = script_colors(report);
+    let legend = legend(&scripts, points.iter().map(|&(lang, _, _)| lang));
+    let (x_step, x_max) = axis(points.iter().map(|p| p.1).fold(0.0, f64::max));
+    let (y_step, y_max) = axis(points.iter().map(|p| p.2).fold(0.0, f64::max));
+    let y_label_width = numbers.metric(y, y_max).chars().count() as f64 * CHAR_WIDTH;
+
+    let plot_x = MARGIN + FONT_SIZE * 2.0 + y_label_width + MARGIN;
+    let plot_y = MARGIN + FONT_SIZE * 3.0;
+    let legend_x = plot_x + PLOT_WIDTH + MARGIN * 3.0;
+    let longest_script = legend
+        .iter()
+        .map(|(script, _)| script.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = legend_x + FONT_SIZE + MARGIN + longest_script as f64 * CHAR_WIDTH + MARGIN;
+    let height = (plot_y + PLOT_HEIGHT + FONT_SIZE * 4.0 + MARGIN)
+        .max(plot_y + legend.len() as f64 * LINE_HEIGHT + MARGIN);
+    let to_x = |value: f64| plot_x + value / x_max * PLOT_WIDTH;
+    let to_y = |value: f64| plot_y + PLOT_HEIGHT - value / y_max * PLOT_HEIGHT;
+
+    write_start(
+        out,
+        width,
+        height,
+        &format!("{} against {}", y.label(), x.label()),
+    )?;
+
+    // The grid and the axes
+    for tick in ticks(x_step, x_max) {
+        writeln!(
+            out,
+            "<line x1=\"{0:.1}\" y1=\"{1:.1}\" x2=\"{0:.1}\" y2=\"{2:.1}\" stroke=\"#ddd\"/>",
+            to_x(tick),
+            plot_y,
+            plot_y + PLOT_HEIGHT
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            to_x(tick),
+            plot_y + PLOT_HEIGHT + FONT_SIZE * 1.5,
+            numbers.metric(x, tick)
+        )?;
+    }
+    write_y_axis(
+        out,
+        ticks(y_step, y_max),
+        to_y,
+        plot_x,
+        PLOT_WIDTH,
+        |tick| numbers.metric(y, tick),
+    )?;
+    writeln!(
+        out,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+        plot_x + PLOT_WIDTH / 2.0,
+        plot_y + PLOT_HEIGHT + FONT_SIZE * 3.5,
+        escape(&x.label())
+    )?;
+    write_y_label(out, plot_y + PLOT_HEIGHT / 2.0, &y.label())?;
+
+    for &(lang, x_value, y_value) in points.iter() {
+        writeln!(
+            out,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"{}\" fill-opacity=\"0.8\"><title>{}: {} {}, {} {}</title></circle>",
+            to_x(x_value),
+            to_y(y_value),
+            RADIUS,
+            script_color(&scripts, lang),
+            escape(&lang.name),
+            numbers.metric(x, x_value),
+            escape(&x.label()),
+            numbers.metric(y, y_value),
+            escape(&y.label())
+        )?;
+    }
+
+    write_legend(out, &legend, legend_x, plot_y)?;
+    writeln!(out, "</svg>")
+}
+
+/// Writes the horizontal grid lines and the labels of the vertical axis of
+/// a plot from `plot_x` that is `plot_width` wide, at the `ticks` placed by
+/// `to_y`.
+fn write_y_axis<W: Write>(
+    out: &mut W,
+    ticks: impl Iterator<Item = f64>,
+    to_y: impl Fn(f64) -> f64,
+    plot_x: f64,
+    plot_width: f64,
+    label: impl Fn(f64) -> String,
+) -> std::io::Result<()> {
+    for tick in ticks {
+        writeln!(
+            out,
+            "<line x1=\"{0:.1}\" y1=\"{1:.1}\" x2=\"{2:.1}\" y2=\"{1:.1}\" stroke=\"#ddd\"/>",
+            plot_x,
+            to_y(tick),
+            plot_x + plot_width
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" dominant-baseline=\"central\">{}</text>",
+            plot_x - MARGIN / 2.0,
+            to_y(tick),
+            label(tick)
+        )?;
+    }
+    Ok(())
+}
+
+/// Writes `label` rotated along the vertical axis at height `y`.
+fn write_y_label<W: Write>(out: &mut W, y: f64, label: &str) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "<text transform=\"translate({:.1} {:.1}) rotate(-90)\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>",
+        MARGIN + FONT_SIZE / 2.0,
+        y,
+        escape(label)
+    )
+}
+
+/// The number of bins of a histogram of `len` values by Sturges' rule.
+fn sturges(len: usize) -> usize {
+    (len.max(1) as f64).log2().ceil() as usize + 1
+}
+
+/// Writes a histogram of `metric` over the translations of `report` with
+/// `bins` bins of equal width, by default as many as Sturges' rule gives.
+pub fn write_histogram<W: Write>(
+    out: &mut W,
+    report: &Report,
+    metric: Metric,
+    bins: Option<usize>,
+    numbers: Numbers,
+) -> std::io::Result<()> {
+    const PLOT_WIDTH: f64 = 500.0;
+    const PLOT_HEIGHT: f64 = 300.0;
+
+    let values: Vec<f64> = report
+        .langs
+        .iter()
+        .filter_map(|lang| metric.value(&lang.metrics))
+        .collect();
+    let bins = bins.unwrap_or_else(|| sturges(values.len())).max(1);
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let (min, max) = if values.is_empty() {
+        (0.0, 0.0)
+    } else {
+        (min, max)
+    };
+    let mut bin_width = ((max - min) / bins as f64).max(f64::MIN_POSITIVE);
+    // Bins of whole counts
+    if metric.is_count() {
+        bin_width = bin_width.ceil().max(1.0);
+    }
+    let mut counts = vec![0usize; bins];
+    for value in values.iter() {
+        let bin = ((value - min) / bin_width) as usize;
+        counts[bin.min(bins - 1)] += 1;
+    }
+    let largest = counts.iter().copied().max().unwrap_or(0);
+    // Ticks of whole translations
+    let y_step = tick_step(largest as f64).ceil().max(1.0);
+    let y_max = (largest as f64 / y_step).ceil().max(1.0) * y_step;
+    let edge = |i: usize| min + i as f64 * bin_width;
+    let end = edge(bins);
+
+    let y_label_width = numbers.format(y_max, 0).chars().count() as f64 * CHAR_WIDTH;
+    let plot_x = MARGIN + FONT_SIZE * 2.0 + y_label_width + MARGIN;
+    let plot_y = MARGIN + FONT_SIZE * 3.0;
+    let last_label = numbers.metric(metric, end).chars().count() as f64 * CHAR_WIDTH;
+    let width = plot_x + PLOT_WIDTH + last_label / 2.0 + MARGIN;
+    let height = plot_y + PLOT_HEIGHT + FONT_SIZE * 4.0 + MARGIN;
+    let to_x = |value: f64| plot_x + (value - min) / (end - min) * PLOT_WIDTH;
+    let to_y = |count: f64| plot_y + PLOT_HEIGHT - count / y_max * PLOT_HEIGHT;
+
+    write_start(
+        out,
+        width,
+        height,
+        &format!("Distribution of {}", metric.label()),
+    )?;
+    write_y_axis(
+        out,
+        ticks(y_step, y_max),
+        to_y,
+        plot_x,
+        PLOT_WIDTH,
+        |tick| numbers.format(tick, 0),
+    )?;
+    write_y_label(out, plot_y + PLOT_HEIGHT / 2.0, "Translations")?;
+
+    // Label at most about ten of the edges of the bins.
+    let every = bins.div_ceil(10);
+    for i in (0..=bins).step_by(every) {
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            to_x(edge(i)),
+            plot_y + PLOT_HEIGHT + FONT_SIZE * 1.5,
+            numbers.metric(metric, edge(i))
+        )?;
+    }
+    writeln!(
+        out,
+        "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+        plot_x + PLOT_WIDTH / 2.0,
+        plot_y + PLOT_HEIGHT + FONT_SIZE * 3.5,
+        escape(&metric.label())
+    )?;
+
+    for (i, &count) in counts.iter().enumerate() {
+        let (x, y) = (to_x(edge(i)), to_y(count as f64));
+        writeln!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{}\" stroke=\"#fff\"><title>{}–{}: {}</title></rect>",
+            x,
+            y,
+            to_x(edge(i + 1)) - x,
+            plot_y + PLOT_HEIGHT - y,
+            SCRIPT_COLORS[0],
+            numbers.metric(metric, edge(i)),
+            numbers.metric(metric, edge(i + 1)),
+            count
+        )?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Writes a box plot of `metric` for each script of the translations of
+/// `report`, with the whiskers reaching the smallest and the largest value
+/// that aren't outliers by the outlier method of the report and the
+/// outliers as points.
+pub fn write_boxplot<W: Write>(
+    out: &mut W,
+    report: &Report,
+    metric: Metric,
+    numbers: Numbers,
+) -> std::io::Result<()> {
+    const SLOT_WIDTH: f64 = 70.0;
+    const BOX_WIDTH: f64 = 30.0;
+    const PLOT_HEIGHT: f64 = 300.0;
+    const RADIUS: f64 = 3.0;
+
+    let scripts = script_colors(report);
+    let groups: Vec<(&str, &str, Vec<Lang>, Stats)> = legend(&scripts, report.langs.iter())
+        .into_iter()
+        .filter_map(|(name, color)| {
+            let script = scripts.iter().any(|&(s, _)| s == name).then_some(name);
+            let langs: Vec<Lang> = report
+                .langs
+                .iter()
+                .filter(|lang| lang.script.as_deref() == script)
+                .filter(|lang| metric.value(&lang.metrics).is_some())
+                .cloned()
+                .collect();
+            if langs.is_empty() {
+                return None;
+            }
+            let stats = Stats::new(&langs, metric, &report.stats_options);
+            Some((name, color, langs, stats))
+        })
+        .collect();
+    let max = groups.iter().map(|g| g.3.max).fold(0.0, f64::max);
+    let (y_step, y_max) = axis(max);
+
+    let y_label_width = numbers.metric(metric, y_max).chars().count() as f64 * CHAR_WIDTH;
+    let plot_x = MARGIN + FONT_SIZE * 2.0 + y_label_width + MARGIN;
+    let plot_y = MARGIN + FONT_SIZE * 3.0;
+    let plot_width = groups.len().max(1) as f64 * SLOT_WIDTH;
+    let title = format!("{} by script", metric.label());
+    let width = (plot_x + plot_width + MARGIN)
+        .max(MARGIN * 2.0 + title.chars().count() as f64 * CHAR_WIDTH);
+    let height = plot_y + PLOT_HEIGHT + FONT_SIZE * 2.0 + MARGIN;
+    let to_y = |value: f64| plot_y + PLOT_HEIGHT - value / y_max * PLOT_HEIGHT;
+
+    write_start(out, width, height, &title)?;
+    write_y_axis(
+        out,
+        ticks(y_step, y_max),
+        to_y,
+        plot_x,
+        plot_width,
+        |tick| numbers.metric(metric, tick),
+    )?;
+    write_y_label(out, plot_y + PLOT_HEIGHT / 2.0, &metric.label())?;
+
+    for (i, (name, color, langs, stats)) in groups.iter().enumerate() {
+        let center = plot_x + (i as f64 + 0.5) * SLOT_WIDTH;
+        let (left, right) = (center - BOX_WIDTH / 2.0, center + BOX_WIDTH / 2.0);
+        let is_outlier = |lang: &Lang| {
+            let id = lang.code.as_ref().unwrap_or(&lang.name);
+            stats.outliers.contains(id)
+        };
+        let low = langs
+            .iter()
+            .filter(|lang| !is_outlier(lang))
+            .filter_map(|lang| metric.value(&lang.metrics))
+            .fold(stats.max_ignoring_outliers, f64::min);
+        writeln!(out, "<g>")?;
+        writeln!(
+            out,
+            "<title>{}: median {}, quartiles {}–{}, {} translation{}</title>",
+            escape(name),
+            numbers.metric(metric, stats.median),
+            numbers.metric(metric, stats.q1),
+            numbers.metric(metric, stats.q3),
+            langs.len(),
+            if langs.len() == 1 { "" } else { "s" }
+        )?;
+        writeln!(
+            out,
+            "<line x1=\"{0:.1}\" y1=\"{1:.1}\" x2=\"{0:.1}\" y2=\"{2:.1}\" stroke=\"#333\"/>",
+            center,
+            to_y(low),
+            to_y(stats.max_ignoring_outliers)
+        )?;
+        for value in [low, stats.max_ignoring_outliers] {
+            writeln!(
+                out,
+                "<line x1=\"{:.1}\" y1=\"{2:.1}\" x2=\"{:.1}\" y2=\"{2:.1}\" stroke=\"#333\"/>",
+                center - BOX_WIDTH / 4.0,
+                center + BOX_WIDTH / 4.0,
+                to_y(value)
+            )?;
+        }
+        writeln!(
+            out,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{:.1}\" fill=\"{}\" stroke=\"#333\"/>",
+            left,
+            to_y(stats.q3),
+            BOX_WIDTH,
+            to_y(stats.q1) - to_y(stats.q3),
+            color
+        )?;
+        writeln!(
+            out,
+            "<line x1=\"{:.1}\" y1=\"{2:.1}\" x2=\"{:.1}\" y2=\"{2:.1}\" stroke=\"#333\" stroke-width=\"2\"/>",
+            left,
+            right,
+            to_y(stats.median)
+        )?;
+        writeln!(out, "</g>")?;
+        for lang in langs.iter().filter(|lang| is_outlier(lang)) {
+            if let Some(value) = metric.value(&lang.metrics) {
+                writeln!(
+                    out,
+                    "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{}\" fill=\"none\" stroke=\"#333\"><title>{}: {}</title></circle>",
+                    center,
+                    to_y(value),
+                    RADIUS,
+                    escape(&lang.name),
+                    numbers.metric(metric, value)
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+            center,
+            plot_y + PLOT_HEIGHT + FONT_SIZE * 1.5,
+            escape(name)
+        )?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Writes a heatmap of the translations of `report` in rows by its columns,
+/// with each cell colored by the deviation of its value from the baseline
+/// like in the HTML table and a legend of the colors below.
+pub fn write_heatmap<W: Write>(out: &mut W, report: &Report, style: &Style) -> std::io::Result<()> {
+    const ROW_HEIGHT: f64 = 20.0;
+    const SWATCH_WIDTH: f64 = 16.0;
+
+    let numbers = style.numbers;
+    let coloring = &style.coloring;
+    let values: Vec<Vec<Option<f64>>> = report
+        .langs
+        .iter()
+        .map(|lang| {
+            report
+                .columns
+                .iter()
+                .map(|&metric| metric.value(&lang.metrics))
+                .collect()
+        })
+        .collect();
+    let value_width = values
+        .iter()
+        .flatten()
+        .zip(report.columns.iter().cycle())
+        .filter_map(|(value, &metric)| value.map(|v| numbers.metric(metric, v).chars().count()))
+        .max()
+        .unwrap_or(0);
+    let cell_width = (value_width as f64 * CHAR_WIDTH + MARGIN).max(ROW_HEIGHT * 2.0);
+    let name_width = report
+        .langs
+        .iter()
+        .map(|lang| lang.name.chars().count())
+        .max()
+        .unwrap_or(0) as f64
+        * CHAR_WIDTH;
+    let label_width = report
+        .columns
+        .iter()
+        .map(|metric| metric.label().chars().count())
+        .max()
+        .unwrap_or(0) as f64
+        * CHAR_WIDTH;
+    // The column labels are at 45 degrees.
+    let plot_x = MARGIN + name_width + MARGIN / 2.0;
+    let plot_y = MARGIN + FONT_SIZE * 2.0 + label_width * std::f64::consts::FRAC_1_SQRT_2;
+    let plot_width = report.columns.len() as f64 * cell_width;
+    let plot_height = report.langs.len() as f64 * ROW_HEIGHT;
+    let legend_y = plot_y + plot_height + MARGIN * 2.0;
+    let legend_width = (BUCKETS * 2) as f64 * SWATCH_WIDTH;
+    let width = (plot_x + plot_width + label_width * std::f64::consts::FRAC_1_SQRT_2)
+        .max(plot_x + legend_width)
+        + MARGIN;
+    let height = legend_y + ROW_HEIGHT + FONT_SIZE * 1.5 + MARGIN;
+
+    write_start(out, width, height, CAPTION)?;
+    for (j, metric) in report.columns.iter().enumerate() {
+        writeln!(
+            out,
+            "<text transform=\"translate({:.1} {:.1}) rotate(-45)\">{}</text>",
+            plot_x + (j as f64 + 0.5) * cell_width,
+            plot_y - MARGIN / 2.0,
+            escape(&metric.label())
+        )?;
+    }
+    for (i, (lang, row)) in report.langs.iter().zip(values.iter()).enumerate() {
+        let y = plot_y + i as f64 * ROW_HEIGHT;
+        writeln!(out, "<g>")?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\" dominant-baseline=\"central\">{}</text>",
+            plot_x - MARGIN / 2.0,
+            y + ROW_HEIGHT / 2.0,
+            escape(&lang.name)
+        )?;
+        for (j, (&metric, &value)) in report.columns.iter().zip(row.iter()).enumerate() {
+            let x = plot_x + j as f64 * cell_width;
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    writeln!(
+                        out,
+                        "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"none\" stroke=\"#ddd\"/>",
+                        x, y, cell_width, ROW_HEIGHT
+                    )?;
+                    continue;
+                }
+            };
+            let baseline = report
+                .baseline_value(&|m| m.value(&lang.metrics), metric)
+                .filter(|&b| b > 0.0);
+            let colors = baseline.and_then(|baseline| {
+                let (above, bucket) = deviation_bucket(baseline, value, coloring);
+                bucket_colors(above, bucket, coloring)
+            });
+            let colors = colors.map(|(background, text)| (background.to_string(), text));
+            let (background, text) = colors.unwrap_or_else(|| ("#fff".to_string(), "#000"));
+            let deviation = baseline.map_or_else(String::new, |baseline| {
+                format!(
+                    ", {}% from the baseline",
+                    numbers.format((value - baseline) / baseline * 100.0, 1)
+                )
+            });
+            writeln!(
+                out,
+                "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{}\" fill=\"{}\" stroke=\"#fff\"><title>{}, {}: {}{}</title></rect>",
+                x,
+                y,
+                cell_width,
+                ROW_HEIGHT,
+                background,
+                escape(&lang.name),
+                escape(&metric.label()),
+                numbers.metric(metric, value),
+                deviation
+            )?;
+            writeln!(
+                out,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\" pointer-events=\"none\">{}</text>",
+                x + cell_width / 2.0,
+                y + ROW_HEIGHT / 2.0,
+                text,
+                numbers.metric(metric, value)
+            )?;
+        }
+        writeln!(out, "</g>")?;
+    }
+
+    if coloring.palette != Palette::None {
+        // From the largest deviations below the baseline to the largest
+        // above it
+        let buckets = (0..BUCKETS)
+            .rev()
+            .map(|bucket| (false, bucket))
+            .chain((0..BUCKETS).map(|bucket| (true, bucket)));
+        for (k, (above, bucket)) in buckets.enumerate() {
+            if let Some((background, _)) = bucket_colors(above, bucket, coloring) {
+                writeln!(
+                    out,
+                    "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    plot_x + k as f64 * SWATCH_WIDTH,
+                    legend_y,
+                    SWATCH_WIDTH,
+                    ROW_HEIGHT / 2.0,
+                    background
+                )?;
+            }
+        }
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\">Below the baseline</text>",
+            plot_x,
+            legend_y + ROW_HEIGHT / 2.0 + FONT_SIZE * 1.5
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">Above the baseline</text>",
+            plot_x + legend_width,
+            legend_y + ROW_HEIGHT / 2.0 + FONT_SIZE * 1.5
+        )?;
+    }
+    writeln!(out, "</svg>")
+}
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The rows and cells of the table of a `Report` like the HTML table, for
+//! the formats that lay it out themselves.
+
+use super::html::bucket_colors;
+use super::html::deviation_bucket;
+use super::html::Color;
+use super::html::Style;
+use crate::stats::Stats;
+use crate::Metric;
+use crate::Report;
+
+pub(crate) struct Cell {
+    pub(crate) text: String,
+    /// The background and the text color
+    pub(crate) colors: Option<(Color, &'static str)>,
+    /// Whether aligned to the left rather than to the right
+    pub(crate) left: bool,
+}
+
+impl Cell {
+    pub(crate) fn left(text: &str) -> Cell {
+        Cell {
+            text: text.to_string(),
+            colors: None,
+            left: true,
+        }
+    }
+
+    pub(crate) fn right(text: String) -> Cell {
+        Cell {
+            text,
+            colors: None,
+            left: false,
+        }
+    }
+}
+
+/// A row of a table or a rule between its header, body and footer
+pub(crate) enum Line {
+    Row(Vec<Cell>),
+    Rule,
+}
+
+/// The cell of `value`, colored by its deviation from `baseline`.
+fn value_cell(metric: Metric, value: Option<f64>, baseline: Option<f64>, style: &Style) -> Cell {
+    let value = match value {
+        Some(value) => value,
+        None => return Cell::right(String::new()),
+    };
+    // Deviations from a baseline that isn't positive aren't meaningful.
+    let colors = baseline.filter(|&b| b > 0.0).and_then(|baseline| {
+        let (above, bucket) = deviation_bucket(baseline, value, &style.coloring);
+        bucket_colors(above, bucket, &style.coloring)
+    });
+    Cell {
+        text: style.numbers.metric(metric, value),
+        colors,
+        left: false,
+    }
+}
+
+fn row(
+    heading: &str,
+    value: &dyn Fn(Metric) -> Option<f64>,
+    script: &str,
+    report: &Report,
+    style: &Style,
+) -> Line {
+    let mut cells = vec![Cell::left(heading)];
+    for &metric in report.columns.iter() {
+        let baseline = report.baseline_value(value, metric);
+        cells.push(value_cell(metric, value(metric), baseline, style));
+    }
+    cells.push(Cell::left(script));
+    Line::Row(cells)
+}
+
+fn summary_row(
+    heading: &str,
+    stat: &dyn Fn(&Stats) -> f64,
+    report: &Report,
+    style: &Style,
+) -> Line {
+    row(
+        heading,
+        &|metric| report.summary.get(metric).map(stat),
+        "",
+        report,
+        style,
+    )
+}
+
+/// A footer row without colors.
+fn plain_row(heading: &str, stat: &dyn Fn(Metric, &Stats) -> String, report: &Report) -> Line {
+    let mut cells = vec![Cell::left(heading)];
+    for &metric in report.columns.iter() {
+        let text = report
+            .summary
+            .get(metric)
+            .map_or_else(String::new, |stats| stat(metric, stats));
+        cells.push(Cell::right(text));
+    }
+    cells.push(Cell::left(""));
+    Line::Row(cells)
+}
+
+/// The lines of the table of the translations with characters that
+/// indicate corrupt source data, if there are any: the replacement
+/// characters, the controls other than whitespace and the private-use
+/// characters of each, like the warnings table in HTML.
+pub(crate) fn warning_lines(report: &Report) -> Option<Vec<Line>> {
+    let mut lines = vec![
+        Line::Row(vec![
+            Cell::left("Warnings"),
+            Cell::right("U+FFFD".to_string()),
+            Cell::right("Controls".to_string()),
+            Cell::right("Private use".to_string()),
+        ]),
+        Line::Rule,
+    ];
+    for lang in report.langs.iter() {
+        let suspicious = lang.metrics.suspicious;
+        if !suspicious.is_empty() {
+            lines.push(Line::Row(vec![
+                Cell::left(&lang.name),
+                Cell::right(suspicious.replacement.to_string()),
+                Cell::right(suspicious.controls.to_string()),
+                Cell::right(suspicious.private_use.to_string()),
+            ]));
+        }
+    }
+    (lines.len() > 2).then_some(lines)
+}
+
+/// The lines of the table of `report`, like the HTML table.
+pub(crate) fn lines(report: &Report, style: &Style) -> Vec<Line> {
+    let numbers = style.numbers;
+    let mut header = vec![Cell::left("Name")];
+    for metric in report.columns.iter() {
+        header.push(Cell::right(metric.label()));
+    }
+    header.push(Cell::left("Script"));
+    let mut lines = vec![Line::Row(header), Line::Rule];
+
+    let mut groups = report.groups.iter();
+    let mut group = groups.next();
+    let mut remaining = group.map_or(0, |g| g.count);
+    for lang in report.langs.iter() {
+        lines.push(row(
+            &lang.name,
+            &|metric| metric.value(&lang.metrics),
+            lang.script.as_deref().unwrap_or(""),
+            report,
+            style,
+        ));
+        if let Some(g) = group {
+            remaining -= 1;
+            if remaining == 0 {
+                lines.push(row(
+                    &format!("{} median", g.script),
+                    &|metric| g.summary.get(metric).map(|s| s.median),
+                    &g.script,
+                    report,
+                    style,
+                ));
+                group = groups.next();
+                remaining = group.map_or(0, |g| g.count);
+            }
+        }
+    }
+
+    lines.push(Line::Rule);
+    lines.push(summary_row("Min", &|s| s.min, report, style));
+    lines.push(summary_row("Q1", &|s| s.q1, report, style));
+    lines.push(plain_row(
+        "Median",
+        &|m, s| numbers.metric(m, s.median),
+        report,
+    ));
+    lines.push(summary_row("Q3", &|s| s.q3, report, style));
+    for (i, p) in report.stats_options.percentiles.iter().enumerate() {
+        lines.push(summary_row(
+            &format!("P{}", p),
+            &|s| s.percentiles[i].1,
+            report,
+            style,
+        ));
+    }
+    lines.push(summary_row("Mean", &|s| s.mean, report, style));
+    if report.stats_options.populations.is_some() {
+        lines.push(row(
+            "Weighted mean",
+            &|metric| report.summary.get(metric).and_then(|s| s.weighted_mean),
+            "",
+            report,
+            style,
+        ));
+        lines.push(row(
+            "Weighted median",
+            &|metric| report.summary.get(metric).and_then(|s| s.weighted_median),
+            "",
+            report,
+            style,
+        ));
+    }
+    lines.push(summary_row(
+        "Max (ignoring outliers)",
+        &|s| s.max_ignoring_outliers,
+        report,
+        style,
+    ));
+    lines.push(summary_row("Max", &|s| s.max, report, style));
+    lines.push(plain_row(
+        "Outliers",
+        &|_, s| match s.outliers.len() {
+            0 => "0".to_string(),
+            n => format!("{} ({})", n, s.outliers.join(", ")),
+        },
+        report,
+    ));
+    lines.push(plain_row(
+        "Std. dev.",
+        &|_, s| numbers.format(s.std_dev, 1),
+        report,
+    ));
+    lines.push(plain_row(
+        "CV",
+        &|_, s| format!("{}%", numbers.format(s.cv * 100.0, 1)),
+        report,
+    ));
+    lines
+}
@@ -0,0 +1,110 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Rendering a `Report` with a Tera template.
+//!
+//! The context of the template has:
+//!
+//! * `columns`: the reported metrics, each with its `name` and `label`
+//! * `rows`: the translations in the order of the report, each with the
+//!   fields of the JSON output and `cells`, which has the `metric`, the
+//!   `value`, the `formatted` value and the `deviation` from the baseline in
+//!   percent of each column
+//! * `summary`: the statistics of each metric by name, as in the JSON output
+//! * `groups` and `encoding_wins`, as in the JSON output, if present
+
+use super::Numbers;
+use crate::stats::Summary;
+use crate::EncodingWins;
+use crate::Group;
+use crate::Lang;
+use crate::Report;
+use crate::UdhrError;
+use serde::Serialize;
+use std::path::Path;
+use tera::Context;
+use tera::Tera;
+
+#[derive(Serialize)]
+struct Column {
+    name: String,
+    label: String,
+}
+
+#[derive(Serialize)]
+struct Cell {
+    metric: String,
+    value: Option<f64>,
+    formatted: Option<String>,
+    deviation: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct Row<'a> {
+    #[serde(flatten)]
+    lang: &'a Lang,
+    cells: Vec<Cell>,
+}
+
+#[derive(Serialize)]
+struct Data<'a> {
+    columns: Vec<Column>,
+    rows: Vec<Row<'a>>,
+    summary: &'a Summary,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    groups: &'a [Group],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    encoding_wins: &'a [EncodingWins],
+}
+
+/// Renders `report` with the template in the file at `path`, writing the
+/// numbers in `formatted` with `numbers`. The values are escaped for HTML
+/// if the file name ends in `.html`, `.htm` or `.xml`.
+pub fn render(path: &Path, report: &Report, numbers: Numbers) -> Result<String, UdhrError> {
+    let source = std::fs::read_to_string(path).map_err(UdhrError::io(path.display()))?;
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let mut tera = Tera::default();
+    tera.add_raw_template(&name, &source)
+        .map_err(UdhrError::template(path.display()))?;
+    let rows = report
+        .langs
+        .iter()
+        .map(|lang| {
+            let cells = report
+                .columns
+                .iter()
+                .map(|&metric| {
+                    let value = metric.value(&lang.metrics);
+                    let baseline = report
+                        .baseline_value(&|m| m.value(&lang.metrics), metric)
+                        .filter(|&b| b > 0.0);
+                    Cell {
+                        metric: metric.name(),
+                        value,
+                        formatted: value.map(|v| numbers.metric(metric, v)),
+                        deviation: value.zip(baseline).map(|(v, b)| (v - b) / b * 100.0),
+                    }
+                })
+                .collect();
+            Row { lang, cells }
+        })
+        .collect();
+    let data = Data {
+        columns: report
+            .columns
+            .iter()
+            .map(|metric| Column {
+                name: metric.name(),
+                label: metric.label(),
+            })
+            .collect(),
+        rows,
+        summary: &report.summary,
+        groups: &report.groups,
+        encoding_wins: &report.encoding_wins,
+    };
+    let context = Context::from_serialize(&data).map_err(UdhrError::template(path.display()))?;
+    tera.render(&name, &context)
+        .map_err(UdhrError::template(path.display()))
+}
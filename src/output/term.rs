@@ -0,0 +1,176 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Aligned tables of a `Report` for the terminal, with the cells colored by
+//! their deviation from the baseline like in the HTML table.
+
+use super::html::Style;
+use super::table::lines;
+use super::table::warning_lines;
+use super::table::Cell;
+use super::table::Line;
+use crate::Report;
+use std::io::Write;
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
+
+/// How to write the tables for the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Terminal {
+    /// Whether to color the cells with ANSI escape sequences
+    pub colors: bool,
+    /// The width in columns that the names of the translations are
+    /// truncated to make the tables fit in, if any
+    pub width: Option<usize>,
+}
+
+/// The width of the terminal of standard output or, if it can't be asked,
+/// the `COLUMNS` environment variable or 80.
+pub fn stdout_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(terminal_size::Width(width), _)| usize::from(width))
+        .or_else(|| std::env::var("COLUMNS").ok()?.parse().ok())
+        .unwrap_or(80)
+}
+
+/// The narrowest that truncating makes the names
+const MIN_NAME_WIDTH: usize = 10;
+
+/// The space between the columns
+const GAP: &str = "  ";
+
+/// `text` cut to at most `width` columns, ending in an ellipsis if cut.
+fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += w;
+    }
+    truncated.push('…');
+    truncated
+}
+
+fn write_lines<W: Write>(out: &mut W, lines: &[Line], terminal: &Terminal) -> std::io::Result<()> {
+    let mut widths: Vec<usize> = Vec::new();
+    for line in lines {
+        if let Line::Row(cells) = line {
+            widths.resize(widths.len().max(cells.len()), 0);
+            for (width, cell) in widths.iter_mut().zip(cells.iter()) {
+                *width = (*width).max(cell.text.width());
+            }
+        }
+    }
+    let total = widths.iter().sum::<usize>() + GAP.len() * widths.len().saturating_sub(1);
+    if let (Some(available), Some(name)) = (terminal.width, widths.first_mut()) {
+        if total > available {
+            let others = total - *name;
+            *name = available
+                .saturating_sub(others)
+                .max(MIN_NAME_WIDTH)
+                .min(*name);
+        }
+    }
+    let total = widths.iter().sum::<usize>() + GAP.len() * widths.len().saturating_sub(1);
+
+    for line in lines {
+        let cells = match line {
+            Line::Row(cells) => cells,
+            Line::Rule => {
+                writeln!(out, "{}", "─".repeat(total))?;
+                continue;
+            }
+        };
+        let mut text = String::new();
+        for (i, (cell, &width)) in cells.iter().zip(widths.iter()).enumerate() {
+            if i > 0 {
+                text.push_str(GAP);
+            }
+            let content = truncate(&cell.text, width);
+            let padding = " ".repeat(width - content.width());
+            let padded = if cell.left {
+                // No trailing spaces at the end of the line
+                if i + 1 == cells.len() {
+                    content
+                } else {
+                    content + &padding
+                }
+            } else {
+                padding + &content
+            };
+            match cell.colors.filter(|_| terminal.colors) {
+                Some((background, foreground)) => {
+                    let (r, g, b) = background.rgb();
+                    let foreground = if foreground == "#fff" { 97 } else { 30 };
+                    text.push_str(&format!(
+                        "\x1b[48;2;{};{};{}m\x1b[{}m{}\x1b[0m",
+                        r, g, b, foreground, padded
+                    ));
+                }
+                None => text.push_str(&padded),
+            }
+        }
+        writeln!(out, "{}", text.trim_end_matches(' '))?;
+    }
+    Ok(())
+}
+
+/// Writes `report` as an aligned table with the numbers written in
+/// `style` and the cells colored by their deviation from the baseline if
+/// `terminal` has colors.
+pub fn write<W: Write>(
+    out: &mut W,
+    report: &Report,
+    style: &Style,
+    terminal: &Terminal,
+) -> std::io::Result<()> {
+    write_lines(out, &lines(report, style), terminal)?;
+    if !report.encoding_wins.is_empty() {
+        writeln!(out)?;
+        let mut lines = vec![
+            Line::Row(vec![
+                Cell::left("Smallest encoding"),
+                Cell::right("Translations".to_string()),
+            ]),
+            Line::Rule,
+        ];
+        for wins in report.encoding_wins.iter() {
+            lines.push(Line::Row(vec![
+                Cell::left(wins.encoding),
+                Cell::right(wins.count.to_string()),
+            ]));
+        }
+        write_lines(out, &lines, terminal)?;
+    }
+    if let Some(lines) = warning_lines(report) {
+        writeln!(out)?;
+        write_lines(out, &lines, terminal)?;
+    }
+    Ok(())
+}
+
+/// Writes a table like `write` for each article.
+pub fn write_articles<W: Write>(
+    out: &mut W,
+    articles: &[(u32, Report)],
+    style: &Style,
+    terminal: &Terminal,
+) -> std::io::Result<()> {
+    for (i, (number, report)) in articles.iter().enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        writeln!(out, "Article {}", number)?;
+        writeln!(out)?;
+        write(out, report, style, terminal)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Charts of a `Report` as Vega-Lite specifications with the data inlined,
+//! which the Vega editor and Observable render interactively.
+
+use crate::Metric;
+use crate::Report;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use std::io::Write;
+
+const SCHEMA: &str = "https://vega.github.io/schema/vega-lite/v5.json";
+
+/// A record of the name, code and script of each translation of `report`
+/// that has all of `metrics`, with them under their names.
+fn values(report: &Report, metrics: &[Metric]) -> Vec<Value> {
+    report
+        .langs
+        .iter()
+        .filter_map(|lang| {
+            let mut record = Map::new();
+            record.insert("name".to_string(), json!(lang.name));
+            record.insert("code".to_string(), json!(lang.code));
+            record.insert("script".to_string(), json!(lang.script));
+            for &metric in metrics {
+                let value = metric.value(&lang.metrics)?;
+                // Counts as integers, like in the JSON output
+                let value = if metric.is_count() {
+                    json!(value as i64)
+                } else {
+                    json!(value)
+                };
+                record.insert(metric.name(), value);
+            }
+            Some(Value::Object(record))
+        })
+        .collect()
+}
+
+fn field(metric: Metric) -> Value {
+    json!({
+        "field": metric.name(),
+        "type": "quantitative",
+        "title": metric.label(),
+    })
+}
+
+fn write_spec<W: Write>(out: &mut W, spec: &Value) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, spec)?;
+    writeln!(out)
+}
+
+/// Writes a horizontal bar chart of `metric` with a bar for each
+/// translation of `report`, in its order, colored by script.
+pub fn write_bars<W: Write>(out: &mut W, report: &Report, metric: Metric) -> std::io::Result<()> {
+    let spec = json!({
+        "$schema": SCHEMA,
+        "title": format!("{} by translation", metric.label()),
+        "data": { "values": values(report, &[metric]) },
+        "mark": "bar",
+        "encoding": {
+            "y": { "field": "name", "type": "nominal", "sort": null, "title": null },
+            "x": field(metric),
+            "color": { "field": "script", "type": "nominal", "title": "Script" },
+            "tooltip": [
+                { "field": "name", "type": "nominal", "title": "Name" },
+                field(metric),
+            ],
+        },
+    });
+    write_spec(out, &spec)
+}
+
+/// Writes a scatter plot of `y` against `x` with a point for each
+/// translation of `report`, colored by script.
+pub fn write_scatter<W: Write>(
+    out: &mut W,
+    report: &Report,
+    x: Metric,
+    y: Metric,
+) -> std::io::Result<()> {
+    let spec = json!({
+        "$schema": SCHEMA,
+        "title": format!("{} against {}", y.label(), x.label()),
+        "data": { "values": values(report, &[x, y]) },
+        "mark": { "type": "circle", "size": 60 },
+        "encoding": {
+            "x": field(x),
+            "y": field(y),
+            "color": { "field": "script", "type": "nominal", "title": "Script" },
+            "tooltip": [
+                { "field": "name", "type": "nominal", "title": "Name" },
+                field(x),
+                field(y),
+            ],
+        },
+    });
+    write_spec(out, &spec)
+}
+
+/// Writes a histogram of `metric` over the translations of `report` with
+/// at most `bins` bins, by default as many as Vega-Lite chooses.
+pub fn write_histogram<W: Write>(
+    out: &mut W,
+    report: &Report,
+    metric: Metric,
+    bins: Option<usize>,
+) -> std::io::Result<()> {
+    let bin = match bins {
+        Some(bins) => json!({ "maxbins": bins }),
+        None => json!(true),
+    };
+    let mut x = field(metric);
+    x["bin"] = bin;
+    let spec = json!({
+        "$schema": SCHEMA,
+        "title": format!("Distribution of {}", metric.label()),
+        "data": { "values": values(report, &[metric]) },
+        "mark": "bar",
+        "encoding": {
+            "x": x,
+            "y": { "aggregate": "count", "type": "quantitative", "title": "Translations" },
+        },
+    });
+    write_spec(out, &spec)
+}
+
+/// Writes a box plot of `metric` for each script of the translations of
+/// `report`, with the whiskers reaching 1.5 times the interquartile range.
+pub fn write_boxplot<W: Write>(
+    out: &mut W,
+    report: &Report,
+    metric: Metric,
+) -> std::io::Result<()> {
+    let spec = json!({
+        "$schema": SCHEMA,
+        "title": format!("{} by script", metric.label()),
+        "data": { "values": values(report, &[metric]) },
+        "mark": "boxplot",
+        "encoding": {
+            "x": { "field": "script", "type": "nominal", "sort": null, "title": "Script" },
+            "y": field(metric),
+            "color": { "field": "script", "type": "nominal", "legend": null },
+        },
+    });
+    write_spec(out, &spec)
+}
@@ -0,0 +1,232 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! The results as XML documents valid against the schema in
+//! `schema/report.xsd`: the reported metrics, the translations with their
+//! values and the summary statistics.
+
+use crate::encode::markup::escape;
+use crate::stats::Stats;
+use crate::stats::Summary;
+use crate::Baseline;
+use crate::Metric;
+use crate::Report;
+use std::io::Write;
+
+/// `value` in the lexical space of `xs:double`.
+fn double(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "INF" } else { "-INF" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// `value` as an integer if `metric` is a count.
+fn number(metric: Metric, value: f64) -> String {
+    if metric.is_count() && value.is_finite() {
+        (value as i64).to_string()
+    } else {
+        double(value)
+    }
+}
+
+/// The `--baseline` syntax of `baseline`.
+fn baseline_name(baseline: &Baseline) -> String {
+    match baseline {
+        Baseline::Median => "median".to_string(),
+        Baseline::Mean => "mean".to_string(),
+        Baseline::Utf8 => "utf8".to_string(),
+        Baseline::Lang(code) => format!("lang:{}", code),
+    }
+}
+
+fn write_stats<W: Write>(
+    out: &mut W,
+    indent: &str,
+    metric: Metric,
+    stats: &Stats,
+) -> std::io::Result<()> {
+    write!(
+        out,
+        "{}<statistics metric=\"{}\" min=\"{}\" q1=\"{}\" median=\"{}\" q3=\"{}\" mean=\"{}\" max-ignoring-outliers=\"{}\" max=\"{}\" std-dev=\"{}\" cv=\"{}\"",
+        indent,
+        escape(&metric.name()),
+        number(metric, stats.min),
+        number(metric, stats.q1),
+        number(metric, stats.median),
+        number(metric, stats.q3),
+        number(metric, stats.mean),
+        number(metric, stats.max_ignoring_outliers),
+        number(metric, stats.max),
+        double(stats.std_dev),
+        double(stats.cv),
+    )?;
+    if let (Some(mean), Some(median)) = (stats.weighted_mean, stats.weighted_median) {
+        write!(
+            out,
+            " weighted-mean=\"{}\" weighted-median=\"{}\"",
+            number(metric, mean),
+            number(metric, median)
+        )?;
+    }
+    if stats.percentiles.is_empty() && stats.outliers.is_empty() {
+        return writeln!(out, "/>");
+    }
+    writeln!(out, ">")?;
+    for &(p, value) in stats.percentiles.iter() {
+        writeln!(
+            out,
+            "{}  <percentile p=\"{}\" value=\"{}\"/>",
+            indent,
+            p,
+            number(metric, value)
+        )?;
+    }
+    for outlier in stats.outliers.iter() {
+        writeln!(out, "{}  <outlier>{}</outlier>", indent, escape(outlier))?;
+    }
+    writeln!(out, "{}</statistics>", indent)
+}
+
+fn write_summary<W: Write>(
+    out: &mut W,
+    indent: &str,
+    report: &Report,
+    summary: &Summary,
+) -> std::io::Result<()> {
+    let inner = format!("{}  ", indent);
+    for &metric in report.columns.iter() {
+        if let Some(stats) = summary.get(metric) {
+            write_stats(out, &inner, metric, stats)?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the `report` element of `report`, with an `article` attribute if
+/// it is of an article.
+fn write_report<W: Write>(
+    out: &mut W,
+    indent: &str,
+    report: &Report,
+    article: Option<u32>,
+) -> std::io::Result<()> {
+    write!(out, "{}<report", indent)?;
+    if let Some(number) = article {
+        write!(out, " article=\"{}\"", number)?;
+    }
+    writeln!(
+        out,
+        " baseline=\"{}\">",
+        escape(&baseline_name(&report.baseline))
+    )?;
+
+    writeln!(out, "{}  <columns>", indent)?;
+    for metric in report.columns.iter() {
+        writeln!(
+            out,
+            "{}    <column name=\"{}\" label=\"{}\"/>",
+            indent,
+            escape(&metric.name()),
+            escape(&metric.label())
+        )?;
+    }
+    writeln!(out, "{}  </columns>", indent)?;
+
+    writeln!(out, "{}  <languages>", indent)?;
+    for lang in report.langs.iter() {
+        write!(
+            out,
+            "{}    <language name=\"{}\"",
+            indent,
+            escape(&lang.name)
+        )?;
+        if let Some(code) = &lang.code {
+            write!(out, " code=\"{}\"", escape(code))?;
+        }
+        if let Some(script) = &lang.script {
+            write!(out, " script=\"{}\"", escape(script))?;
+        }
+        if let Some(stage) = lang.stage {
+            write!(out, " stage=\"{}\"", stage)?;
+        }
+        if let Some(encoding) = lang.smallest_encoding {
+            write!(out, " smallest-encoding=\"{}\"", escape(encoding))?;
+        }
+        if report.has_direction() {
+            if let Some(bidi) = lang.metrics.bidi {
+                write!(out, " direction=\"{}\"", bidi.direction())?;
+            }
+        }
+        writeln!(out, ">")?;
+        for &metric in report.columns.iter() {
+            if let Some(value) = metric.value(&lang.metrics) {
+                writeln!(
+                    out,
+                    "{}      <metric name=\"{}\">{}</metric>",
+                    indent,
+                    escape(&metric.name()),
+                    number(metric, value)
+                )?;
+            }
+        }
+        writeln!(out, "{}    </language>", indent)?;
+    }
+    writeln!(out, "{}  </languages>", indent)?;
+
+    writeln!(out, "{}  <summary>", indent)?;
+    write_summary(out, &format!("{}  ", indent), report, &report.summary)?;
+    writeln!(out, "{}  </summary>", indent)?;
+
+    if !report.groups.is_empty() {
+        writeln!(out, "{}  <groups>", indent)?;
+        for group in report.groups.iter() {
+            writeln!(
+                out,
+                "{}    <group script=\"{}\" count=\"{}\">",
+                indent,
+                escape(&group.script),
+                group.count
+            )?;
+            write_summary(out, &format!("{}    ", indent), report, &group.summary)?;
+            writeln!(out, "{}    </group>", indent)?;
+        }
+        writeln!(out, "{}  </groups>", indent)?;
+    }
+
+    if !report.encoding_wins.is_empty() {
+        writeln!(out, "{}  <encoding-wins>", indent)?;
+        for wins in report.encoding_wins.iter() {
+            writeln!(
+                out,
+                "{}    <encoding name=\"{}\" count=\"{}\"/>",
+                indent,
+                escape(wins.encoding),
+                wins.count
+            )?;
+        }
+        writeln!(out, "{}  </encoding-wins>", indent)?;
+    }
+    writeln!(out, "{}</report>", indent)
+}
+
+/// Writes a document whose root is the `report` element.
+pub fn write<W: Write>(out: &mut W, report: &Report) -> std::io::Result<()> {
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    write_report(out, "", report, None)
+}
+
+/// Writes a document whose root is an `articles` element with a `report`
+/// element for each article.
+pub fn write_articles<W: Write>(out: &mut W, articles: &[(u32, Report)]) -> std::io::Result<()> {
+    writeln!(out, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+    writeln!(out, "<articles>")?;
+    for (number, report) in articles {
+        write_report(out, "  ", report, Some(*number))?;
+    }
+    writeln!(out, "</articles>")
+}
@@ -0,0 +1,92 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Speaker populations for weighting the summary statistics.
+
+use crate::Lang;
+use crate::ParseError;
+use crate::UdhrError;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Speaker populations keyed by UDHR translation code (e.g. `cmn_hans`) or
+/// language code (e.g. `cmn`).
+#[derive(Debug, Clone, Default)]
+pub struct Populations(HashMap<String, f64>);
+
+impl Populations {
+    /// Reads a JSON object mapping codes to populations if `path` ends in
+    /// `.json` and CSV lines of `code,population` otherwise. A first CSV
+    /// line whose population isn't a number is skipped as a header.
+    pub fn load(path: &Path) -> Result<Populations, UdhrError> {
+        let text = std::fs::read_to_string(path).map_err(UdhrError::io(path.display()))?;
+        if path.extension().is_some_and(|e| e == "json") {
+            let map: HashMap<String, f64> =
+                serde_json::from_str(&text).map_err(UdhrError::json(path.display()))?;
+            for (code, &population) in map.iter() {
+                if !is_valid(population) {
+                    // The position of the key, which is good enough for
+                    // pointing at the entry.
+                    let position = text.find(&format!("\"{}\"", code)).unwrap_or(0);
+                    return Err(UdhrError::parse(path.display())(ParseError::new(
+                        position,
+                        format!("invalid population '{}' for '{}'", population, code),
+                    )));
+                }
+            }
+            return Ok(Populations(map));
+        }
+        parse_csv(&text)
+            .map(Populations)
+            .map_err(UdhrError::parse(path.display()))
+    }
+
+    /// The population of `lang`, looked up by its code and then by its
+    /// code up to the first `_`.
+    pub fn get(&self, lang: &Lang) -> Option<f64> {
+        let code = lang.code.as_deref()?;
+        self.0
+            .get(code)
+            .or_else(|| {
+                let language = code.split('_').next()?;
+                self.0.get(language)
+            })
+            .copied()
+    }
+}
+
+/// Whether `population` is a finite non-negative number.
+fn is_valid(population: f64) -> bool {
+    population.is_finite() && population >= 0.0
+}
+
+fn parse_csv(text: &str) -> Result<HashMap<String, f64>, ParseError> {
+    let mut map = HashMap::new();
+    let mut position = 0;
+    for (i, line) in text.lines().enumerate() {
+        let start = position;
+        position += line.len() + 1;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (code, population) = match line.split_once(',') {
+            Some((code, population)) => (code.trim(), population.trim()),
+            None => return Err(ParseError::new(start, "expected code,population")),
+        };
+        match population.parse::<f64>() {
+            Ok(population) if is_valid(population) => {
+                map.insert(code.to_string(), population);
+            }
+            Err(_) if i == 0 => {} // Header
+            _ => {
+                return Err(ParseError::new(
+                    start,
+                    format!("invalid population '{}'", population),
+                ))
+            }
+        }
+    }
+    Ok(map)
+}
@@ -0,0 +1,114 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Python bindings, built with the `python` feature as the extension module
+//! `udhrlen`.
+
+use crate::CountOptions;
+use crate::Metrics;
+use crate::NormalizationForm;
+use crate::UdhrError;
+use pyo3::exceptions::PyOSError;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::types::PyList;
+use pyo3::IntoPyObjectExt;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+fn to_python_error(error: UdhrError) -> PyErr {
+    match error {
+        UdhrError::Io { .. } => PyOSError::new_err(error.to_string()),
+        _ => PyValueError::new_err(error.to_string()),
+    }
+}
+
+/// Converts the JSON form of a value to the corresponding Python objects.
+fn to_python<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        Value::Null => Ok(py.None().into_bound(py)),
+        Value::Bool(b) => b.into_bound_py_any(py),
+        Value::Number(n) => match n.as_u64() {
+            Some(n) => n.into_bound_py_any(py),
+            None => match n.as_i64() {
+                Some(n) => n.into_bound_py_any(py),
+                None => n.as_f64().unwrap_or(f64::NAN).into_bound_py_any(py),
+            },
+        },
+        Value::String(s) => s.into_bound_py_any(py),
+        Value::Array(values) => {
+            let list = PyList::empty(py);
+            for value in values {
+                list.append(to_python(py, value)?)?;
+            }
+            list.into_bound_py_any(py)
+        }
+        Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+            dict.into_bound_py_any(py)
+        }
+    }
+}
+
+fn to_dict<'py>(py: Python<'py>, value: &impl Serialize) -> PyResult<Bound<'py, PyAny>> {
+    let value = serde_json::to_value(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    to_python(py, &value)
+}
+
+/// Measures `text`, after normalizing it to NFC unless `normalize` is
+/// false, and returns the base metrics as a dict.
+#[pyfunction]
+#[pyo3(signature = (text, normalize = true))]
+fn count_text<'py>(py: Python<'py>, text: &str, normalize: bool) -> PyResult<Bound<'py, PyAny>> {
+    let metrics = if normalize {
+        Metrics::new(&NormalizationForm::Nfc.normalize(text))
+    } else {
+        Metrics::new(text)
+    };
+    to_dict(py, &metrics)
+}
+
+/// Measures the corpus directory or zip archive at `path` and returns a
+/// list of a dict per translation with its name, code, script and stage
+/// together with its metrics, ready for `pandas.DataFrame`. The
+/// translations that can't be read or parsed are skipped unless `strict`
+/// is true.
+#[pyfunction]
+#[pyo3(signature = (path, strict = false))]
+fn count_udhr<'py>(py: Python<'py>, path: PathBuf, strict: bool) -> PyResult<Bound<'py, PyList>> {
+    let options = CountOptions {
+        strict,
+        ..CountOptions::default()
+    };
+    let counts = crate::count_corpus(&path, &options).map_err(to_python_error)?;
+    let list = PyList::empty(py);
+    for lang in counts.langs.iter() {
+        let dict = PyDict::new(py);
+        dict.set_item("name", &lang.name)?;
+        dict.set_item("code", &lang.code)?;
+        dict.set_item("script", &lang.script)?;
+        dict.set_item("stage", lang.stage)?;
+        if let Value::Object(metrics) =
+            serde_json::to_value(&lang.metrics).map_err(|e| PyValueError::new_err(e.to_string()))?
+        {
+            for (key, value) in metrics.iter() {
+                dict.set_item(key, to_python(py, value)?)?;
+            }
+        }
+        list.append(dict)?;
+    }
+    Ok(list)
+}
+
+#[pymodule]
+fn udhrlen(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(count_text, module)?)?;
+    module.add_function(wrap_pyfunction!(count_udhr, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Serving the report and the metrics of the translations over HTTP.
+//!
+//! * `/` is the HTML report, sorted by the `sort` query parameter, e.g.
+//!   `/?sort=utf16&desc`
+//! * `/api/langs` is the JSON array of the translations
+//! * `/api/lang/<code>` is the JSON of one translation
+
+use crate::output;
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use crate::SortKey;
+use crate::UdhrError;
+use tiny_http::Header;
+use tiny_http::Method;
+use tiny_http::Response;
+use tiny_http::Server;
+
+type Reply = (u16, &'static str, Vec<u8>);
+
+/// The measured translations being served.
+pub struct Site {
+    langs: Vec<Lang>,
+    /// The columns of the report
+    columns: Vec<Metric>,
+    /// Everything that was measured, which the report can be sorted by
+    measured: Vec<Metric>,
+}
+
+impl Site {
+    pub fn new(langs: Vec<Lang>, columns: Vec<Metric>, measured: Vec<Metric>) -> Site {
+        Site {
+            langs,
+            columns,
+            measured,
+        }
+    }
+
+    /// Answers to `GET` on `url` with the status, the content type and the
+    /// body.
+    fn get(&self, url: &str) -> Reply {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        if path == "/" {
+            return self.report(query);
+        }
+        if path == "/api/langs" {
+            return json(&self.langs);
+        }
+        if let Some(code) = path.strip_prefix("/api/lang/") {
+            if let Some(lang) = self.langs.iter().find(|l| l.code.as_deref() == Some(code)) {
+                return json(lang);
+            }
+            return error(404, format!("no translation with code '{}'", code));
+        }
+        error(404, format!("{} not found", path))
+    }
+
+    fn report(&self, query: &str) -> Reply {
+        let mut sort = None;
+        let mut descending = false;
+        for (key, value) in query
+            .split('&')
+            .filter(|p| !p.is_empty())
+            .map(|p| p.split_once('=').unwrap_or((p, "")))
+        {
+            match key {
+                "sort" => match value.parse::<SortKey>() {
+                    Ok(SortKey::Metric(metric)) if !metric.is_measured_in(&self.measured) => {
+                        return error(400, format!("{} is not measured", metric.name()))
+                    }
+                    Ok(key) => sort = Some(key),
+                    Err(message) => return error(400, message),
+                },
+                "desc" => descending = true,
+                _ => {}
+            }
+        }
+        let mut report = Report::new(self.langs.clone(), self.columns.clone());
+        if sort.is_some() || descending {
+            report.sort(sort.unwrap_or(SortKey::Metric(Metric::Utf8)), descending);
+        }
+        let mut body = Vec::new();
+        let document = output::html::Document {
+            description: Some("Measured when the server started.".to_string()),
+            ..output::html::Document::default()
+        };
+        match output::html::write_document(&mut body, &document, |out| {
+            output::html::write(out, &report, &document.style)
+        }) {
+            Ok(()) => (200, "text/html; charset=utf-8", body),
+            Err(e) => error(500, e.to_string()),
+        }
+    }
+}
+
+fn json(value: &impl serde::Serialize) -> Reply {
+    match serde_json::to_vec_pretty(value) {
+        Ok(body) => (200, "application/json", body),
+        Err(e) => error(500, e.to_string()),
+    }
+}
+
+fn error(status: u16, message: String) -> Reply {
+    (status, "text/plain; charset=utf-8", message.into_bytes())
+}
+
+/// Serves `site` on `address`, e.g. `127.0.0.1:8080`, until the process is
+/// terminated.
+pub fn serve(address: &str, site: &Site) -> Result<(), UdhrError> {
+    let server = Server::http(address).map_err(|source| UdhrError::Serve {
+        address: address.to_string(),
+        source,
+    })?;
+    for request in server.incoming_requests() {
+        let (status, content_type, body) = match request.method() {
+            Method::Get | Method::Head => site.get(request.url()),
+            _ => error(405, "only GET is supported".to_string()),
+        };
+        let header = Header::from_bytes(&b"Content-Type"[..], content_type.as_bytes())
+            .expect("valid header");
+        let response = Response::from_data(body)
+            .with_status_code(status)
+            .with_header(header);
+        // A client that went away doesn't concern the other clients.
+        let _ = request.respond(response);
+    }
+    Ok(())
+}
@@ -0,0 +1,383 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Summary statistics over the translations.
+
+use crate::population::Populations;
+use crate::Lang;
+use crate::Metric;
+use schemars::json_schema;
+use schemars::JsonSchema;
+use schemars::Schema;
+use schemars::SchemaGenerator;
+use serde::ser::SerializeMap;
+use serde::Serialize;
+use serde::Serializer;
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// How to decide which values are outliers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutlierMethod {
+    /// Outside Tukey's fences at 1.5 times the interquartile range beyond
+    /// the quartiles
+    #[default]
+    Iqr,
+    /// More than three standard deviations from the mean
+    ZScore,
+    /// Just the largest value
+    Max,
+}
+
+impl FromStr for OutlierMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<OutlierMethod, String> {
+        match s.trim() {
+            "iqr" => Ok(OutlierMethod::Iqr),
+            "zscore" => Ok(OutlierMethod::ZScore),
+            "max" => Ok(OutlierMethod::Max),
+            other => Err(format!(
+                "unknown outlier method '{}', expected iqr, zscore or max",
+                other
+            )),
+        }
+    }
+}
+
+/// Settings for computing the statistics.
+#[derive(Debug, Clone, Default)]
+pub struct StatsOptions {
+    /// Percentiles to compute in addition to the quartiles
+    pub percentiles: Vec<f64>,
+    pub outlier_method: OutlierMethod,
+    /// Speaker populations to weight the translations by
+    pub populations: Option<Arc<Populations>>,
+}
+
+/// The statistics of a single metric across the translations.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    pub min: f64,
+    /// The first quartile
+    pub q1: f64,
+    pub median: f64,
+    /// The third quartile
+    pub q3: f64,
+    /// Rounded down for counts
+    pub mean: f64,
+    /// The largest value that isn't an outlier
+    pub max_ignoring_outliers: f64,
+    pub max: f64,
+    /// The codes, or failing that the names, of the translations whose
+    /// values are outliers
+    pub outliers: Vec<String>,
+    /// The population standard deviation
+    pub std_dev: f64,
+    /// The coefficient of variation, i.e. the standard deviation relative
+    /// to the mean
+    pub cv: f64,
+    /// The requested percentiles and their values
+    pub percentiles: Vec<(f64, f64)>,
+    /// The mean weighted by speaker population, over the translations whose
+    /// population is known
+    pub weighted_mean: Option<f64>,
+    /// The value that half of the speakers are at or below
+    pub weighted_median: Option<f64>,
+}
+
+/// The `p`th percentile of the sorted `values`: the value at index
+/// ⌊p/100·n⌋, the upper of the two candidates like the median, rather than
+/// the nearest rank ⌈p/100·n⌉ − 1.
+fn percentile(values: &[f64], p: f64) -> f64 {
+    let rank = (p / 100.0 * values.len() as f64) as usize;
+    values[rank.min(values.len() - 1)]
+}
+
+/// The weighted mean and median of the sorted `weighted` values, if their
+/// total weight is positive.
+fn weighted(weighted: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let total: f64 = weighted.iter().map(|&(_, w)| w).sum();
+    if total <= 0.0 {
+        return None;
+    }
+    let mean = weighted.iter().map(|&(v, w)| v * w).sum::<f64>() / total;
+    let mut cumulative = 0.0;
+    let median = weighted
+        .iter()
+        .find(|&&(_, w)| {
+            cumulative += w;
+            cumulative >= total / 2.0
+        })
+        .map_or(mean, |&(v, _)| v);
+    Some((mean, median))
+}
+
+impl Stats {
+    /// The statistics of `metric` over the translations that have it.
+    pub fn new(langs: &[Lang], metric: Metric, options: &StatsOptions) -> Stats {
+        let mut measured: Vec<(f64, &Lang)> = langs
+            .iter()
+            .filter_map(|l| Some((metric.value(&l.metrics)?, l)))
+            .collect();
+        if measured.is_empty() {
+            return Stats::default();
+        }
+        measured.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let values: Vec<f64> = measured.iter().map(|&(v, _)| v).collect();
+        let len = values.len();
+        let exact_mean = values.iter().sum::<f64>() / len as f64;
+        let variance = values
+            .iter()
+            .map(|v| (v - exact_mean) * (v - exact_mean))
+            .sum::<f64>()
+            / len as f64;
+        let std_dev = variance.sqrt();
+        let (q1, q3) = (percentile(&values, 25.0), percentile(&values, 75.0));
+        let is_outlier = |i: usize, v: f64| match options.outlier_method {
+            OutlierMethod::Iqr => {
+                let fence = 1.5 * (q3 - q1);
+                v < q1 - fence || v > q3 + fence
+            }
+            OutlierMethod::ZScore => (v - exact_mean).abs() > 3.0 * std_dev,
+            OutlierMethod::Max => i == len - 1,
+        };
+        let mut outliers = Vec::new();
+        let mut max_ignoring_outliers = values[0];
+        for (i, &(v, lang)) in measured.iter().enumerate() {
+            if is_outlier(i, v) {
+                outliers.push(lang.code.clone().unwrap_or_else(|| lang.name.clone()));
+            } else {
+                max_ignoring_outliers = v;
+            }
+        }
+        let weighted = options.populations.as_ref().and_then(|populations| {
+            let values: Vec<(f64, f64)> = measured
+                .iter()
+                .filter_map(|&(v, lang)| Some((v, populations.get(lang)?)))
+                .collect();
+            weighted(&values)
+        });
+        Stats {
+            min: values[0],
+            q1,
+            median: values[len / 2],
+            q3,
+            mean: if metric.is_count() {
+                exact_mean.floor()
+            } else {
+                exact_mean
+            },
+            max_ignoring_outliers,
+            max: values[len - 1],
+            outliers,
+            std_dev,
+            cv: std_dev / exact_mean,
+            percentiles: options
+                .percentiles
+                .iter()
+                .map(|&p| (p, percentile(&values, p)))
+                .collect(),
+            weighted_mean: weighted.map(|(mean, _)| {
+                if metric.is_count() {
+                    mean.floor()
+                } else {
+                    mean
+                }
+            }),
+            weighted_median: weighted.map(|(_, median)| median),
+        }
+    }
+}
+
+/// The statistics of every reported metric.
+#[derive(Debug, Clone)]
+pub struct Summary {
+    stats: Vec<(Metric, Stats)>,
+}
+
+impl Summary {
+    pub fn new(langs: &[Lang], columns: &[Metric], options: &StatsOptions) -> Summary {
+        Summary {
+            stats: columns
+                .iter()
+                .map(|&metric| (metric, Stats::new(langs, metric, options)))
+                .collect(),
+        }
+    }
+
+    /// The statistics of `metric`, if it is reported.
+    pub fn get(&self, metric: Metric) -> Option<&Stats> {
+        self.stats
+            .iter()
+            .find(|(m, _)| *m == metric)
+            .map(|(_, stats)| stats)
+    }
+}
+
+/// Serializes the statistics of counts as integers, except for the
+/// measures of spread.
+struct MetricStats<'a>(Metric, &'a Stats);
+
+impl Serialize for MetricStats<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let MetricStats(metric, stats) = *self;
+        let mut map = serializer.serialize_map(None)?;
+        let fields = [
+            ("min", stats.min),
+            ("q1", stats.q1),
+            ("median", stats.median),
+            ("q3", stats.q3),
+            ("mean", stats.mean),
+            ("max_ignoring_outliers", stats.max_ignoring_outliers),
+            ("max", stats.max),
+        ];
+        for &(key, value) in fields.iter() {
+            map.serialize_entry(key, &Value(metric, value))?;
+        }
+        map.serialize_entry("std_dev", &finite(stats.std_dev))?;
+        map.serialize_entry("cv", &finite(stats.cv))?;
+        map.serialize_entry("outliers", &stats.outliers)?;
+        if let (Some(mean), Some(median)) = (stats.weighted_mean, stats.weighted_median) {
+            map.serialize_entry("weighted_mean", &Value(metric, mean))?;
+            map.serialize_entry("weighted_median", &Value(metric, median))?;
+        }
+        if !stats.percentiles.is_empty() {
+            map.serialize_entry("percentiles", &Percentiles(metric, &stats.percentiles))?;
+        }
+        map.end()
+    }
+}
+
+/// `value` unless it is NaN or infinite, which JSON writes as null, so that
+/// YAML writes null too and TOML, which has no null, leaves out the key.
+fn finite(value: f64) -> Option<f64> {
+    Some(value).filter(|value| value.is_finite())
+}
+
+/// Serializes a value of a metric as an integer if it is a count.
+struct Value(Metric, f64);
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if self.0.is_count() {
+            serializer.serialize_i64(self.1 as i64)
+        } else {
+            finite(self.1).serialize(serializer)
+        }
+    }
+}
+
+/// Serializes percentiles as a map from the percentile to the value.
+struct Percentiles<'a>(Metric, &'a [(f64, f64)]);
+
+impl Serialize for Percentiles<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.1.len()))?;
+        for &(p, value) in self.1 {
+            map.serialize_entry(&p.to_string(), &Value(self.0, value))?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for Summary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.stats.len()))?;
+        for (metric, stats) in &self.stats {
+            map.serialize_entry(&metric.name(), &MetricStats(*metric, stats))?;
+        }
+        map.end()
+    }
+}
+
+/// Described by hand to match the serialization, in which the values are
+/// integers for counts and the coefficient of variation is null if the mean
+/// is zero.
+impl JsonSchema for Summary {
+    fn schema_name() -> Cow<'static, str> {
+        "Summary".into()
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "description": "The statistics of each reported metric, by the name of the metric",
+            "type": "object",
+            "additionalProperties": {
+                "type": "object",
+                "properties": {
+                    "min": { "type": "number" },
+                    "q1": { "type": "number", "description": "The first quartile" },
+                    "median": { "type": "number" },
+                    "q3": { "type": "number", "description": "The third quartile" },
+                    "mean": { "type": "number", "description": "Rounded down for counts" },
+                    "max_ignoring_outliers": {
+                        "type": "number",
+                        "description": "The largest value that isn't an outlier"
+                    },
+                    "max": { "type": "number" },
+                    "std_dev": {
+                        "type": "number",
+                        "description": "The population standard deviation"
+                    },
+                    "cv": {
+                        "type": ["number", "null"],
+                        "description": "The coefficient of variation"
+                    },
+                    "outliers": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "The codes, or failing that the names, of the translations whose values are outliers"
+                    },
+                    "weighted_mean": {
+                        "type": "number",
+                        "description": "The mean weighted by speaker population"
+                    },
+                    "weighted_median": {
+                        "type": "number",
+                        "description": "The median weighted by speaker population"
+                    },
+                    "percentiles": {
+                        "type": "object",
+                        "additionalProperties": { "type": "number" },
+                        "description": "The requested percentiles by the percentile"
+                    }
+                },
+                "required": [
+                    "min",
+                    "q1",
+                    "median",
+                    "q3",
+                    "mean",
+                    "max_ignoring_outliers",
+                    "max",
+                    "std_dev",
+                    "cv",
+                    "outliers"
+                ]
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::percentile;
+
+    #[test]
+    fn percentiles() {
+        let values: Vec<f64> = (1..=10).map(f64::from).collect();
+        assert_eq!(percentile(&values, 5.0), 1.0);
+        assert_eq!(percentile(&values, 25.0), 3.0);
+        assert_eq!(percentile(&values, 50.0), 6.0);
+        assert_eq!(percentile(&values, 75.0), 8.0);
+        assert_eq!(percentile(&values, 95.0), 10.0);
+        assert_eq!(percentile(&values, 100.0), 10.0);
+        let values = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(percentile(&values, 25.0), 2.0);
+        assert_eq!(percentile(&values, 75.0), 4.0);
+        assert_eq!(percentile(&[7.0], 5.0), 7.0);
+    }
+}
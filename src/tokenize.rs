@@ -0,0 +1,90 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Token counts with the byte-pair encodings of OpenAI's language models,
+//! which determine what processing a translation with such a model costs.
+//! Counting requires the `tokenizer` feature, which embeds the vocabularies
+//! via `tiktoken-rs`.
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// A byte-pair encoding vocabulary.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum Tokenizer {
+    /// GPT-2 and GPT-3
+    R50kBase,
+    /// Codex
+    P50kBase,
+    /// GPT-3.5 and GPT-4
+    Cl100kBase,
+    /// GPT-4o
+    O200kBase,
+}
+
+impl Tokenizer {
+    pub const ALL: [Tokenizer; 4] = [
+        Tokenizer::R50kBase,
+        Tokenizer::P50kBase,
+        Tokenizer::Cl100kBase,
+        Tokenizer::O200kBase,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tokenizer::R50kBase => "r50k_base",
+            Tokenizer::P50kBase => "p50k_base",
+            Tokenizer::Cl100kBase => "cl100k_base",
+            Tokenizer::O200kBase => "o200k_base",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tokenizer::R50kBase => "r50k",
+            Tokenizer::P50kBase => "p50k",
+            Tokenizer::Cl100kBase => "cl100k",
+            Tokenizer::O200kBase => "o200k",
+        }
+    }
+
+    /// The number of tokens `text` encodes to, treating special tokens as
+    /// ordinary text.
+    #[cfg(feature = "tokenizer")]
+    pub fn count(self, text: &str) -> usize {
+        let bpe = match self {
+            Tokenizer::R50kBase => tiktoken_rs::r50k_base_singleton(),
+            Tokenizer::P50kBase => tiktoken_rs::p50k_base_singleton(),
+            Tokenizer::Cl100kBase => tiktoken_rs::cl100k_base_singleton(),
+            Tokenizer::O200kBase => tiktoken_rs::o200k_base_singleton(),
+        };
+        bpe.encode_ordinary(text).len()
+    }
+}
+
+impl FromStr for Tokenizer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Tokenizer, String> {
+        let name = s.trim().to_ascii_lowercase();
+        let tokenizer = Tokenizer::ALL
+            .iter()
+            .copied()
+            .find(|t| t.name() == name || t.label() == name)
+            .ok_or_else(|| format!("unknown tokenizer '{}'", s.trim()))?;
+        if cfg!(feature = "tokenizer") {
+            Ok(tokenizer)
+        } else {
+            Err(format!(
+                "counting {} tokens requires building with the tokenizer feature",
+                tokenizer.name()
+            ))
+        }
+    }
+}
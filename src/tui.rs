@@ -0,0 +1,376 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Exploring the measured translations interactively in the terminal.
+//!
+//! * ↑ and ↓ (or k and j), PgUp, PgDn, Home and End move in the list
+//! * ← and → (or h and l) choose a column and s sorts by it, the second
+//!   time in descending order
+//! * f and F show only the translations in the next or the previous script
+//! * Enter opens and closes the details of the translation
+//! * q quits, as does Esc when the details aren't open
+
+use crate::output::html::bucket_colors;
+use crate::output::html::deviation_bucket;
+use crate::output::html::Style as TableStyle;
+use crate::Lang;
+use crate::Metric;
+use crate::Report;
+use crate::SortKey;
+use crate::UdhrError;
+use ratatui::crossterm::event;
+use ratatui::crossterm::event::Event;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyEventKind;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::widgets::Block;
+use ratatui::widgets::Cell;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Row;
+use ratatui::widgets::Table;
+use ratatui::widgets::TableState;
+use ratatui::Frame;
+use unicode_width::UnicodeWidthStr;
+
+const HELP: &str = "↑↓ move  ←→ column  s sort  f/F script  Enter details  q quit";
+
+/// The widest that the names get before being cut
+const MAX_NAME_WIDTH: usize = 32;
+
+/// The measured translations being explored and the state of the view.
+pub struct Explorer {
+    langs: Vec<Lang>,
+    /// The columns of the table
+    columns: Vec<Metric>,
+    /// Everything that was measured, which the details show
+    measured: Vec<Metric>,
+    /// The scripts of the translations, in alphabetical order
+    scripts: Vec<String>,
+    /// The script whose translations are shown, if not all
+    script: Option<usize>,
+    /// The chosen column: the name, the metrics and then the script
+    column: usize,
+    /// The column sorted by and whether in descending order, if any
+    sort: Option<(usize, bool)>,
+    /// The shown translations
+    report: Report,
+    table: TableState,
+    details: bool,
+    style: TableStyle,
+}
+
+impl Explorer {
+    pub fn new(langs: Vec<Lang>, columns: Vec<Metric>, measured: Vec<Metric>) -> Explorer {
+        let mut scripts: Vec<String> = langs.iter().filter_map(|l| l.script.clone()).collect();
+        scripts.sort();
+        scripts.dedup();
+        let report = Report::new(langs.clone(), columns.clone());
+        let mut table = TableState::default();
+        if !report.langs.is_empty() {
+            table.select(Some(0));
+        }
+        Explorer {
+            langs,
+            columns,
+            measured,
+            scripts,
+            script: None,
+            column: 0,
+            sort: None,
+            report,
+            table,
+            details: false,
+            style: TableStyle::default(),
+        }
+    }
+
+    /// The key of sorting by `column`.
+    fn sort_key(&self, column: usize) -> SortKey {
+        match column.checked_sub(1).and_then(|i| self.columns.get(i)) {
+            Some(&metric) => SortKey::Metric(metric),
+            None if column == 0 => SortKey::Name,
+            None => SortKey::Script,
+        }
+    }
+
+    fn selected(&self) -> Option<&Lang> {
+        self.report.langs.get(self.table.selected()?)
+    }
+
+    /// Recomputes the shown translations after changing the script or the
+    /// sorting, keeping the selected translation selected if it's shown.
+    fn update(&mut self) {
+        let selected = self.selected().map(|l| (l.name.clone(), l.code.clone()));
+        let script = self.script.map(|i| &self.scripts[i]);
+        let langs = self
+            .langs
+            .iter()
+            .filter(|l| script.is_none() || l.script.as_ref() == script)
+            .cloned()
+            .collect();
+        self.report = Report::new(langs, self.columns.clone());
+        if let Some((column, descending)) = self.sort {
+            self.report.sort(self.sort_key(column), descending);
+        }
+        let index = selected
+            .and_then(|(name, code)| {
+                self.report
+                    .langs
+                    .iter()
+                    .position(|l| l.name == name && l.code == code)
+            })
+            .or_else(|| (!self.report.langs.is_empty()).then_some(0));
+        self.table.select(index);
+    }
+
+    /// Moves the selection by `delta` rows.
+    fn scroll(&mut self, delta: isize) {
+        let last = self.report.langs.len().saturating_sub(1);
+        let index = self.table.selected().unwrap_or(0);
+        self.table
+            .select(Some(index.saturating_add_signed(delta).min(last)));
+    }
+
+    /// Handles the key `code`, returning whether to quit.
+    fn key(&mut self, code: KeyCode, page: isize) -> bool {
+        let last_column = self.columns.len() + 1;
+        match code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Esc if !self.details => return true,
+            KeyCode::Esc => self.details = false,
+            KeyCode::Enter => self.details = !self.details,
+            KeyCode::Up | KeyCode::Char('k') => self.scroll(-1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll(1),
+            KeyCode::PageUp => self.scroll(-page),
+            KeyCode::PageDown => self.scroll(page),
+            KeyCode::Home => self.scroll(isize::MIN),
+            KeyCode::End => self.scroll(isize::MAX),
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => self.column = (self.column + 1).min(last_column),
+            KeyCode::Char('s') => {
+                let descending = self.sort == Some((self.column, false));
+                self.sort = Some((self.column, descending));
+                self.update();
+            }
+            KeyCode::Char('f') => {
+                self.script = match self.script {
+                    None if !self.scripts.is_empty() => Some(0),
+                    Some(i) if i + 1 < self.scripts.len() => Some(i + 1),
+                    _ => None,
+                };
+                self.update();
+            }
+            KeyCode::Char('F') => {
+                self.script = match self.script {
+                    None => self.scripts.len().checked_sub(1),
+                    Some(i) => i.checked_sub(1),
+                };
+                self.update();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn status(&self) -> String {
+        let script = self.script.map_or("all", |i| &self.scripts[i]);
+        let sort = match self.sort {
+            Some((column, descending)) => {
+                let order = if descending {
+                    "descending"
+                } else {
+                    "ascending"
+                };
+                format!("{}, {}", self.column_label(column), order)
+            }
+            None => "UTF-8".to_string(),
+        };
+        format!(
+            "{} translations · script: {} · sorted by {}",
+            self.report.langs.len(),
+            script,
+            sort
+        )
+    }
+
+    fn column_label(&self, column: usize) -> String {
+        match self.sort_key(column) {
+            SortKey::Name => "Name".to_string(),
+            SortKey::Metric(metric) => metric.label(),
+            SortKey::Script => "Script".to_string(),
+        }
+    }
+
+    fn header(&self) -> Row<'static> {
+        let cells = (0..self.columns.len() + 2).map(|column| {
+            let mut label = self.column_label(column);
+            match self.sort {
+                Some((sorted, false)) if sorted == column => label.push_str(" ▲"),
+                Some((sorted, true)) if sorted == column => label.push_str(" ▼"),
+                _ => {}
+            }
+            let mut style = Style::default().add_modifier(Modifier::BOLD);
+            if column == self.column {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+            Cell::from(label).style(style)
+        });
+        Row::new(cells)
+    }
+
+    /// The cell of `metric` of `lang`, colored by its deviation from the
+    /// baseline like in the HTML table.
+    fn cell(&self, lang: &Lang, metric: Metric) -> Cell<'static> {
+        let value = match metric.value(&lang.metrics) {
+            Some(value) => value,
+            None => return Cell::from(""),
+        };
+        let text = Line::from(self.style.numbers.metric(metric, value)).right_aligned();
+        let baseline = self
+            .report
+            .baseline_value(&|m| m.value(&lang.metrics), metric)
+            .filter(|&b| b > 0.0);
+        let colors = baseline.and_then(|baseline| {
+            let (above, bucket) = deviation_bucket(baseline, value, &self.style.coloring);
+            bucket_colors(above, bucket, &self.style.coloring)
+        });
+        match colors {
+            Some((background, text_color)) => {
+                let (r, g, b) = background.rgb();
+                let foreground = if text_color == "#fff" {
+                    Color::White
+                } else {
+                    Color::Black
+                };
+                Cell::from(text).style(Style::default().bg(Color::Rgb(r, g, b)).fg(foreground))
+            }
+            None => Cell::from(text),
+        }
+    }
+
+    fn draw_table(&mut self, frame: &mut Frame, area: Rect) {
+        let name_width = self
+            .report
+            .langs
+            .iter()
+            .map(|l| l.name.width())
+            .max()
+            .unwrap_or(0)
+            .clamp(4, MAX_NAME_WIDTH);
+        let mut widths = vec![Constraint::Length(name_width as u16)];
+        for &metric in self.columns.iter() {
+            let values = self.report.langs.iter().filter_map(|l| {
+                let value = metric.value(&l.metrics)?;
+                Some(self.style.numbers.metric(metric, value).width())
+            });
+            // Room for the sort arrow
+            let width = values.max().unwrap_or(0).max(metric.label().width() + 2);
+            widths.push(Constraint::Length(width as u16));
+        }
+        widths.push(Constraint::Min(6));
+        let rows: Vec<Row> = self
+            .report
+            .langs
+            .iter()
+            .map(|lang| {
+                let mut cells = vec![Cell::from(lang.name.clone())];
+                cells.extend(self.columns.iter().map(|&metric| self.cell(lang, metric)));
+                cells.push(Cell::from(lang.script.clone().unwrap_or_default()));
+                Row::new(cells)
+            })
+            .collect();
+        let table = Table::new(rows, widths)
+            .header(self.header())
+            .row_highlight_style(Style::default().add_modifier(Modifier::BOLD))
+            .highlight_symbol("▶ ");
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+
+    fn draw_details(&self, frame: &mut Frame, area: Rect) {
+        let lang = match self.selected() {
+            Some(lang) => lang,
+            None => return,
+        };
+        let numbers = self.style.numbers;
+        let mut lines = vec![
+            format!("Code: {}", lang.code.as_deref().unwrap_or("")),
+            format!("Script: {}", lang.script.as_deref().unwrap_or("")),
+            format!(
+                "Stage: {}",
+                lang.stage.map_or(String::new(), |s| s.to_string())
+            ),
+            String::new(),
+        ];
+        for &metric in self.measured.iter() {
+            let value = match metric.value(&lang.metrics) {
+                Some(value) => value,
+                None => continue,
+            };
+            let mut line = format!("{}: {}", metric.label(), numbers.metric(metric, value));
+            let baseline = self
+                .report
+                .baseline_value(&|m| m.value(&lang.metrics), metric)
+                .filter(|&b| b > 0.0);
+            if let Some(baseline) = baseline {
+                let deviation = (value - baseline) / baseline * 100.0;
+                line.push_str(&format!(" ({}%)", numbers.format(deviation, 1)));
+            }
+            lines.push(line);
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "Changed by NFC: {} code points",
+            numbers.format(lang.metrics.non_nfc as f64, 0)
+        ));
+        let lines: Vec<Line> = lines.into_iter().map(Line::from).collect();
+        let details = Paragraph::new(lines).block(Block::bordered().title(lang.name.clone()));
+        frame.render_widget(details, area);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [status, body, help] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        frame.render_widget(Paragraph::new(self.status()), status);
+        if self.details {
+            let [table, details] =
+                Layout::horizontal([Constraint::Min(0), Constraint::Length(40)]).areas(body);
+            self.draw_table(frame, table);
+            self.draw_details(frame, details);
+        } else {
+            self.draw_table(frame, body);
+        }
+        let help_style = Style::default().add_modifier(Modifier::DIM);
+        frame.render_widget(Paragraph::new(HELP).style(help_style), help);
+    }
+}
+
+/// Explores `explorer` in the terminal until the user quits.
+pub fn explore(explorer: &mut Explorer) -> Result<(), UdhrError> {
+    let mut terminal = ratatui::try_init().map_err(UdhrError::io("the terminal"))?;
+    let result = (|| loop {
+        let mut page = 1;
+        terminal.draw(|frame| {
+            // The rows of the table that fit
+            page = frame.area().height.saturating_sub(3).max(1) as isize;
+            explorer.draw(frame);
+        })?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && explorer.key(key.code, page) {
+                return Ok(());
+            }
+        }
+    })();
+    let restored = ratatui::try_restore();
+    result.and(restored).map_err(UdhrError::io("the terminal"))
+}